@@ -0,0 +1,7 @@
+//! The language server for the tree files working over stdio.
+fn main() {
+    if let Err(e) = forester_rs::lsp::run_stdio() {
+        eprintln!("{:?}", e);
+        std::process::exit(1);
+    }
+}
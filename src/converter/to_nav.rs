@@ -176,7 +176,7 @@ impl<'a> ToRosNavConverter<'a> {
 
 
 fn handle_attrs(attrs: Vec<RtArgument>, e: &mut BytesStart) -> RtOk {
-    for RtArgument { name, value } in attrs {
+    for RtArgument { name, value, .. } in attrs {
        match value {
             RtValue::Pointer(v) => {
                 e.push_attribute((name.as_str(), format!(r#"{{{}}}"#, v.to_string()).as_str()));
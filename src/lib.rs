@@ -17,6 +17,8 @@ pub mod tracer;
 pub mod tree;
 pub mod visualizer;
 pub mod converter;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 
 use crate::runtime::RuntimeError;
 use crate::tree::project::Project;
@@ -54,6 +56,7 @@ pub fn runtime_tree_default(
             new_output
         }
     };
+    project.typecheck()?;
     let rt = RuntimeTree::build(project)?;
     Ok((rt, output_pb))
 }
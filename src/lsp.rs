@@ -0,0 +1,361 @@
+//! The language server for the tree files.
+//!
+//! The server works over stdio and provides the following features:
+//!  - diagnostics for the syntax errors, imports and the errors of the type checking
+//!  - go to definition across the imports including the aliases and the standard files
+//!  - hover with the parameters of the definition
+//!  - completion of the tree names and the parameters
+//!  - find references
+//!
+//! # Example
+//! ```no_run
+//! forester_rs::lsp::run_stdio().unwrap();
+//! ```
+pub mod index;
+
+use crate::lsp::index::{Index, Workspace};
+use crate::runtime::{RtResult, RuntimeError};
+use crate::tree::diagnostic::{LineCol, Span};
+use crate::tree::project::FileName;
+use crate::tree::TreeError;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, References, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionParams, CompletionResponse, DiagnosticSeverity,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, InitializeParams, Location, MarkupContent, MarkupKind, OneOf,
+    Position, PublishDiagnosticsParams, Range, ReferenceParams, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// runs the server over stdin and stdout until the client asks to shut it down
+pub fn run_stdio() -> RtResult<()> {
+    let (connection, io_threads) = Connection::stdio();
+    run(connection)?;
+    io_threads.join()?;
+    Ok(())
+}
+
+/// runs the server over the given connection until the client asks to shut it down
+pub fn run(connection: Connection) -> RtResult<()> {
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(Default::default()),
+        references_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    })?;
+    let params: InitializeParams = serde_json::from_value(connection.initialize(capabilities)?)?;
+    let mut server = Server::new(root(&params));
+    debug!(target:"lsp", "initialized with the root {:?}", server.workspace.root);
+
+    for msg in &connection.receiver {
+        match msg {
+            Message::Request(req) => {
+                if connection.handle_shutdown(&req)? {
+                    return Ok(());
+                }
+                let resp = server.request(req);
+                send(&connection, Message::Response(resp))?;
+            }
+            Message::Notification(n) => {
+                for n in server.notification(n) {
+                    send(&connection, Message::Notification(n))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn send(connection: &Connection, msg: Message) -> RtResult<()> {
+    connection
+        .sender
+        .send(msg)
+        .map_err(|e| RuntimeError::IOError(format!("{e}")))
+}
+
+#[allow(deprecated)]
+fn root(params: &InitializeParams) -> PathBuf {
+    params
+        .root_uri
+        .as_ref()
+        .or(params
+            .workspace_folders
+            .as_ref()
+            .and_then(|fs| fs.first())
+            .map(|f| &f.uri))
+        .and_then(|uri| uri.to_file_path().ok())
+        .or(std::env::current_dir().ok())
+        .unwrap_or_default()
+}
+
+struct Server {
+    workspace: Workspace,
+}
+
+impl Server {
+    fn new(root: PathBuf) -> Self {
+        Server {
+            workspace: Workspace::new(root),
+        }
+    }
+
+    fn file_name(&self, uri: &Url) -> FileName {
+        self.workspace
+            .file_name(&uri.to_file_path().unwrap_or(PathBuf::from(uri.path())))
+    }
+    fn uri(&self, file: &FileName) -> Option<Url> {
+        Url::from_file_path(self.workspace.path(file)).ok()
+    }
+
+    fn request(&mut self, req: Request) -> Response {
+        let id = req.id.clone();
+        let result = match req.method.as_str() {
+            GotoDefinition::METHOD => handle(req, |p| self.definition(p)),
+            HoverRequest::METHOD => handle(req, |p| self.hover(p)),
+            Completion::METHOD => handle(req, |p| self.completion(p)),
+            References::METHOD => handle(req, |p| self.references(p)),
+            m => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("the method {m} is not supported"),
+                )
+            }
+        };
+        match result {
+            Ok(v) => Response::new_ok(id, v),
+            Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, format!("{e:?}")),
+        }
+    }
+
+    /// updates the documents and returns the diagnostics for the opened documents
+    fn notification(&mut self, n: Notification) -> Vec<Notification> {
+        match n.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                if let Ok(p) = params::<lsp_types::DidOpenTextDocumentParams>(n.params) {
+                    let file = self.file_name(&p.text_document.uri);
+                    self.workspace.open(file, p.text_document.text);
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                if let Ok(mut p) = params::<lsp_types::DidChangeTextDocumentParams>(n.params) {
+                    let file = self.file_name(&p.text_document.uri);
+                    if let Some(change) = p.content_changes.pop() {
+                        self.workspace.open(file, change.text);
+                    }
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                if let Ok(p) = params::<lsp_types::DidCloseTextDocumentParams>(n.params) {
+                    let file = self.file_name(&p.text_document.uri);
+                    self.workspace.close(&file);
+                    return self.publish(&file, vec![]).into_iter().collect();
+                }
+            }
+            DidSaveTextDocument::METHOD => self.workspace.invalidate(),
+            _ => return vec![],
+        }
+        self.diagnostics()
+    }
+
+    fn diagnostics(&mut self) -> Vec<Notification> {
+        let files: Vec<FileName> = self.workspace.documents.keys().cloned().collect();
+        let index = self.workspace.indexed();
+        files
+            .iter()
+            .flat_map(|file| {
+                let text = self.text(&index, file);
+                let diagnostics = index
+                    .diagnostics(file)
+                    .iter()
+                    .map(|e| diagnostic(&text, e))
+                    .collect();
+                self.publish(file, diagnostics)
+            })
+            .collect()
+    }
+
+    fn publish(
+        &self,
+        file: &FileName,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+    ) -> Option<Notification> {
+        self.uri(file).map(|uri| {
+            Notification::new(
+                PublishDiagnostics::METHOD.to_string(),
+                PublishDiagnosticsParams::new(uri, diagnostics, None),
+            )
+        })
+    }
+
+    fn text(&self, index: &Index, file: &FileName) -> String {
+        index
+            .project
+            .files
+            .get(file)
+            .map(|f| f.text.clone())
+            .unwrap_or_default()
+    }
+
+    /// takes the index of the whole workspace and finds the offset of the position in the document.
+    /// The document that is neither opened nor placed in the root folder is indexed separately.
+    fn locate(&mut self, uri: &Url, pos: Position) -> (Rc<Index>, FileName, usize) {
+        let file = self.file_name(uri);
+        let mut index = self.workspace.indexed();
+        if !index.project.files.contains_key(&file) {
+            let mut files = self.workspace.tree_files();
+            files.insert(0, file.clone());
+            index = Rc::new(self.workspace.index(files));
+        }
+        let offset = offset(&self.text(&index, &file), pos);
+        (index, file, offset)
+    }
+
+    fn location(&self, index: &Index, file: &FileName, span: Span) -> Option<Location> {
+        self.uri(file)
+            .map(|uri| Location::new(uri, range(&self.text(index, file), span)))
+    }
+
+    fn definition(&mut self, p: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let doc = p.text_document_position_params;
+        let (index, file, offset) = self.locate(&doc.text_document.uri, doc.position);
+        let (def_file, span) = index.definition(&file, offset)?;
+        self.location(&index, &def_file, span)
+            .map(GotoDefinitionResponse::Scalar)
+    }
+
+    fn hover(&mut self, p: HoverParams) -> Option<Hover> {
+        let doc = p.text_document_position_params;
+        let (index, file, offset) = self.locate(&doc.text_document.uri, doc.position);
+        let (_, span) = index.ident_at(&file, offset)?;
+        let value = index.hover(&file, offset)?;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(range(&self.text(&index, &file), span)),
+        })
+    }
+
+    fn completion(&mut self, p: CompletionParams) -> Option<CompletionResponse> {
+        let doc = p.text_document_position;
+        let (index, file, offset) = self.locate(&doc.text_document.uri, doc.position);
+        let items = index
+            .completion(&file, offset)
+            .into_iter()
+            .map(|c| CompletionItem {
+                label: c.label,
+                detail: Some(c.detail),
+                kind: Some(if c.is_param {
+                    CompletionItemKind::VARIABLE
+                } else {
+                    CompletionItemKind::FUNCTION
+                }),
+                ..Default::default()
+            })
+            .collect();
+        Some(CompletionResponse::Array(items))
+    }
+
+    fn references(&mut self, p: ReferenceParams) -> Option<Vec<Location>> {
+        let doc = p.text_document_position;
+        let (index, file, offset) = self.locate(&doc.text_document.uri, doc.position);
+        Some(
+            index
+                .references(&file, offset, p.context.include_declaration)
+                .into_iter()
+                .flat_map(|(f, span)| self.location(&index, &f, span))
+                .collect(),
+        )
+    }
+}
+
+fn params<P: DeserializeOwned>(value: Value) -> RtResult<P> {
+    Ok(serde_json::from_value(value)?)
+}
+
+fn handle<P, R, F>(req: Request, f: F) -> RtResult<Value>
+where
+    P: DeserializeOwned,
+    R: serde::Serialize,
+    F: FnOnce(P) -> R,
+{
+    Ok(serde_json::to_value(f(params(req.params)?))?)
+}
+
+fn diagnostic(text: &str, error: &TreeError) -> lsp_types::Diagnostic {
+    let (message, span) = match error.diagnostic() {
+        Some(d) => (d.message.clone(), d.location.span.unwrap_or_default()),
+        None => (error.to_string(), Span::default()),
+    };
+    lsp_types::Diagnostic {
+        range: range(text, span),
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("forester".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// the lsp position counts the characters in utf-16 code units
+fn position(text: &str, offset: usize) -> Position {
+    let LineCol { line, .. } = LineCol::from_offset(text, offset);
+    let line_start = text[..offset.min(text.len())]
+        .rfind('\n')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let character = text[line_start..offset.min(text.len())]
+        .chars()
+        .map(char::len_utf16)
+        .sum::<usize>();
+    Position::new((line - 1) as u32, character as u32)
+}
+
+fn range(text: &str, span: Span) -> Range {
+    Range::new(position(text, span.start), position(text, span.end))
+}
+
+fn offset(text: &str, pos: Position) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(pos.line as usize)
+        .map(str::len)
+        .sum();
+    let mut units = 0;
+    for (i, c) in text[line_start..].char_indices() {
+        if units >= pos.character as usize || c == '\n' {
+            return line_start + i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lsp::{offset, position};
+    use lsp_types::Position;
+
+    #[test]
+    fn positions() {
+        let text = "a\nбв(x)\n";
+        assert_eq!(position(text, 0), Position::new(0, 0));
+        assert_eq!(position(text, 6), Position::new(1, 2));
+        assert_eq!(offset(text, Position::new(1, 2)), 6);
+        assert_eq!(offset(text, Position::new(1, 100)), 9);
+        assert_eq!(offset(text, Position::new(5, 0)), text.len());
+    }
+}
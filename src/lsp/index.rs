@@ -0,0 +1,618 @@
+use crate::tree::diagnostic::{Diagnostic, Span};
+use crate::tree::parser::ast::arg::Param;
+use crate::tree::parser::ast::call::Call;
+use crate::tree::parser::ast::{FileEntity, Tree};
+use crate::tree::parser::lexer::Token;
+use crate::tree::parser::Parser;
+use crate::tree::project::file::File;
+use crate::tree::project::imports::ImportMap;
+use crate::tree::project::libs::Libraries;
+use crate::tree::project::{file_to_str, typecheck, FileName, Project, TreeName};
+use crate::tree::TreeError;
+use itertools::Itertools;
+use logos::Logos;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// The folder with the tree files along with the texts of the opened documents.
+/// The texts of the opened documents take precedence over the files on the disk.
+/// The libraries are taken from the manifest in the root folder if it is presented.
+#[derive(Debug, Default)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub documents: HashMap<FileName, String>,
+    pub libs: Libraries,
+    /// the index of all tree files that is kept until the documents change
+    indexed: Option<Rc<Index>>,
+}
+
+impl Workspace {
+    pub fn new(root: PathBuf) -> Self {
+        let libs = Libraries::load(&root).unwrap_or_default();
+        Workspace {
+            root,
+            documents: HashMap::new(),
+            libs,
+            indexed: None,
+        }
+    }
+
+    pub fn open(&mut self, file: FileName, text: String) {
+        self.documents.insert(file, text);
+        self.invalidate();
+    }
+    pub fn close(&mut self, file: &FileName) {
+        self.documents.remove(file);
+        self.invalidate();
+    }
+    /// drops the cached index, e.g. when the files on the disk are changed
+    pub fn invalidate(&mut self) {
+        self.indexed = None;
+    }
+
+    /// the index of all tree files from the root folder and the opened documents.
+    /// It is built once and reused until the documents change.
+    pub fn indexed(&mut self) -> Rc<Index> {
+        match &self.indexed {
+            Some(index) => index.clone(),
+            None => {
+                let index = Rc::new(self.index(self.tree_files()));
+                self.indexed = Some(index.clone());
+                index
+            }
+        }
+    }
+
+    /// the name of the file in the terms of the project, namely relative to the root
+    /// or to the folder of the library
+    pub fn file_name(&self, path: &Path) -> FileName {
+        self.libs
+            .modules
+            .names()
+            .into_iter()
+            .find(|f| std_path(f) == path)
+            .map(|f| f.to_string())
+            .or(self.libs.libraries.iter().find_map(|(name, dir)| {
+                path.strip_prefix(dir)
+                    .ok()
+                    .map(|p| format!("{name}::{}", p.to_string_lossy()))
+            }))
+            .or(path
+                .strip_prefix(&self.root)
+                .ok()
+                .map(|p| p.to_string_lossy().to_string()))
+            .unwrap_or(path.to_string_lossy().to_string())
+    }
+
+    /// the path of the file on the disk.
+    /// The standard files are written down to the temporary folder to be available for the editors.
+    /// The file is rewritten when it is left from another version of the module.
+    pub fn path(&self, file: &FileName) -> PathBuf {
+        if self.libs.modules.contains(file) {
+            let path = std_path(file);
+            if let Ok(text) = file_to_str(self.root.clone(), &self.libs, file.clone()) {
+                if fs::read_to_string(&path).ok().as_ref() != Some(&text) {
+                    let _ = path.parent().map(fs::create_dir_all);
+                    let _ = fs::write(&path, text);
+                }
+            }
+            path
+        } else if let Ok(Some(path)) = self.libs.resolve(file) {
+            path
+        } else {
+            self.root.join(file)
+        }
+    }
+
+    /// all tree files from the root folder and the opened documents
+    pub fn tree_files(&self) -> Vec<FileName> {
+        let mut files: HashSet<FileName> = self.documents.keys().cloned().collect();
+        let mut folders = vec![self.root.clone()];
+        while let Some(folder) = folders.pop() {
+            let Ok(entries) = fs::read_dir(folder) else {
+                continue;
+            };
+            for path in entries.flatten().map(|e| e.path()) {
+                let hidden = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().starts_with('.') || n == "target")
+                    .unwrap_or(true);
+                if hidden {
+                    continue;
+                }
+                if path.is_dir() {
+                    folders.push(path);
+                } else if path.extension().map(|e| e == "tree").unwrap_or(false) {
+                    files.insert(self.file_name(&path));
+                }
+            }
+        }
+        files.into_iter().sorted().collect()
+    }
+
+    /// loads the given files along with all imported files and checks the types of the calls.
+    /// The errors do not stop the loading but get collected for every file.
+    pub fn index(&self, files: Vec<FileName>) -> Index {
+        let mut index = Index::default();
+        index.project.root = self.root.clone();
+        index.project.libs = self.libs.clone();
+        let mut queue = VecDeque::from(files);
+        while let Some(file) = queue.pop_front() {
+            if index.project.files.contains_key(&file) {
+                continue;
+            }
+            let text = match self.documents.get(&file) {
+                Some(text) => text.clone(),
+                None => match file_to_str(self.root.clone(), &self.libs, file.clone()) {
+                    Ok(text) => text,
+                    Err(_) => continue,
+                },
+            };
+            let (ast, mut errors) = Parser::parse_recovering(text.as_str());
+            let mut f = File::new_with_text(file.clone(), text);
+            for entity in ast.0 {
+                match entity {
+                    FileEntity::Tree(tree) => {
+                        if let Err(e) = f.add_def(tree) {
+                            errors.push(e);
+                        }
+                    }
+                    FileEntity::Struct(def) => {
+                        if let Err(e) = f.add_struct(def) {
+                            errors.push(e);
+                        }
+                    }
+                    FileEntity::Enum(def) => {
+                        if let Err(e) = f.add_enum(def) {
+                            errors.push(e);
+                        }
+                    }
+                    FileEntity::Const(def) => {
+                        if let Err(e) = f.add_const(def) {
+                            errors.push(e);
+                        }
+                    }
+                    FileEntity::Import(import) => {
+                        let imported = import.f_name().to_string();
+                        let readable = self.documents.contains_key(&imported)
+                            || file_to_str(self.root.clone(), &self.libs, imported.clone()).is_ok();
+                        if readable {
+                            queue.push_back(imported.clone());
+                        } else {
+                            errors.push(
+                                f.locate(
+                                    TreeError::CompileError(
+                                        Diagnostic::new(format!(
+                                            "the import {imported} can not be processed"
+                                        ))
+                                        .into(),
+                                    ),
+                                    import.span(),
+                                ),
+                            );
+                        }
+                        let _ = f.add_import(import);
+                    }
+                }
+            }
+            index.errors.insert(file.clone(), errors);
+            index.project.files.insert(file, f);
+        }
+        let mut errors = index.project.resolve_types();
+        errors.extend(typecheck::check(&index.project));
+        for e in errors {
+            if let Some(file) = e.diagnostic().and_then(|d| d.location.file.clone()) {
+                index.errors.entry(file).or_default().push(e);
+            }
+        }
+        index
+    }
+}
+
+/// the standard files are kept apart for every version of the library
+fn std_path(file: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("forester-{}", env!("CARGO_PKG_VERSION")))
+        .join(format!("{}.tree", file.replace("::", "_")))
+}
+
+/// The symbol under the cursor
+#[derive(Debug, Clone, PartialEq)]
+pub enum Symbol<'a> {
+    /// the parameter of the enclosing tree
+    Param(&'a Tree, &'a Param),
+    /// the tree definition along with the file where it is defined
+    Tree(&'a FileName, &'a Tree),
+}
+
+/// The item of the completion list
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub label: String,
+    pub detail: String,
+    pub is_param: bool,
+}
+
+/// The loaded files of the workspace along with the errors found while loading them.
+#[derive(Debug, Default)]
+pub struct Index {
+    pub project: Project,
+    pub errors: HashMap<FileName, Vec<TreeError>>,
+}
+
+impl Index {
+    /// the syntax errors along with the imports, duplicates and the errors of the type checking
+    pub fn diagnostics(&self, file_name: &FileName) -> Vec<TreeError> {
+        self.errors.get(file_name).cloned().unwrap_or_default()
+    }
+
+    /// finds the definition of the tree that is visible in the file by the name or alias
+    pub fn resolve(&self, file_name: &FileName, name: &TreeName) -> Option<(&FileName, &Tree)> {
+        let file = self.project.files.get(file_name)?;
+        match file.definitions.get(name) {
+            Some(tree) => Some((&file.name, tree)),
+            None => {
+                let map = ImportMap::build(file).ok()?;
+                let (tree, file) = map.find(name, &self.project).ok()?;
+                let (file, _) = self.project.files.get_key_value(file)?;
+                Some((file, self.project.find_tree(file, &tree.name)?))
+            }
+        }
+    }
+
+    /// the identifier under the cursor along with the place of it.
+    /// The qualified names like `nav::approach` are taken as a whole.
+    pub fn ident_at(&self, file_name: &FileName, offset: usize) -> Option<(String, Span)> {
+        let text = self.project.files.get(file_name)?.text.as_str();
+        let mut path: Option<(String, Span)> = None;
+        let mut qualified = false;
+        for (token, range) in Token::lexer(text).spanned() {
+            match (token, path.as_mut()) {
+                (Ok(Token::Id(id)), Some((name, span))) if qualified => {
+                    name.push_str(&id);
+                    span.end = range.end;
+                    qualified = false;
+                }
+                (Ok(Token::ColonColon), Some((name, span))) if !qualified => {
+                    name.push_str("::");
+                    span.end = range.end;
+                    qualified = true;
+                }
+                (token, _) => {
+                    if let Some((_, span)) = path.as_ref() {
+                        if span.start <= offset && offset <= span.end {
+                            return path;
+                        }
+                    }
+                    qualified = false;
+                    path = match token {
+                        Ok(Token::Id(id)) => Some((id, Span::new(range.start, range.end))),
+                        _ => None,
+                    };
+                }
+            }
+        }
+        path.filter(|(_, span)| span.start <= offset && offset <= span.end)
+    }
+
+    /// the tree definition that contains the offset
+    pub fn enclosing_tree(&self, file_name: &FileName, offset: usize) -> Option<&Tree> {
+        self.project
+            .files
+            .get(file_name)?
+            .definitions
+            .values()
+            .find(|t| t.span.start <= offset && offset <= t.span.end)
+    }
+
+    pub fn symbol_at(&self, file_name: &FileName, offset: usize) -> Option<Symbol<'_>> {
+        let (name, _) = self.ident_at(file_name, offset)?;
+        let param = self.enclosing_tree(file_name, offset).and_then(|tree| {
+            tree.params
+                .params
+                .iter()
+                .find(|p| p.name == name)
+                .map(|p| Symbol::Param(tree, p))
+        });
+        param.or_else(|| {
+            self.resolve(file_name, &name)
+                .map(|(file, tree)| Symbol::Tree(file, tree))
+        })
+    }
+
+    /// the place of the name of the definition for the symbol under the cursor
+    pub fn definition(&self, file_name: &FileName, offset: usize) -> Option<(FileName, Span)> {
+        match self.symbol_at(file_name, offset)? {
+            Symbol::Param(tree, _) => Some((file_name.clone(), self.name_span(file_name, tree))),
+            Symbol::Tree(file, tree) => Some((file.clone(), self.name_span(file, tree))),
+        }
+    }
+
+    /// the short description of the symbol under the cursor in markdown
+    pub fn hover(&self, file_name: &FileName, offset: usize) -> Option<String> {
+        match self.symbol_at(file_name, offset)? {
+            Symbol::Param(tree, param) => Some(format!(
+                "```f-tree\n{}\n```\nthe parameter of `{}`",
+                param, tree.name
+            )),
+            Symbol::Tree(file, tree) => Some(format!(
+                "```f-tree\n{}\n```\ndefined in `{}`",
+                header(tree),
+                file
+            )),
+        }
+    }
+
+    /// the trees visible in the file and the parameters of the enclosing tree
+    pub fn completion(&self, file_name: &FileName, offset: usize) -> Vec<Completion> {
+        let mut items = vec![];
+        if let Some(tree) = self.enclosing_tree(file_name, offset) {
+            for p in tree.params.params.iter() {
+                items.push(Completion {
+                    label: p.name.clone(),
+                    detail: format!("{}:{}", p.name, p.tpe),
+                    is_param: true,
+                });
+            }
+        }
+        let Some(file) = self.project.files.get(file_name) else {
+            return items;
+        };
+        let mut names: HashSet<TreeName> = file.definitions.keys().cloned().collect();
+        if let Ok(map) = ImportMap::build(file) {
+            names.extend(map.trees.keys().cloned());
+            names.extend(map.aliases.keys().cloned());
+            for imported in map.files.iter().flat_map(|f| self.project.files.get(f)) {
+                names.extend(imported.definitions.keys().cloned());
+            }
+            for (ns, f) in map.namespaces.iter() {
+                if let Some(imported) = self.project.files.get(f) {
+                    names.extend(imported.definitions.keys().map(|k| format!("{ns}::{k}")));
+                }
+            }
+        }
+        for name in names.into_iter().sorted() {
+            if let Some((_, tree)) = self.resolve(file_name, &name) {
+                items.push(Completion {
+                    label: name,
+                    detail: header(tree),
+                    is_param: false,
+                });
+            }
+        }
+        items
+    }
+
+    /// all invocations of the tree under the cursor in the loaded files
+    pub fn references(
+        &self,
+        file_name: &FileName,
+        offset: usize,
+        include_declaration: bool,
+    ) -> Vec<(FileName, Span)> {
+        let Some(Symbol::Tree(def_file, def)) = self.symbol_at(file_name, offset) else {
+            return vec![];
+        };
+        let mut refs = vec![];
+        if include_declaration {
+            refs.push((def_file.clone(), self.name_span(def_file, def)));
+        }
+        for file in self
+            .project
+            .files
+            .values()
+            .sorted_by_key(|f| f.name.clone())
+        {
+            let mut spans = vec![];
+            for tree in file.definitions.values() {
+                invocations(&tree.calls.elems, &mut |name, span| {
+                    let same = self
+                        .resolve(&file.name, name)
+                        .map(|(f, t)| f == def_file && t.name == def.name)
+                        .unwrap_or(false);
+                    if same {
+                        spans.push(Span::new(span.start, span.start + name.len()));
+                    }
+                });
+            }
+            spans.sort_by_key(|s| s.start);
+            refs.extend(spans.into_iter().map(|s| (file.name.clone(), s)));
+        }
+        refs
+    }
+
+    /// the span of the name in the tree definition
+    pub fn name_span(&self, file_name: &FileName, tree: &Tree) -> Span {
+        self.project
+            .files
+            .get(file_name)
+            .and_then(|f| f.text.get(tree.span.start..tree.span.end))
+            .and_then(|text| {
+                Token::lexer(text)
+                    .spanned()
+                    .nth(1)
+                    .map(|(_, r)| Span::new(tree.span.start + r.start, tree.span.start + r.end))
+            })
+            .unwrap_or(tree.span)
+    }
+}
+
+/// visits all invocations including the ones passed as arguments
+fn invocations<F>(calls: &[Call], f: &mut F)
+where
+    F: FnMut(&TreeName, Span),
+{
+    for call in calls {
+        match call {
+            Call::Invocation(name, args, span) => {
+                f(name, *span);
+                let arg_calls: Vec<Call> = args
+                    .args
+                    .iter()
+                    .flat_map(|a| a.value().get_call())
+                    .collect();
+                invocations(&arg_calls, f);
+            }
+            Call::HoInvocation(..) => {}
+            Call::Lambda(_, _, calls, _) => invocations(&calls.elems, f),
+            Call::Decorator(_, args, child, _) => {
+                let mut arg_calls: Vec<Call> = args
+                    .args
+                    .iter()
+                    .flat_map(|a| a.value().get_call())
+                    .collect();
+                arg_calls.push(*child.clone());
+                invocations(&arg_calls, f);
+            }
+        }
+    }
+}
+
+/// the header of the definition like `impl name(a:num)`
+pub fn header(tree: &Tree) -> String {
+    let params = tree.params.params.iter().map(|p| p.to_string()).join(", ");
+    format!("{} {}({})", tree.tpe, tree.name, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lsp::index::{Symbol, Workspace};
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    fn ws() -> Workspace {
+        let mut ws = Workspace::new(PathBuf::from("/nowhere"));
+        ws.open(
+            "main.tree".to_string(),
+            r#"import "std::actions"
+import "lib.tree" { go => move }
+
+root main sequence {
+    move(1)
+    helper(x = 2)
+    absent()
+}
+
+sequence helper(x:num) {
+    go(x)
+    fail("reason")
+}
+"#
+            .to_string(),
+        );
+        ws.open("lib.tree".to_string(), "impl go(dist:num);".to_string());
+        ws
+    }
+
+    #[test]
+    fn diagnostics() {
+        let ws = ws();
+        let index = ws.index(vec!["main.tree".to_string()]);
+        let errors: Vec<String> = index
+            .diagnostics(&"main.tree".to_string())
+            .iter()
+            .map(|e| e.diagnostic().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            errors,
+            vec!["main.tree:7:5: the call absent can not be found"]
+        );
+    }
+
+    #[test]
+    fn cached_index() {
+        let mut ws = ws();
+        let index = ws.indexed();
+        assert!(Rc::ptr_eq(&index, &ws.indexed()));
+        assert_eq!(index.diagnostics(&"main.tree".to_string()).len(), 1);
+
+        ws.open("main.tree".to_string(), "root main go(1)".to_string());
+        let reindexed = ws.indexed();
+        assert!(!Rc::ptr_eq(&index, &reindexed));
+        assert_eq!(reindexed.diagnostics(&"main.tree".to_string()).len(), 1);
+    }
+
+    #[test]
+    fn std_files() {
+        let ws = ws();
+        let file = "std::actions".to_string();
+        let path = ws.path(&file);
+        std::fs::write(&path, "outdated").unwrap();
+        assert_eq!(ws.path(&file), path);
+        assert_ne!(std::fs::read_to_string(&path).unwrap(), "outdated");
+        assert_eq!(ws.file_name(&path), file);
+    }
+
+    #[test]
+    fn navigation() {
+        let ws = ws();
+        let main = "main.tree".to_string();
+        let index = ws.index(vec![main.clone()]);
+        let text = index.project.files[&main].text.clone();
+        let move_pos = text.find("move(1)").unwrap() + 1;
+
+        let (file, span) = index.definition(&main, move_pos).unwrap();
+        assert_eq!(file, "lib.tree");
+        assert_eq!((span.start, span.end), (5, 7));
+
+        let hover = index.hover(&main, move_pos).unwrap();
+        assert!(hover.contains("impl go(dist:num)"));
+
+        let x_pos = text.find("go(x)").unwrap() + 3;
+        assert!(matches!(
+            index.symbol_at(&main, x_pos),
+            Some(Symbol::Param(..))
+        ));
+
+        let fail_pos = text.find("fail(").unwrap() + 1;
+        assert_eq!(index.definition(&main, fail_pos).unwrap().0, "std::actions");
+
+        let labels: Vec<String> = index
+            .completion(&main, x_pos)
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        assert!(labels.contains(&"x".to_string()));
+        assert!(labels.contains(&"move".to_string()));
+        assert!(labels.contains(&"helper".to_string()));
+        assert!(labels.contains(&"success".to_string()));
+
+        let helper_pos = text.find("sequence helper").unwrap() + 10;
+        let refs = index.references(&main, helper_pos, true);
+        let starts: Vec<usize> = refs.iter().map(|(_, s)| s.start).collect();
+        assert_eq!(
+            starts,
+            vec![
+                text.find("sequence helper").unwrap() + 9,
+                text.find("helper(x = 2)").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn namespaces() {
+        let mut ws = ws();
+        let main = "ns.tree".to_string();
+        ws.open(
+            main.clone(),
+            "import \"lib.tree\" as lib\nroot main lib::go(1)\n".to_string(),
+        );
+        let index = ws.index(vec![main.clone()]);
+        let text = index.project.files[&main].text.clone();
+        let go_pos = text.find("go(1)").unwrap() + 1;
+
+        let (name, span) = index.ident_at(&main, go_pos).unwrap();
+        assert_eq!(name, "lib::go");
+        assert_eq!(span.start, text.find("lib::go").unwrap());
+        assert_eq!(index.definition(&main, go_pos).unwrap().0, "lib.tree");
+
+        let labels: Vec<String> = index
+            .completion(&main, go_pos)
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        assert!(labels.contains(&"lib::go".to_string()));
+    }
+}
@@ -162,12 +162,25 @@ impl From<serde_json::Error> for RuntimeError {
     }
 }
 
+impl From<bincode::Error> for RuntimeError {
+    fn from(value: bincode::Error) -> Self {
+        RuntimeError::IOError(format!("{value}"))
+    }
+}
+
 impl From<std::io::Error> for RuntimeError {
     fn from(value: std::io::Error) -> Self {
         RuntimeError::IOError(format!("{value}"))
     }
 }
 
+#[cfg(feature = "lsp")]
+impl From<lsp_server::ProtocolError> for RuntimeError {
+    fn from(value: lsp_server::ProtocolError) -> Self {
+        RuntimeError::IOError(format!("{value}"))
+    }
+}
+
 impl From<reqwest::Error> for RuntimeError {
     fn from(value: reqwest::Error) -> Self {
         RuntimeError::fail(format!("{value}"))
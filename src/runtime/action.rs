@@ -25,7 +25,7 @@ pub fn recover(tick: Tick) -> Tick {
 /// - async to handle the future (uses tokio under the hood)
 /// - remote to handle the remote actions
 pub enum Action {
-    Sync(Box<dyn Impl>),
+    Sync(Arc<dyn Impl>),
     Async(Arc<dyn ImplAsync>),
     Remote(Box<dyn ImplRemote>),
 }
@@ -35,7 +35,7 @@ impl Action {
     where
         T: Impl + 'static,
     {
-        Action::Sync(Box::new(a))
+        Action::Sync(Arc::new(a))
     }
 
     pub fn a_sync<T>(a: T) -> Self
@@ -134,6 +134,17 @@ pub trait Impl: Sync + Send {
 
 pub trait ImplAsync: Sync + Send {
     fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick;
+
+    /// Called when the running action is halted.
+    /// The handle of the task is dropped beforehand, thus its result is ignored,
+    /// but the task that is already running on the blocking pool can not be stopped from the outside,
+    /// thus the action can use the hook to signal it (e.g. through the blackboard).
+    fn halt(&self, args: RtArgs, ctx: TreeContextRef) -> RtOk {
+        // Default halt is a no-op function.
+        let _ = args;
+        let _ = ctx;
+        Ok(())
+    }
 }
 
 /// The trait to implement remote action.
@@ -148,11 +159,19 @@ pub trait ImplAsync: Sync + Send {
 ///
 pub trait ImplRemote: Sync + Send {
     fn tick(&self, args: RtArgs, ctx: TreeRemoteContextRef) -> Tick;
+
+    /// Called when the running action is halted to cancel it on the remote side.
+    fn halt(&self, args: RtArgs, ctx: TreeRemoteContextRef) -> RtOk {
+        // Default halt is a no-op function.
+        let _ = args;
+        let _ = ctx;
+        Ok(())
+    }
 }
 
 impl From<Box<dyn Impl>> for Action {
     fn from(value: Box<dyn Impl>) -> Self {
-        Action::Sync(value)
+        Action::Sync(Arc::from(value))
     }
 }
 
@@ -6,9 +6,11 @@
 //! - `store_tick` - save current tick to bb
 //! - `check_eq` - compare a value in the cell with the given expected value
 //! - `test_bool` - compare a value in the cell with the true
+//! - `check_expr` - evaluate the inline expression against bb
 //! - `generate_data` - a simple action that can generate and then update data in the given cell in bb.
 
 use crate::runtime::action::{Impl, Tick};
+use crate::runtime::args::expr::eval;
 use crate::runtime::args::{RtArgs, RtValue};
 use crate::runtime::context::TreeContextRef;
 use crate::runtime::{RuntimeError, TickResult};
@@ -129,6 +131,56 @@ impl Impl for TestBool {
     }
 }
 
+/// Evaluate the inline expression like `check(battery < 20 && mode == "auto")`
+/// The ids of the expression are the keys in bb.
+/// The reason of the failure contains the values of the keys.
+pub struct CheckExpr;
+
+impl Impl for CheckExpr {
+    fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+        let expr = match args
+            .find_or_ith("expr".to_string(), 0)
+            .ok_or(RuntimeError::fail("the expression is expected".to_string()))?
+        {
+            RtValue::Expr(e) => e,
+            v => {
+                return match v.clone().cast(ctx).bool()? {
+                    Some(true) => Ok(TickResult::success()),
+                    Some(false) => Ok(TickResult::failure(format!("{v} is false"))),
+                    None => Ok(TickResult::failure(format!("{v} is not a bool"))),
+                }
+            }
+        };
+
+        match eval(&expr, &ctx)? {
+            RtValue::Bool(true) => Ok(TickResult::success()),
+            RtValue::Bool(false) => {
+                let bb = ctx.bb();
+                let bb = bb.lock()?;
+                let mut values = vec![];
+                for id in expr.ids() {
+                    if let Some(v) = bb.get(id.clone())? {
+                        values.push(format!("{id} = {v}"));
+                    }
+                }
+                if values.is_empty() {
+                    Ok(TickResult::failure(format!(
+                        "the condition '{expr}' is false"
+                    )))
+                } else {
+                    Ok(TickResult::failure(format!(
+                        "the condition '{expr}' is false ({})",
+                        values.join(", ")
+                    )))
+                }
+            }
+            v => Ok(TickResult::failure(format!(
+                "the condition '{expr}' is expected to be a bool but it is {v}"
+            ))),
+        }
+    }
+}
+
 /// A simple action that can generate and then update data in the given cell in bb.
 /// Encompasses a function that accepts a current value of the cell and then place the updated one.
 ///
@@ -1,17 +1,23 @@
 use crate::runtime::action::{ ImplRemote, Tick};
 use crate::runtime::args::{RtArgs, RtArgument};
 use crate::runtime::context::{TreeRemoteContextRef};
-use crate::runtime::{to_fail, TickResult};
+use crate::runtime::{to_fail, RtOk, RtResult, RuntimeError, TickResult};
 
 use hyper::client::HttpConnector;
-use hyper::{body, Body, Client, Method, Request};
+use hyper::{body, Body, Client, Method, Request, Response};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The default timeout of the requests
+const TIMEOUT: Duration = Duration::from_secs(30);
 
 /// The struct defines the remote action that can be executed on the remote server.
 ///
 /// #Notes
-/// The connection is confined by the timeout of 30 seconds by default
+/// The request is confined by the timeout of 30 seconds by default, see [`RemoteHttpAction::with_timeout`]
 /// The error is recovered as a failure and returns the Failure result
+/// When the running action is halted, the same request is sent to the url with the suffix `/halt`
+/// confined by the same timeout
 ///
 /// #Examples
 /// ```
@@ -42,47 +48,79 @@ use serde::{Deserialize, Serialize};
 pub struct RemoteHttpAction {
     url: String,
     serv_ip: Option<String>,
+    timeout: Duration,
 }
 
 impl ImplRemote for RemoteHttpAction {
     fn tick(&self, args: RtArgs, ctx: TreeRemoteContextRef) -> Tick {
-        let serv_url = self
-            .serv_ip
-            .clone()
-            .unwrap_or("http://localhost".to_string());
-        let request = RemoteActionRequest {
-            tick: ctx.curr_ts,
-            args: args.0,
-            serv_url: format!("{}:{}", serv_url, ctx.port),
-        };
+        let request = self.request(args, &ctx);
 
         debug!(target:"remote_action", "remote request {:?} to {}",&request, &self.url.clone());
 
-        let env = ctx.env.lock()?;
-        let resp = env.runtime.block_on(async {
-            let client: Client<HttpConnector, Body> =
-                hyper::Client::builder().build(HttpConnector::new());
-            // todo with vec is slow. Bytes?
-            let body_js = serde_json::to_vec(&request).unwrap();
-
-            let request = Request::builder()
-                .method(Method::POST)
-                .header("Content-Type", "application/json")
-                .uri(self.url.clone())
-                .body(Body::from(body_js))
-                .expect("flawless request");
-
-            match to_fail(client.request(request).await) {
-                Ok(r) => to_fail(body::to_bytes(r.into_body()).await)
-                    .and_then(|bytes| to_fail(serde_json::from_slice::<TickResult>(&bytes))),
-                Err(e) => Err(e),
-            }
+        // the lock of the env is not held while the request is running
+        let runtime = ctx.env.lock()?.runtime.handle().clone();
+        let resp = runtime.block_on(async {
+            let resp = tokio::time::timeout(self.timeout, async {
+                match post(self.url.clone(), &request).await {
+                    Ok(r) => to_fail(body::to_bytes(r.into_body()).await)
+                        .and_then(|bytes| to_fail(serde_json::from_slice::<TickResult>(&bytes))),
+                    Err(e) => Err(e),
+                }
+            })
+            .await;
+            resp.unwrap_or_else(|_| {
+                Err(RuntimeError::fail(format!(
+                    "the remote action {} is timed out after {:?}",
+                    self.url, self.timeout
+                )))
+            })
         });
 
         debug!(target:"http_serv_proxy", "remote_action: {:?}", resp);
 
         resp
     }
+
+    /// Sends the same request to the url with the suffix `/halt`.
+    /// The remote side that does not handle the halting or is not reachable is tolerated.
+    fn halt(&self, args: RtArgs, ctx: TreeRemoteContextRef) -> RtOk {
+        let request = self.request(args, &ctx);
+        let url = format!("{}/halt", self.url.trim_end_matches('/'));
+
+        debug!(target:"remote_action", "remote halt request {:?} to {}",&request, &url);
+
+        let runtime = ctx.env.lock()?.runtime.handle().clone();
+        match runtime
+            .block_on(async { tokio::time::timeout(self.timeout, post(url, &request)).await })
+        {
+            Ok(Ok(resp)) if !resp.status().is_success() => {
+                debug!(target:"remote_action", "the halt request is not accepted, the status is {}", resp.status());
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => {
+                debug!(target:"remote_action", "the halt request is not delivered: {:?}", e);
+            }
+            Err(_) => {
+                debug!(target:"remote_action", "the halt request is timed out after {:?}", self.timeout);
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn post(url: String, request: &RemoteActionRequest) -> RtResult<Response<Body>> {
+    let client: Client<HttpConnector, Body> = hyper::Client::builder().build(HttpConnector::new());
+    // todo with vec is slow. Bytes?
+    let body_js = serde_json::to_vec(request).unwrap();
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .header("Content-Type", "application/json")
+        .uri(url)
+        .body(Body::from(body_js))
+        .expect("flawless request");
+
+    to_fail(client.request(request).await)
 }
 
 impl RemoteHttpAction {
@@ -96,7 +134,28 @@ impl RemoteHttpAction {
     /// #Notes
     /// The server ip is not set, the default is localhost
     pub fn new(url: String) -> Self {
-        Self { url, serv_ip: None }
+        Self {
+            url,
+            serv_ip: None,
+            timeout: TIMEOUT,
+        }
+    }
+
+    /// Sets the timeout of the requests instead of the default 30 seconds
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    fn request(&self, args: RtArgs, ctx: &TreeRemoteContextRef) -> RemoteActionRequest {
+        let serv_url = self
+            .serv_ip
+            .clone()
+            .unwrap_or("http://localhost".to_string());
+        RemoteActionRequest {
+            tick: ctx.curr_ts,
+            args: args.0,
+            serv_url: format!("{}:{}", serv_url, ctx.port),
+        }
     }
 
     /// Create a new remote action with the url and the server ip
@@ -104,6 +163,7 @@ impl RemoteHttpAction {
         Self {
             url,
             serv_ip: Some(serv_ip),
+            timeout: TIMEOUT,
         }
     }
 }
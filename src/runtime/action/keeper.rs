@@ -1,10 +1,12 @@
 use crate::runtime::action::Tick;
-use crate::runtime::action::{Action, ActionName};
+use crate::runtime::action::{Action, ActionName, Impl};
 use crate::runtime::args::RtArgs;
 use crate::runtime::context::{TreeContextRef, TreeRemoteContextRef};
+use crate::runtime::env::AsyncTask;
 use crate::runtime::env::RtEnv;
 use crate::runtime::env::TaskState;
 use crate::runtime::forester::serv::ServInfo;
+use crate::runtime::rtree::rnode::RNodeId;
 use crate::runtime::{RtResult, RuntimeError, TickResult};
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
@@ -86,6 +88,14 @@ impl ActionKeeper {
                 "the action {name} is not registered"
             )))
     }
+    /// The sync action to tick it outside the keeper, e.g. concurrently with the other actions.
+    /// Returns None if the action is either absent or not sync.
+    pub(crate) fn sync_action(&self, name: &ActionName) -> Option<Arc<dyn Impl>> {
+        match self.actions.get(name) {
+            Some(ActionImpl::Present(Action::Sync(action))) => Some(action.clone()),
+            _ => None,
+        }
+    }
     /// Register an action with the given name and the impl.
     pub fn register(&mut self, name: ActionName, action: Action) -> RtResult<()> {
         debug!(target:"action","A new action {name} is registered");
@@ -95,9 +105,11 @@ impl ActionKeeper {
 
     /// Execute an action, previously find it by name.
     /// If the action is async and running, check the process instead.
+    /// The async tasks are tracked by the node, thus the same action can run in several places at once.
     pub fn on_tick(
         &mut self,
         env: Arc<Mutex<RtEnv>>,
+        id: RNodeId,
         name: &ActionName,
         args: RtArgs,
         ctx: TreeContextRef,
@@ -112,18 +124,19 @@ impl ActionKeeper {
             Action::Async(ref mut action) => {
                 let mut env = env.lock()?;
 
-                match env.task_state(name)? {
+                match env.task_state(&id)? {
                     // just to start it in the separate thread(supposedly)
                     TaskState::Absent => {
                         let action = action.to_owned();
-                        let tick_handle =
-                            env.runtime.spawn_blocking(move || action.tick(args, ctx));
-                        env.tasks.insert(name.to_string(), tick_handle);
+                        let task = AsyncTask::spawn(&env.runtime, env.signal.clone(), move || {
+                            action.tick(args, ctx)
+                        });
+                        env.tasks.insert(id, task);
                         Ok(TickResult::running())
                     }
-                    TaskState::Started(handle) => {
+                    TaskState::Started(task) => {
                         // return it to the running tasks instantly.
-                        env.tasks.insert(name.to_string(), handle);
+                        env.tasks.insert(id, task);
                         Ok(TickResult::running())
                     }
                     TaskState::Finished(r) => r,
@@ -132,23 +145,30 @@ impl ActionKeeper {
         }
     }
 
+    /// Halt the running action.
+    /// The task of the async action is dropped, thus its result is ignored.
+    /// The task that has already started can not be stopped and runs till the end on the blocking pool.
     pub fn halt(
         &mut self,
-        _env: Arc<Mutex<RtEnv>>,
+        env: Arc<Mutex<RtEnv>>,
+        id: RNodeId,
         name: &ActionName,
         args: RtArgs,
         ctx: TreeContextRef,
-        _http_serv: &Option<ServInfo>,
+        http_serv: &Option<ServInfo>,
     ) -> RtOk {
         match self.get_mut(name)? {
             Action::Sync(action) => action.halt(args, ctx),
-            Action::Remote(..) => {
-                // Halting is not implemented for remote actions.
-                Ok(())
-            }
-            Action::Async(..) => {
-                // Halting is not implemented for async actions.
-                Ok(())
+            Action::Remote(action) => action.halt(
+                args,
+                TreeRemoteContextRef::new(ctx.current_tick(), get_port(http_serv)?, env),
+            ),
+            Action::Async(action) => {
+                if let Some(task) = env.lock()?.tasks.remove(&id) {
+                    debug!(target:"action","the task of the action {name} in the node {id} is dropped, its result is ignored");
+                    task.abort();
+                }
+                action.halt(args, ctx)
             }
         }
     }
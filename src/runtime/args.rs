@@ -1,17 +1,20 @@
 pub mod display;
+pub mod expr;
 mod sede;
 pub mod transform;
 
-use crate::runtime::args::transform::find_arg_value;
+use crate::runtime::args::transform::{find_arg_value, substitute_expr};
 use crate::runtime::blackboard::BBKey;
 use crate::runtime::context::TreeContextRef;
-use crate::runtime::{RtResult, RuntimeError};
-use crate::tree::parser::ast::arg::{ArgumentRhs, Arguments, MesType, Param, Params};
+use crate::runtime::{RtOk, RtResult, RuntimeError};
+use crate::tree::parser::ast::arg::{ArgumentRhs, Arguments, MesType, Param, ParamDir, Params};
 use crate::tree::parser::ast::call::Call;
+use crate::tree::parser::ast::expr::Expr;
 use crate::tree::parser::ast::message::{Message, Number};
 use crate::tree::{cerr, TreeError};
 use itertools::Itertools;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{format, Display, Formatter};
@@ -56,6 +59,7 @@ impl From<Number> for RtValueNumber {
 /// - `Number` - the number value
 /// - `Pointer` - the pointer to the value in BlackBoard (or to parent arguments)
 /// - `Call` - the call to the tree (for the higher order trees)
+/// - `Expr` - the expression that is evaluated against BlackBoard (the ids are the pointers)
 #[derive(Debug, PartialEq, Clone)]
 pub enum RtValue {
     String(String),
@@ -65,6 +69,7 @@ pub enum RtValue {
     Number(RtValueNumber),
     Pointer(BBKey),
     Call(Call),
+    Expr(Expr),
 }
 
 /// Just a utility helping to cast the `RtValue` to the specific type.
@@ -121,6 +126,10 @@ impl RtValueCast {
     {
         self.with_ptr().map(|v| v.as_map(map))
     }
+    /// tries to deserialize the value into the given struct
+    pub fn into_struct<T: DeserializeOwned>(self) -> RtResult<T> {
+        self.with_ptr().and_then(RtValue::into_struct)
+    }
 }
 
 impl RtValue {
@@ -190,10 +199,37 @@ impl RtValue {
         }
     }
 
+    /// deserializes the value into the given struct
+    /// that is supposed to match the struct type of the parameter.
+    ///
+    /// # Example
+    /// ```
+    /// use forester_rs::runtime::args::RtValue;
+    /// use std::collections::HashMap;
+    /// #[derive(serde::Deserialize)]
+    /// struct Cfg {
+    ///     rate: i64,
+    ///     tp: Option<String>,
+    /// }
+    ///
+    /// let v = RtValue::Object(HashMap::from_iter(vec![("rate".to_string(), RtValue::int(10))]));
+    /// let cfg: Cfg = v.into_struct().unwrap();
+    /// assert_eq!(cfg.rate, 10);
+    /// assert_eq!(cfg.tp, None);
+    /// ```
+    pub fn into_struct<T: DeserializeOwned>(self) -> RtResult<T> {
+        let json = serde_json::to_value(&self)?;
+        serde_json::from_value(json).map_err(|e| {
+            RuntimeError::WrongArgument(format!("the value {self} can not be deserialized: {e}"))
+        })
+    }
+
     /// tries to resolve the pointer to the value in BlackBoard,
+    /// evaluates the expression against BlackBoard
     /// or if it is already a scalar value, then returns it
     pub fn with_ptr(self, ctx: TreeContextRef) -> RtResult<RtValue> {
         match self {
+            RtValue::Expr(e) => expr::eval(&e, &ctx),
             RtValue::Pointer(p) => {
                 ctx.bb()
                     .lock()?
@@ -281,6 +317,28 @@ impl RtArgs {
         RtArgs(elems)
     }
 
+    /// writes the value to bb using the pointer that is passed to the output parameter.
+    /// # Notes
+    /// The output arguments are found by the name of the parameter
+    /// and only the parameters declared as `out` or `inout` can be written.
+    pub fn write(&self, key: &str, value: RtValue, ctx: TreeContextRef) -> RtOk {
+        let arg = self
+            .0
+            .iter()
+            .find(|a| a.name == key)
+            .ok_or(RuntimeError::WrongArgument(format!(
+                "the output argument {key} is not found"
+            )))?;
+        match (&arg.dir, &arg.value) {
+            (dir, RtValue::Pointer(bb_key)) if dir.is_out() => {
+                ctx.bb().lock()?.put(bb_key.clone(), value)
+            }
+            (dir, _) => Err(RuntimeError::WrongArgument(format!(
+                "the argument {key} is not a pointer to bb to write to, the direction is {dir}"
+            ))),
+        }
+    }
+
     /// remove from the given list of RtValues another one.
     pub fn remove(self, key: &str) -> RtArgs {
         RtArgs(self.0.into_iter().filter(|v| v.name != key).collect())
@@ -297,10 +355,13 @@ impl Display for RtArgs {
 
 /// The structure that represents the pair of the argument name and the value.
 /// It is used in bb to store the arguments of the tree.
+/// The direction is taken from the parameter and the output arguments are always pointers.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub struct RtArgument {
     pub name: RtAKey,
     pub value: RtValue,
+    #[serde(default)]
+    pub dir: ParamDir,
 }
 
 impl Display for RtValueNumber {
@@ -330,6 +391,7 @@ impl Display for RtValue {
             RtValue::Number(n) => f.write_str(format!("{}", n).as_str())?,
             RtValue::Pointer(p) => f.write_str(format!("&{p}").as_str())?,
             RtValue::Call(_) => f.write_str("<Call>>")?,
+            RtValue::Expr(e) => f.write_str(format!("{e}").as_str())?,
         }
         Ok(())
     }
@@ -353,14 +415,18 @@ impl RtArgument {
 
 impl RtArgument {
     pub fn new(name: RtAKey, value: RtValue) -> Self {
-        Self { name, value }
-    }
-    pub fn new_noname(value: RtValue) -> Self {
         Self {
-            name: "arg".to_string(),
+            name,
             value,
+            dir: ParamDir::In,
         }
     }
+    pub fn new_noname(value: RtValue) -> Self {
+        RtArgument::new("arg".to_string(), value)
+    }
+    pub fn with_dir(self, dir: ParamDir) -> Self {
+        Self { dir, ..self }
+    }
 
     /// tries to convert the argument to the given type
     /// # Notes
@@ -373,14 +439,30 @@ impl RtArgument {
         parent_params: Params,
     ) -> Result<(RtArgument, ArgumentRhs), TreeError> {
         RtArgument::validate_type(a.clone(), p.clone().tpe)?;
-        match &a {
+        let dir = p.dir;
+        let name = p.name.clone();
+        let (rt_arg, rhs) = match &a {
             ArgumentRhs::Id(id) => match find_arg_value(id, &parent_params, &parent_args).ok() {
-                None => Ok((RtArgument::new(p.name, RtValue::Pointer(id.clone())), a)),
-                Some(v) => RtArgument::try_from(v, p, Arguments::default(), Params::default()),
+                None => (RtArgument::new(p.name, RtValue::Pointer(id.clone())), a),
+                Some(v) => RtArgument::try_from(v, p, Arguments::default(), Params::default())?,
             },
-            ArgumentRhs::Mes(m) => Ok((RtArgument::new(p.name, m.clone().into()), a)),
-            ArgumentRhs::Call(c) => Ok((RtArgument::new(p.name, RtValue::Call(c.clone())), a)),
+            ArgumentRhs::Mes(m) => (RtArgument::new(p.name, m.clone().into()), a),
+            ArgumentRhs::Call(c) => (RtArgument::new(p.name, RtValue::Call(c.clone())), a),
+            ArgumentRhs::Expr(e) => {
+                let e = substitute_expr(e.clone(), &parent_params, &parent_args)?;
+                (
+                    RtArgument::new(p.name, RtValue::Expr(e.clone())),
+                    ArgumentRhs::Expr(e),
+                )
+            }
+        };
+        if dir.is_out() && !matches!(rt_arg.value, RtValue::Pointer(_)) {
+            return Err(cerr(format!(
+                "the parameter '{dir} {name}' expects a pointer to bb to write to but got '{}'",
+                rt_arg.value
+            )));
         }
+        Ok((rt_arg.with_dir(dir), rhs))
     }
     /// validates the type of the argument in accordance with the type of the parameter
     pub fn validate_type(arg: ArgumentRhs, param: MesType) -> Result<(), TreeError> {
@@ -398,13 +480,20 @@ impl RtArgument {
 
             (ArgumentRhs::Id(_), _) => Ok(()),
 
-            (ArgumentRhs::Mes(m), m_t) => {
-                if m.same(&m_t) {
-                    Ok(())
-                } else {
-                    error(format!("{}", m).as_str(), format!("{:?}", m_t).as_str())
+            (ArgumentRhs::Expr(e), m_t) => match e.tpe()? {
+                Some(t) if !m_t.compatible(&t) => {
+                    error(format!("{}", e).as_str(), format!("{:?}", m_t).as_str())
                 }
-            }
+                _ => Ok(()),
+            },
+
+            (ArgumentRhs::Mes(m), m_t) => match m.conform(&m_t) {
+                Ok(()) => Ok(()),
+                Err(reason) if matches!(m_t, MesType::ArrayOf(_) | MesType::Struct(..) | MesType::Enum(..)) => Err(
+                    cerr(format!("the argument does not conform to the type '{m_t}' of the definition since {reason}")),
+                ),
+                Err(_) => error(format!("{}", m).as_str(), format!("{:?}", m_t).as_str()),
+            },
         }
     }
 }
@@ -1,10 +1,12 @@
 use crate::runtime::args::{RtArgs, RtArgument, RtValue};
+use crate::tree::parser::ast::arg::ParamDir;
 use crate::tree::parser::ast::call::Call;
 use itertools::Itertools;
 use std::fmt::{Display, Formatter};
 
 /// The short version of arguments to display,
 /// It shows [..] and {..} when the list is too long
+/// and the output arguments as `name->&key`
 #[derive(Debug)]
 pub struct ShortDisplayedRtArguments<'a>(pub &'a RtArgs);
 
@@ -16,14 +18,20 @@ impl<'a> Display for ShortDisplayedRtArgument<'a> {
         let short_mes = |m: &RtValue| match m {
             RtValue::Array(_) => "[..]".to_string(),
             RtValue::Object(_) => "{..}".to_string(),
-            RtValue::Call(Call::Lambda(tpe, _)) => format!("{tpe}.."),
-            RtValue::Call(Call::Decorator(tpe, args, _)) => format!("{tpe}({args})"),
-            RtValue::Call(Call::Invocation(key, _)) => format!("{key}(<>)"),
+            RtValue::Call(Call::Lambda(tpe, ..)) => format!("{tpe}.."),
+            RtValue::Call(Call::Decorator(tpe, args, ..)) => format!("{tpe}({args})"),
+            RtValue::Call(Call::Invocation(key, ..)) => format!("{key}(<>)"),
             m => format!("{}", m),
         };
 
-        let RtArgument { name, value } = &self.0;
-        write!(f, "{}={}", name, short_mes(value))
+        // the arrows show the flow of the data between the action and bb
+        let RtArgument { name, value, dir } = &self.0;
+        let arrow = match dir {
+            ParamDir::In => "=",
+            ParamDir::Out => "->",
+            ParamDir::InOut => "<->",
+        };
+        write!(f, "{}{}{}", name, arrow, short_mes(value))
     }
 }
 
@@ -0,0 +1,168 @@
+use crate::runtime::args::{RtValue, RtValueNumber};
+use crate::runtime::context::TreeContextRef;
+use crate::runtime::{RtResult, RuntimeError};
+use crate::tree::parser::ast::expr::{BinOp, Expr, TemplatePart};
+use crate::tree::parser::ast::Key;
+use std::cmp::Ordering;
+
+/// evaluates the expression taking the values of the ids from the blackboard.
+/// The logical operators are short-circuited.
+///
+/// # Errors
+/// The absent keys in bb and the operands of the incompatible types
+/// turn the result into the failure.
+pub fn eval(expr: &Expr, ctx: &TreeContextRef) -> RtResult<RtValue> {
+    eval_with(expr, &|id| {
+        RtValue::Pointer(id.clone()).with_ptr(ctx.clone())
+    })
+}
+
+/// evaluates the expression that does not have ids, like `3 * 2`.
+pub fn eval_const(expr: &Expr) -> RtResult<RtValue> {
+    eval_with(expr, &|id| {
+        Err(RuntimeError::fail(format!(
+            "the expression '{expr}' refers to {id} that is unknown before the tick"
+        )))
+    })
+}
+
+/// evaluates the expression taking the values of the ids from the given function.
+pub fn eval_with<F>(expr: &Expr, ids: &F) -> RtResult<RtValue>
+where
+    F: Fn(&Key) -> RtResult<RtValue>,
+{
+    match expr {
+        Expr::Id(id) => ids(id),
+        Expr::Mes(m) => Ok(m.clone().into()),
+        Expr::Not(e) => Ok(RtValue::Bool(!eval_bool_with(e, ids)?)),
+        Expr::Template(parts) => {
+            let mut res = String::new();
+            for part in parts {
+                match part {
+                    TemplatePart::Text(t) => res.push_str(t),
+                    TemplatePart::Expr(e) => res.push_str(&eval_with(e, ids)?.to_string()),
+                }
+            }
+            Ok(RtValue::String(res))
+        }
+        Expr::Binary(lhs, BinOp::And, rhs) => Ok(RtValue::Bool(
+            eval_bool_with(lhs, ids)? && eval_bool_with(rhs, ids)?,
+        )),
+        Expr::Binary(lhs, BinOp::Or, rhs) => Ok(RtValue::Bool(
+            eval_bool_with(lhs, ids)? || eval_bool_with(rhs, ids)?,
+        )),
+        Expr::Binary(lhs, op, rhs) if op.is_arithmetic() => {
+            let l = eval_with(lhs, ids)?;
+            let r = eval_with(rhs, ids)?;
+            arithmetic(&l, op, &r)?.ok_or(RuntimeError::fail(format!(
+                "the operands of '{expr}' can not be calculated: {l} {op} {r}"
+            )))
+        }
+        Expr::Binary(lhs, op, rhs) => {
+            let l = eval_with(lhs, ids)?;
+            let r = eval_with(rhs, ids)?;
+            let ord = compare(&l, &r).ok_or(RuntimeError::fail(format!(
+                "the operands of '{expr}' can not be compared: {l} and {r}"
+            )))?;
+            let res = match (op, ord) {
+                (BinOp::Eq, ord) => ord == Some(Ordering::Equal),
+                (BinOp::Ne, ord) => ord != Some(Ordering::Equal),
+                (op, None) => {
+                    return Err(RuntimeError::fail(format!(
+                        "the operands of '{expr}' can not be ordered by {op}: {l} and {r}"
+                    )))
+                }
+                (BinOp::Lt, Some(ord)) => ord.is_lt(),
+                (BinOp::Le, Some(ord)) => ord.is_le(),
+                (BinOp::Gt, Some(ord)) => ord.is_gt(),
+                (_, Some(ord)) => ord.is_ge(),
+            };
+            Ok(RtValue::Bool(res))
+        }
+    }
+}
+
+/// evaluates the expression that is expected to be a bool.
+pub fn eval_bool(expr: &Expr, ctx: &TreeContextRef) -> RtResult<bool> {
+    eval_bool_with(expr, &|id| {
+        RtValue::Pointer(id.clone()).with_ptr(ctx.clone())
+    })
+}
+
+fn eval_bool_with<F>(expr: &Expr, ids: &F) -> RtResult<bool>
+where
+    F: Fn(&Key) -> RtResult<RtValue>,
+{
+    match eval_with(expr, ids)? {
+        RtValue::Bool(b) => Ok(b),
+        v => Err(RuntimeError::fail(format!(
+            "the expression '{expr}' is expected to be a bool but it is {v}"
+        ))),
+    }
+}
+
+fn number(n: &RtValueNumber) -> f64 {
+    match n {
+        RtValueNumber::Int(v) | RtValueNumber::Hex(v) => *v as f64,
+        RtValueNumber::Float(v) => *v,
+        RtValueNumber::Binary(v) => *v as f64,
+    }
+}
+
+fn integer(n: &RtValueNumber) -> Option<i64> {
+    match n {
+        RtValueNumber::Int(v) | RtValueNumber::Hex(v) => Some(*v),
+        RtValueNumber::Binary(v) => Some(*v as i64),
+        RtValueNumber::Float(_) => None,
+    }
+}
+
+/// the strings are concatenated with any value,
+/// the integers stay integers (the division is the integer one) unless there is a float.
+/// Returns none if the values can not be calculated.
+fn arithmetic(lhs: &RtValue, op: &BinOp, rhs: &RtValue) -> RtResult<Option<RtValue>> {
+    let (l, r) = match (lhs, op, rhs) {
+        (RtValue::String(_), BinOp::Add, _) | (_, BinOp::Add, RtValue::String(_)) => {
+            return Ok(Some(RtValue::String(format!("{lhs}{rhs}"))))
+        }
+        (RtValue::Number(l), _, RtValue::Number(r)) => (l, r),
+        _ => return Ok(None),
+    };
+    if let (Some(l), Some(r)) = (integer(l), integer(r)) {
+        let res = match op {
+            BinOp::Add => l.checked_add(r),
+            BinOp::Sub => l.checked_sub(r),
+            BinOp::Mul => l.checked_mul(r),
+            BinOp::Div => l.checked_div(r),
+            _ => l.checked_rem(r),
+        };
+        return res
+            .map(|v| Some(RtValue::int(v)))
+            .ok_or(RuntimeError::fail(format!(
+                "the operation {l} {op} {r} overflows or divides by zero"
+            )));
+    }
+    let (l, r) = (number(l), number(r));
+    let res = match op {
+        BinOp::Add => l + r,
+        BinOp::Sub => l - r,
+        BinOp::Mul => l * r,
+        BinOp::Div => l / r,
+        _ => l % r,
+    };
+    Ok(Some(RtValue::float(res)))
+}
+
+/// the outer option is none if the values have the different types,
+/// the inner option is none if the values of the same type can not be ordered.
+fn compare(lhs: &RtValue, rhs: &RtValue) -> Option<Option<Ordering>> {
+    match (lhs, rhs) {
+        (RtValue::Number(l), RtValue::Number(r)) => Some(number(l).partial_cmp(&number(r))),
+        (RtValue::String(l), RtValue::String(r)) => Some(Some(l.cmp(r))),
+        (RtValue::Bool(l), RtValue::Bool(r)) => Some((l == r).then_some(Ordering::Equal)),
+        (RtValue::Array(_), RtValue::Array(_)) | (RtValue::Object(_), RtValue::Object(_)) => {
+            Some((lhs == rhs).then_some(Ordering::Equal))
+        }
+        _ => None,
+    }
+}
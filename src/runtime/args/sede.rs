@@ -44,6 +44,7 @@ impl Serialize for RtValue {
             }
             RtValue::Pointer(_) => Err(S::Error::custom("pointer can not be serialized")),
             RtValue::Call(_) => Err(S::Error::custom("call can not be serialized")),
+            RtValue::Expr(_) => Err(S::Error::custom("expression can not be serialized")),
         }
     }
 }
@@ -1,9 +1,16 @@
-use crate::runtime::args::{RtArgs, RtArgument, RtValue};
-use crate::runtime::rtree::rnode::DecoratorType;
+use crate::runtime::args::expr::eval_const;
+use crate::runtime::args::{RtArgs, RtArgument, RtValue, RtValueNumber};
+use crate::runtime::forester::flow::{
+    CONCURRENT, FAILURE_THRESHOLD, FIRST_FINISHED, SUCCESS_THRESHOLD,
+};
+use crate::runtime::rtree::rnode::{DecoratorType, FlowType};
 use crate::tree::parser::ast::arg::{
     Argument, ArgumentRhs, Arguments, ArgumentsType, Param, Params,
 };
+use crate::tree::parser::ast::call::{Call, Calls};
+use crate::tree::parser::ast::expr::Expr;
 use crate::tree::parser::ast::message::Message;
+use crate::tree::diagnostic::Span;
 use crate::tree::parser::ast::Key;
 use crate::tree::{cerr, TreeError};
 use std::collections::HashMap;
@@ -27,13 +34,84 @@ fn dec_rt_arg(
             }
         }
         ArgumentRhs::Mes(Message::Num(n)) => Ok(RtValue::Number((*n).into())),
+        // the expression over the parameters like `retry(attempts * 2)` is calculated at once
+        ArgumentRhs::Expr(e) => {
+            let e = substitute_expr(e.clone(), &parent_params, &parent_args)?;
+            match eval_const(&e) {
+                Ok(v @ RtValue::Number(_)) => Ok(v),
+                Ok(v) => Err(cerr(format!(
+                    "decorator expects a number but the expression {e} is {v}"
+                ))),
+                Err(err) => Err(cerr(format!(
+                    "decorator expects the expression over the parameters of the tree: {err:?}"
+                ))),
+            }
+        }
         e => Err(cerr(format!(
-            "decorator has only one argument and it is either id or num but got {}",
+            "decorator has only one argument and it is either id, num or expression but got {}",
             e
         ))),
     }
 }
 
+/// It extracts and validates the arguments of the lambdas.
+/// Only the parallel lambda has the arguments setting the policy like `parallel(success = 2) {...}`,
+/// the thresholds are integers from 1 to the number of children
+/// and can be taken from the parent like the arguments of decorators.
+/// The flags `first` and `concurrent` are booleans and `first` excludes the thresholds.
+/// The tree is the name of the tree the lambda is written down in, to point out the errors.
+pub fn to_lambda_rt_args(
+    tpe: &FlowType,
+    tree: &str,
+    args: Arguments,
+    children: usize,
+    parent_args: Arguments,
+    parent_params: Params,
+) -> Result<RtArgs, TreeError> {
+    if !tpe.is_par() && !args.args.is_empty() {
+        return Err(cerr(format!("the lambda {tpe} does not have arguments")));
+    }
+    let mut rt_args = vec![];
+    for a in args.args.iter() {
+        let name = a.name().map(String::as_str);
+        let value = match (name, a.value()) {
+            (Some(FIRST_FINISHED | CONCURRENT), ArgumentRhs::Mes(Message::Bool(v))) => RtValue::Bool(v.clone().into()),
+            (Some(t @ (SUCCESS_THRESHOLD | FAILURE_THRESHOLD)), v) => {
+                let wrong = |v: String| {
+                    cerr(format!(
+                        "the threshold {t} of parallel in the tree {tree} is expected to be an integer from 1 to the number of children {children} but got {v}"
+                    ))
+                };
+                match dec_rt_arg(v, parent_args.clone(), parent_params.clone()) {
+                    Ok(v @ RtValue::Number(RtValueNumber::Int(n))) if n >= 1 && n as usize <= children => v,
+                    Ok(v) => return Err(wrong(v.to_string())),
+                    Err(_) => return Err(wrong(v.to_string())),
+                }
+            }
+            _ => {
+                return Err(cerr(format!(
+                    "parallel accepts only the arguments {SUCCESS_THRESHOLD}, {FAILURE_THRESHOLD}, {FIRST_FINISHED} and {CONCURRENT} but got {a}"
+                )))
+            }
+        };
+        rt_args.push(RtArgument::new(name.unwrap_or_default().to_string(), value));
+    }
+    let rt_args = RtArgs(rt_args);
+    let first = rt_args
+        .find(FIRST_FINISHED.to_string())
+        .and_then(RtValue::as_bool)
+        .unwrap_or(false);
+    let thresholds = [SUCCESS_THRESHOLD, FAILURE_THRESHOLD]
+        .iter()
+        .any(|t| rt_args.find(t.to_string()).is_some());
+    if first && thresholds {
+        return Err(cerr(format!(
+            "parallel in the tree {tree} accepts either {FIRST_FINISHED} or the thresholds {SUCCESS_THRESHOLD} and {FAILURE_THRESHOLD} but got both"
+        )));
+    }
+    Ok(rt_args)
+}
+
 /// It extracts and validates the arguments for decorators since the contract is fixed.
 /// The parent attributes  are used to find the arguments
 /// that comes from parents as pointer the from `parent(x:num) retry(x) action()`
@@ -72,7 +150,9 @@ pub fn to_dec_rt_args(
 /// It extracts and validates the arguments for decorators since the contract is fixed.
 /// The parent attributes  are used to find the arguments
 /// that comes from parents as pointer the from `parent(x:num) retry(x) action()`
-/// returns the runtime arguments and the arguments that get updated (unfolded pointers)
+/// The omitted arguments are taken from the default values of the parameters
+/// and the omitted optional parameters without the default values are skipped.
+/// returns the runtime arguments and the arguments that get updated (unfolded pointers and defaults)
 pub fn to_rt_args(
     name: &str,
     args: Arguments,
@@ -81,49 +161,73 @@ pub fn to_rt_args(
     p_params: Params,
 ) -> Result<(RtArgs, Arguments), TreeError> {
     let mut rt_args: Vec<RtArgument> = vec![];
+    let mut upd_args = vec![];
+    let to_rt = |rhs: ArgumentRhs, p: Param, span: Span| {
+        RtArgument::try_from(rhs, p, p_args.clone(), p_params.clone())
+            .map_err(|r| r.modify(|s| format!("tree: {}, {}", name, s)).with_span(span))
+    };
+    let default = |p: &Param| match &p.default {
+        Some(m) => Ok(Some(ArgumentRhs::Mes(m.clone()))),
+        None if p.optional => Ok(None),
+        None => Err(cerr(format!(
+            "the call {name} misses the argument for the required parameter '{p}'"
+        ))),
+    };
     match args.get_type()? {
         // we can't traverse the parameters if some of them are skipped
-        ArgumentsType::Unnamed if args.args.len() != params.params.len() => {
-            Err(cerr(format!(
-                "the call {} doesn't have the same number of arguments and parameters",
-                name
-            )))
-        }
-        // find by the index according to the parameters
+        ArgumentsType::Unnamed if args.args.len() > params.params.len() => Err(cerr(format!(
+            "the call {} has more arguments than parameters",
+            name
+        ))),
+        // find by the index according to the parameters,
+        // the tail of the parameters can be omitted if they have the default values
         ArgumentsType::Unnamed => {
-            let mut upd_args = vec![];
-            for (a, p) in args.args.into_iter().zip(params.params) {
+            let mut params = params.params.into_iter();
+            for (a, p) in args.args.into_iter().zip(params.by_ref()) {
                 // if the that is a pointer we need to check parent also.
-                let rhs = a.value().clone();
-                let (rt_arg, upd_rhs) = RtArgument::try_from(rhs, p, p_args.clone(), p_params.clone())
-                    .map_err(|r| r.modify(|s| format!("tree: {}, {}", name, s)))?;
+                let span = a.span();
+                let (rt_arg, upd_rhs) = to_rt(a.value().clone(), p, span)?;
                 rt_args.push(rt_arg);
-                upd_args.push(Argument::Unassigned(upd_rhs));
+                upd_args.push(Argument::Unassigned(upd_rhs, span));
+            }
+            for p in params {
+                if let Some(rhs) = default(&p)? {
+                    let (rt_arg, upd_rhs) = to_rt(rhs, p, Span::default())?;
+                    rt_args.push(rt_arg);
+                    upd_args.push(Argument::Unassigned(upd_rhs, Span::default()));
+                }
             }
             Ok((RtArgs(rt_args), Arguments::new(upd_args)))
         }
         // find by the name according to the parameters
-        ArgumentsType::Named => {
-            let mut upd_args = vec![];
-            let param_map: HashMap<String, Param> =
-                HashMap::from_iter(params.params.into_iter().map(|p| (p.name.clone(), p)));
+        ArgumentsType::Named | ArgumentsType::Empty => {
+            let mut param_map: HashMap<String, Param> =
+                HashMap::from_iter(params.params.iter().map(|p| (p.name.clone(), p.clone())));
 
             for a in args.args {
-                let p = a.name().and_then(|n| param_map.get(n)).ok_or(cerr(format!(
-                    "the argument {a} does not correspond to the definition"
-                )))?;
+                let p = a.name().and_then(|n| param_map.remove(n)).ok_or(
+                    cerr(format!(
+                        "the argument {a} does not correspond to the definition"
+                    ))
+                    .with_span(a.span()),
+                )?;
                 // if the that is a pointer we need to check parent also.
                 let key = a.name().unwrap().to_string();
-                let rhs = a.value().clone();
-                let (rt_arg, upd_rhs) =
-                    RtArgument::try_from(rhs, p.clone(), p_args.clone(), p_params.clone())
-                        .map_err(|r| r.modify(|s| format!("tree: {}, {}", name, s)))?;
+                let span = a.span();
+                let (rt_arg, upd_rhs) = to_rt(a.value().clone(), p, span)?;
                 rt_args.push(rt_arg);
-                upd_args.push(Argument::Assigned(key, upd_rhs));
+                upd_args.push(Argument::Assigned(key, upd_rhs, span));
+            }
+            // the order of the parameters is kept for the omitted ones
+            for p in params.params.iter().filter(|p| param_map.contains_key(&p.name)) {
+                if let Some(rhs) = default(p)? {
+                    let (rt_arg, upd_rhs) = to_rt(rhs, p.clone(), Span::default())?;
+                    rt_args.push(rt_arg);
+                    upd_args.push(Argument::Assigned(p.name.clone(), upd_rhs, Span::default()));
+                }
             }
             Ok((RtArgs(rt_args), Arguments::new(upd_args)))
         }
-        ArgumentsType::Empty => Ok((RtArgs(rt_args), args)),
     }
 }
 
@@ -154,3 +258,85 @@ pub fn find_arg_value(
         ArgumentsType::Empty => Err(cerr("the arguments are empty".to_string())),
     }
 }
+
+/// replaces the ids of the expression that refer to the parent parameters with the given values.
+/// The rest of the ids are considered as the pointers to bb.
+pub fn substitute_expr(expr: Expr, params: &Params, args: &Arguments) -> Result<Expr, TreeError> {
+    expr.try_map_ids(&|id| match find_arg_value(&id, params, args).ok() {
+        None => Ok(Expr::Id(id)),
+        Some(ArgumentRhs::Id(p)) => Ok(Expr::Id(p)),
+        Some(ArgumentRhs::Mes(m)) => Ok(Expr::Mes(m)),
+        Some(ArgumentRhs::Expr(e)) => Ok(e),
+        Some(ArgumentRhs::Call(_)) => Err(cerr(format!(
+            "the parameter {id} is a call and can not be used in the expression"
+        ))),
+    })
+}
+
+/// replaces the ids that refer to the constants or the variants of the enums with their values
+/// including the ids in the expressions and in the arguments of the calls passed further.
+/// The parameters of the enclosing tree shadow the constants.
+pub fn inline_consts<F>(
+    args: Arguments,
+    params: &Params,
+    consts: &F,
+) -> Result<Arguments, TreeError>
+where
+    F: Fn(&Key) -> Result<Option<Message>, TreeError>,
+{
+    let is_param = |id: &Key| params.params.iter().any(|p| &p.name == id);
+    let inline_rhs = |rhs: ArgumentRhs| match rhs {
+        ArgumentRhs::Id(id) if !is_param(&id) => Ok(consts(&id)?
+            .map(ArgumentRhs::Mes)
+            .unwrap_or(ArgumentRhs::Id(id))),
+        ArgumentRhs::Expr(e) => e
+            .try_map_ids(&|id| {
+                let value = if is_param(&id) { None } else { consts(&id)? };
+                Ok(value.map(Expr::Mes).unwrap_or(Expr::Id(id)))
+            })
+            .map(ArgumentRhs::Expr),
+        ArgumentRhs::Call(c) => inline_call(c, params, consts).map(ArgumentRhs::Call),
+        rhs => Ok(rhs),
+    };
+    args.args
+        .into_iter()
+        .map(|a| match a {
+            Argument::Assigned(k, rhs, span) => Ok(Argument::Assigned(k, inline_rhs(rhs)?, span)),
+            Argument::Unassigned(rhs, span) => Ok(Argument::Unassigned(inline_rhs(rhs)?, span)),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Arguments::new)
+}
+
+fn inline_call<F>(call: Call, params: &Params, consts: &F) -> Result<Call, TreeError>
+where
+    F: Fn(&Key) -> Result<Option<Message>, TreeError>,
+{
+    match call {
+        Call::Invocation(name, args, span) => Ok(Call::Invocation(
+            name,
+            inline_consts(args, params, consts)?,
+            span,
+        )),
+        Call::Decorator(tpe, args, child, span) => Ok(Call::Decorator(
+            tpe,
+            inline_consts(args, params, consts)?,
+            Box::new(inline_call(*child, params, consts)?),
+            span,
+        )),
+        Call::Lambda(tpe, args, calls, span) => {
+            let elems = calls
+                .elems
+                .into_iter()
+                .map(|c| inline_call(c, params, consts))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Call::Lambda(
+                tpe,
+                inline_consts(args, params, consts)?,
+                Calls::new(elems),
+                span,
+            ))
+        }
+        call => Ok(call),
+    }
+}
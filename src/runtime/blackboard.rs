@@ -3,6 +3,7 @@ pub mod utils;
 use crate::read_file;
 use crate::runtime::args::RtValue;
 use crate::runtime::blackboard::BBValue::{Locked, Taken, Unlocked};
+use crate::runtime::forester::schedule::{TickEvent, TickSignal, Ticking};
 use crate::runtime::{RtOk, RtResult, RuntimeError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -38,9 +39,21 @@ pub enum BBValue {
 /// - lock/unlock the value in the cell.
 /// - take the value in the cell
 ///
-#[derive(Default, Debug, PartialEq, Serialize, Deserialize)]
+///
+/// Every change of a cell notifies the signal to start the next tick if it is set,
+/// except the changes the tree makes itself while it is ticked,
+/// see [`crate::runtime::forester::schedule::TickMode::OnChange`].
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct BlackBoard {
     storage: HashMap<BBKey, BBValue>,
+    #[serde(skip)]
+    signal: Option<TickSignal>,
+}
+
+impl PartialEq for BlackBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.storage == other.storage
+    }
 }
 
 impl BlackBoard {
@@ -54,6 +67,7 @@ impl BlackBoard {
         match v {
             Some(Unlocked(v)) => {
                 self.storage.insert(key, Locked(v.clone()));
+                self.changed();
                 Ok(())
             }
             Some(Locked(_)) => Ok(()),
@@ -82,6 +96,7 @@ impl BlackBoard {
         match v {
             Some(Locked(v)) => {
                 self.storage.insert(key, Unlocked(v.clone()));
+                self.changed();
                 Ok(())
             }
             _ => Ok(()),
@@ -116,6 +131,7 @@ impl BlackBoard {
             Some(Unlocked(v)) => {
                 let v = v.clone();
                 self.storage.insert(key, Taken);
+                self.changed();
                 Ok(v)
             }
         }
@@ -139,6 +155,7 @@ impl BlackBoard {
             Some(Locked(_)) => Err(RuntimeError::bb(format!("the key {key} is locked"))),
             _ => {
                 self.storage.insert(key, Unlocked(value));
+                self.changed();
                 Ok(())
             }
         }
@@ -148,6 +165,18 @@ impl BlackBoard {
         debug!(target:"bb", "create an empty bb");
         Self {
             storage: HashMap::from_iter(elems),
+            signal: None,
+        }
+    }
+
+    /// Sets the signal that is notified on every change.
+    pub(crate) fn watch(&mut self, signal: TickSignal) {
+        self.signal = Some(signal);
+    }
+
+    fn changed(&self) {
+        if let Some(signal) = self.signal.as_ref().filter(|_| !Ticking::is_ticking()) {
+            signal.notify(TickEvent::BlackBoardChanged);
         }
     }
 }
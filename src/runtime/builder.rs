@@ -1,6 +1,8 @@
+pub mod artifact_builder;
 pub mod builtin;
 pub mod custom_builder;
 pub mod file_builder;
+pub mod modules;
 pub mod text_builder;
 pub mod ros_nav;
 pub mod ros_core;
@@ -10,22 +12,29 @@ use crate::get_pb;
 use crate::runtime::action::keeper::{ActionImpl, ActionKeeper};
 use crate::runtime::action::{Action, ActionName, Impl, ImplAsync, ImplRemote};
 use crate::runtime::blackboard::BlackBoard;
+use crate::runtime::builder::artifact_builder::ArtifactForesterBuilder;
 use crate::runtime::builder::custom_builder::CustomForesterBuilder;
 use crate::runtime::builder::file_builder::FileForesterBuilder;
+use crate::runtime::builder::modules::StdModule;
 use crate::runtime::builder::text_builder::TextForesterBuilder;
 use crate::runtime::env::RtEnv;
 
+use crate::runtime::forester::schedule::{Scheduler, TickMode};
 use crate::runtime::forester::{serv, Forester};
+use crate::runtime::rtree::artifact::TreeArtifact;
 use crate::runtime::rtree::builder::RtNodeBuilder;
 use crate::runtime::rtree::rnode::RNodeId;
 use crate::runtime::rtree::{RuntimeTree, RuntimeTreeStarter};
 use crate::runtime::{RtOk, RtResult, RuntimeError};
 use crate::tracer::Tracer;
-use crate::tree::project::{FileName, TreeName};
+use crate::tree::project::lint::{self, LintConfig, LintReport};
+use crate::tree::project::{FileName, Project, TreeName};
+use crate::tree::TreeError;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use crate::runtime::env::daemon::{DaemonName, Daemon};
 use crate::runtime::env::daemon::context::DaemonContext;
 
@@ -97,6 +106,11 @@ pub enum ForesterBuilder {
         delegate: CustomForesterBuilder,
         error: Option<String>,
     },
+    Artifact {
+        cfb: CommonForesterBuilder,
+        delegate: ArtifactForesterBuilder,
+        error: Option<String>,
+    },
 }
 
 impl ForesterBuilder {
@@ -121,6 +135,13 @@ impl ForesterBuilder {
             error: None,
         }
     }
+    pub fn from_artifact() -> ForesterBuilder {
+        ForesterBuilder::Artifact {
+            cfb: CommonForesterBuilder::new(),
+            delegate: ArtifactForesterBuilder::new(),
+            error: None,
+        }
+    }
 
     /// Root folder.
     pub fn root(&mut self, root: PathBuf) {
@@ -128,7 +149,9 @@ impl ForesterBuilder {
             ForesterBuilder::Files { delegate, .. } => {
                 delegate.root(root);
             }
-            ForesterBuilder::Text { error, .. } | ForesterBuilder::Code { error, .. } => {
+            ForesterBuilder::Text { error, .. }
+            | ForesterBuilder::Code { error, .. }
+            | ForesterBuilder::Artifact { error, .. } => {
                 let _ = error.insert("This type of builder does not accept root folder. Only `from_file_system` builder accept it.".to_string());
             }
         }
@@ -139,7 +162,9 @@ impl ForesterBuilder {
             ForesterBuilder::Files { delegate, .. } => {
                 delegate.main_file(main_file);
             }
-            ForesterBuilder::Text { error, .. } | ForesterBuilder::Code { error, .. } => {
+            ForesterBuilder::Text { error, .. }
+            | ForesterBuilder::Code { error, .. }
+            | ForesterBuilder::Artifact { error, .. } => {
                 let _ = error.insert("This type of builder does not accept main_file. Only `from_file_system` builder accept it.".to_string());
             }
         }
@@ -150,12 +175,57 @@ impl ForesterBuilder {
             ForesterBuilder::Files { delegate, .. } => {
                 delegate.main_tree(main_tree);
             }
-            ForesterBuilder::Text { error, .. } | ForesterBuilder::Code { error, .. } => {
+            ForesterBuilder::Text { error, .. }
+            | ForesterBuilder::Code { error, .. }
+            | ForesterBuilder::Artifact { error, .. } => {
                 let _ = error.insert("This type of builder does not accept main_tree. Only `from_file_system` builder accept it.".to_string());
             }
         }
     }
 
+    /// A folder to look up the libraries in, like `import "nav::approach.tree"`
+    /// is looked up as `nav/approach.tree` in the folder.
+    pub fn search_path(&mut self, path: PathBuf) {
+        match self {
+            ForesterBuilder::Files { delegate, .. } => {
+                delegate.search_path(path);
+            }
+            ForesterBuilder::Text { error, .. }
+            | ForesterBuilder::Code { error, .. }
+            | ForesterBuilder::Artifact { error, .. } => {
+                let _ = error.insert("This type of builder does not accept search paths. Only `from_file_system` builder accept it.".to_string());
+            }
+        }
+    }
+    /// A folder of the library with the given name, like `import "nav::approach.tree"`
+    /// is looked up as `approach.tree` in the folder.
+    pub fn library(&mut self, name: &str, dir: PathBuf) {
+        match self {
+            ForesterBuilder::Files { delegate, .. } => {
+                delegate.library(name, dir);
+            }
+            ForesterBuilder::Text { error, .. }
+            | ForesterBuilder::Code { error, .. }
+            | ForesterBuilder::Artifact { error, .. } => {
+                let _ = error.insert("This type of builder does not accept libraries. Only `from_file_system` builder accept it.".to_string());
+            }
+        }
+    }
+
+    /// The module that is imported like the built-in ones, e.g. `import "mycompany::arm"`.
+    /// The module provides the declarations and the implementations of its actions.
+    pub fn register_module<M: StdModule + 'static>(&mut self, module: M) {
+        let res = match self {
+            ForesterBuilder::Files { delegate, .. } => delegate.register_module(module),
+            ForesterBuilder::Text { delegate, .. } => delegate.register_module(module),
+            ForesterBuilder::Artifact { delegate, .. } => delegate.register_module(module),
+            ForesterBuilder::Code { .. } => Err(RuntimeError::Unexpected("This type of builder does not accept modules. Only `from_file_system` and `from_text` builders accept it.".to_string())),
+        };
+        if let Err(e) = res {
+            self.set_error(e);
+        }
+    }
+
     /// add script on the fly.
     /// In that scenario, there is no need in the other attributes like files or root.
     ///
@@ -166,7 +236,9 @@ impl ForesterBuilder {
             ForesterBuilder::Text { delegate, .. } => {
                 delegate.text(txt);
             }
-            ForesterBuilder::Files { error, .. } | ForesterBuilder::Code { error, .. } => {
+            ForesterBuilder::Files { error, .. }
+            | ForesterBuilder::Code { error, .. }
+            | ForesterBuilder::Artifact { error, .. } => {
                 let _ = error.insert("This type of builder does not accept code as text. Only `from_text` builder accept it.".to_string());
             }
         }
@@ -175,13 +247,40 @@ impl ForesterBuilder {
     pub fn add_rt_node(&mut self, node_b: RtNodeBuilder) -> RNodeId {
         match self {
             ForesterBuilder::Code { delegate, .. } => delegate.add_rt_node(node_b),
-            ForesterBuilder::Files { error, .. } | ForesterBuilder::Text { error, .. } => {
+            ForesterBuilder::Files { error, .. }
+            | ForesterBuilder::Text { error, .. }
+            | ForesterBuilder::Artifact { error, .. } => {
                 let _ = error.insert("This type of builder does not accept code as text. Only `from_text` builder accept it.".to_string());
                 0
             }
         }
     }
 
+    /// A file of the compiled runtime tree, see [`TreeArtifact`].
+    pub fn artifact(&mut self, path: PathBuf) {
+        match self {
+            ForesterBuilder::Artifact { delegate, .. } => delegate.artifact(path),
+            ForesterBuilder::Files { error, .. }
+            | ForesterBuilder::Text { error, .. }
+            | ForesterBuilder::Code { error, .. } => {
+                let _ = error.insert("This type of builder does not accept artifacts. Only `from_artifact` builder accept it.".to_string());
+            }
+        }
+    }
+
+    /// The expected hash of the artifact.
+    /// The artifact with another hash is rejected, see [`TreeArtifact::hash`].
+    pub fn artifact_hash(&mut self, hash: String) {
+        match self {
+            ForesterBuilder::Artifact { delegate, .. } => delegate.hash(hash),
+            ForesterBuilder::Files { error, .. }
+            | ForesterBuilder::Text { error, .. }
+            | ForesterBuilder::Code { error, .. } => {
+                let _ = error.insert("This type of builder does not accept artifacts. Only `from_artifact` builder accept it.".to_string());
+            }
+        }
+    }
+
 
     /// Add a daemon
     pub fn register_daemon(&mut self, daemon: Daemon) {
@@ -237,6 +336,81 @@ impl ForesterBuilder {
         self.cfb().rt_env(env);
     }
 
+    /// How the engine proceeds to the next tick.
+    /// By default, the next tick starts right after the previous one.
+    /// The mode `Rate` with the rate that is not a positive number fails the build.
+    pub fn tick_mode(&mut self, mode: TickMode) {
+        self.cfb().tick_mode(mode);
+    }
+
+    /// The longest time the engine waits for the events in the modes
+    /// [`TickMode::OnTaskFinished`] and [`TickMode::OnChange`].
+    /// The next tick starts after it even if nothing has happened.
+    /// By default, the engine waits without a limit.
+    pub fn max_tick_wait(&mut self, max_wait: Duration) {
+        self.cfb().max_tick_wait(max_wait);
+    }
+
+    /// The trees invoking themselves get expanded at most `depth` times along every branch
+    /// instead of being an error. The invocation exceeding the depth fails.
+    pub fn recursion_depth(&mut self, depth: usize) {
+        match self {
+            ForesterBuilder::Files { cfb, .. } | ForesterBuilder::Text { cfb, .. } => {
+                cfb.recursion_depth(depth);
+            }
+            ForesterBuilder::Code { error, .. } | ForesterBuilder::Artifact { error, .. } => {
+                let _ = error.insert("This type of builder does not accept the recursion depth. Only `from_file_system` and `from_text` builders accept it.".to_string());
+            }
+        }
+    }
+
+    /// The subtrees are instantiated only when their parents tick them for the first time
+    /// instead of expanding the whole tree up front, see [`RuntimeTree::build_lazy`].
+    pub fn lazy(&mut self) {
+        match self {
+            ForesterBuilder::Files { cfb, .. } | ForesterBuilder::Text { cfb, .. } => {
+                cfb.lazy();
+            }
+            ForesterBuilder::Code { error, .. } | ForesterBuilder::Artifact { error, .. } => {
+                let _ = error.insert("This type of builder does not accept the lazy expansion. Only `from_file_system` and `from_text` builders accept it.".to_string());
+            }
+        }
+    }
+
+    /// Runs the lint pass over the project along with the check
+    /// that every action of the tree has a registered implementation.
+    /// The lints do not prevent the tree from being built.
+    pub fn lint(&self, config: &LintConfig) -> RtResult<LintReport> {
+        self.error()?;
+        let (project, cfb) = match self {
+            ForesterBuilder::Files { delegate, cfb, .. } => (delegate.project()?, cfb),
+            ForesterBuilder::Text { delegate, cfb, .. } => (delegate.project()?, cfb),
+            ForesterBuilder::Code { .. } | ForesterBuilder::Artifact { .. } => return Err(RuntimeError::Unexpected("This type of builder does not accept lints. Only `from_file_system` and `from_text` builders accept it.".to_string())),
+        };
+        let mut report = lint::check(&project, config);
+        let registered: HashSet<ActionName> = cfb.actions.keys().cloned().collect();
+        let mut starter = cfb.runtime_tree(project.clone())?;
+        starter.tree.instantiate_all()?;
+        report.extend(lint::check_actions(&project, &starter, &registered, config));
+        Ok(report)
+    }
+
+    /// Compiles the project into the artifact that can be loaded by `from_artifact` builder
+    /// without parsing and expanding the project again.
+    /// The lazy expansion, if it is set, is carried out entirely.
+    pub fn compile(&self) -> RtResult<TreeArtifact> {
+        self.error()?;
+        let (project, cfb) = match self {
+            ForesterBuilder::Files { delegate, cfb, .. } => (delegate.project()?, cfb),
+            ForesterBuilder::Text { delegate, cfb, .. } => (delegate.project()?, cfb),
+            ForesterBuilder::Code { .. } | ForesterBuilder::Artifact { .. } => return Err(RuntimeError::Unexpected("This type of builder does not compile artifacts. Only `from_file_system` and `from_text` builders accept it.".to_string())),
+        };
+        project.typecheck()?;
+        let mut starter = cfb.runtime_tree(project)?;
+        starter.tree.instantiate_all()?;
+        TreeArtifact::compile(&starter)
+    }
+
     /// The method to build forester
     pub fn build(self) -> RtResult<Forester> {
         self.build_with(|| ActionImpl::Absent)
@@ -258,23 +432,24 @@ impl ForesterBuilder {
             env,
             bb_load,
             root,
-            port
+            port,
+            scheduler
         ) = match self {
             ForesterBuilder::Files { delegate, cfb, .. } => {
                 let root = delegate.root.clone();
                 let project = delegate.build()?;
+                project.typecheck()?;
+                let modules = project.libs.modules.clone();
                 let RuntimeTreeStarter {
                     tree,
                     std_actions,
                     actions,
-                } = RuntimeTree::build(project)?;
+                } = cfb.runtime_tree(project)?;
                 let mut impl_actions = cfb.actions;
 
                 for (action_name, file_name) in std_actions.iter() {
-                    impl_actions.insert(
-                        action_name.clone(),
-                        builtin::pick_action(action_name, file_name)?,
-                    );
+                    impl_actions
+                        .insert(action_name.clone(), modules.action(action_name, file_name)?);
                 }
                 (
                     tree,
@@ -286,18 +461,21 @@ impl ForesterBuilder {
                     cfb.bb_load,
                     root,
                     cfb.port,
+                    Scheduler::new(cfb.tick_mode, cfb.max_tick_wait)?,
                 )
             }
             ForesterBuilder::Text { delegate, cfb, .. } => {
                 let project = delegate.build()?;
+                project.typecheck()?;
+                let modules = project.libs.modules.clone();
                 let RuntimeTreeStarter {
                     tree,
                     std_actions,
                     actions,
-                } = RuntimeTree::build(project)?;
+                } = cfb.runtime_tree(project)?;
                 let mut impl_actions = cfb.actions;
                 for (action_name, file_name) in std_actions.iter() {
-                    let action = builtin::pick_action(action_name, file_name)?;
+                    let action = modules.action(action_name, file_name)?;
                     impl_actions.insert(action_name.clone(), action);
                 }
                 (
@@ -310,6 +488,34 @@ impl ForesterBuilder {
                     cfb.bb_load,
                     None,
                     cfb.port,
+                    Scheduler::new(cfb.tick_mode, cfb.max_tick_wait)?,
+                )
+            }
+            ForesterBuilder::Artifact { delegate, cfb, .. } => {
+                let (
+                    RuntimeTreeStarter {
+                        tree,
+                        std_actions,
+                        actions,
+                    },
+                    modules,
+                ) = delegate.build()?;
+                let mut impl_actions = cfb.actions;
+                for (action_name, file_name) in std_actions.iter() {
+                    let action = modules.action(action_name, file_name)?;
+                    impl_actions.insert(action_name.clone(), action);
+                }
+                (
+                    tree,
+                    impl_actions,
+                    actions,
+                    cfb.daemons,
+                    cfb.tracer,
+                    cfb.env,
+                    cfb.bb_load,
+                    None,
+                    cfb.port,
+                    Scheduler::new(cfb.tick_mode, cfb.max_tick_wait)?,
                 )
             }
             ForesterBuilder::Code { delegate, cfb, .. } => {
@@ -324,6 +530,7 @@ impl ForesterBuilder {
                     cfb.bb_load,
                     None,
                     cfb.port,
+                    Scheduler::new(cfb.tick_mode, cfb.max_tick_wait)?,
                 )
             }
         };
@@ -364,21 +571,33 @@ impl ForesterBuilder {
 
         let keeper = ActionKeeper::new_with(actions, action_names, default_action)?;
 
-        Forester::new(tree, bb, tracer, keeper, env, serv)
+        Forester::new(tree, bb, tracer, keeper, env, serv, scheduler)
     }
 
     fn cfb(&mut self) -> &mut CommonForesterBuilder {
         match self {
             ForesterBuilder::Files { cfb, .. }
             | ForesterBuilder::Text { cfb, .. }
-            | ForesterBuilder::Code { cfb, .. } => cfb,
+            | ForesterBuilder::Code { cfb, .. }
+            | ForesterBuilder::Artifact { cfb, .. } => cfb,
+        }
+    }
+    fn set_error(&mut self, e: RuntimeError) {
+        match self {
+            ForesterBuilder::Files { error, .. }
+            | ForesterBuilder::Text { error, .. }
+            | ForesterBuilder::Code { error, .. }
+            | ForesterBuilder::Artifact { error, .. } => {
+                let _ = error.insert(format!("{e:?}"));
+            }
         }
     }
     fn error(&self) -> RtOk {
         match &self {
             ForesterBuilder::Files { error: Some(v), .. }
             | ForesterBuilder::Text { error: Some(v), .. }
-            | ForesterBuilder::Code { error: Some(v), .. } => {
+            | ForesterBuilder::Code { error: Some(v), .. }
+            | ForesterBuilder::Artifact { error: Some(v), .. } => {
                 Err(RuntimeError::Unexpected(v.to_string()))
             }
             _ => Ok(()),
@@ -393,6 +612,10 @@ pub struct CommonForesterBuilder {
     actions: HashMap<ActionName, Action>,
     daemons: Vec<DaemonTaskCfg>,
     port: ServerPort,
+    recursion_depth: Option<usize>,
+    lazy: bool,
+    tick_mode: TickMode,
+    max_tick_wait: Option<Duration>,
 }
 
 impl CommonForesterBuilder {
@@ -404,6 +627,10 @@ impl CommonForesterBuilder {
             actions: HashMap::new(),
             daemons: Vec::new(),
             port: ServerPort::None,
+            recursion_depth: None,
+            lazy: false,
+            tick_mode: TickMode::default(),
+            max_tick_wait: None,
         }
     }
 
@@ -426,7 +653,7 @@ impl CommonForesterBuilder {
             A: Impl + 'static,
     {
         self.actions
-            .insert(name.to_string(), Action::Sync(Box::new(action)));
+            .insert(name.to_string(), Action::Sync(Arc::new(action)));
     }
     /// Add an sync action according to the name.
     pub fn register_async_action<A>(&mut self, name: &str, action: A)
@@ -463,6 +690,33 @@ impl CommonForesterBuilder {
     pub fn rt_env(&mut self, env: RtEnv) {
         self.env = Some(env);
     }
+
+    /// How the engine proceeds to the next tick
+    pub fn tick_mode(&mut self, mode: TickMode) {
+        self.tick_mode = mode;
+    }
+
+    /// The longest time the engine waits for the events before the next tick
+    pub fn max_tick_wait(&mut self, max_wait: Duration) {
+        self.max_tick_wait = Some(max_wait);
+    }
+
+    /// The depth the recursive trees get expanded to
+    pub fn recursion_depth(&mut self, depth: usize) {
+        self.recursion_depth = Some(depth);
+    }
+
+    pub fn lazy(&mut self) {
+        self.lazy = true;
+    }
+
+    fn runtime_tree(&self, project: Project) -> Result<RuntimeTreeStarter, TreeError> {
+        match (self.lazy, self.recursion_depth) {
+            (true, depth) => RuntimeTree::build_lazy(project, depth),
+            (false, Some(depth)) => RuntimeTree::build_with_depth(project, depth),
+            (false, None) => RuntimeTree::build(project),
+        }
+    }
 }
 
 /// The struct defines the information of the server.
@@ -0,0 +1,54 @@
+use crate::runtime::builder::modules::{StdModule, StdModules};
+use crate::runtime::rtree::artifact::TreeArtifact;
+use crate::runtime::rtree::RuntimeTreeStarter;
+use crate::runtime::{RtOk, RtResult, RuntimeError};
+use std::path::PathBuf;
+
+/// The builder to build Forester from the compiled runtime tree.
+#[derive(Default)]
+pub struct ArtifactForesterBuilder {
+    artifact: Option<PathBuf>,
+    hash: Option<String>,
+    modules: StdModules,
+}
+
+impl ArtifactForesterBuilder {
+    pub fn new() -> Self {
+        Self {
+            artifact: None,
+            hash: None,
+            modules: StdModules::default(),
+        }
+    }
+
+    /// A file of the artifact
+    pub fn artifact(&mut self, path: PathBuf) {
+        self.artifact = Some(path);
+    }
+    /// The expected hash of the artifact
+    pub fn hash(&mut self, hash: String) {
+        self.hash = Some(hash);
+    }
+    /// The module providing the implementations of the actions the artifact takes from it
+    pub fn register_module<M: StdModule + 'static>(&mut self, module: M) -> RtOk {
+        self.modules.register(module)
+    }
+
+    /// Loads the artifact and checks the hash if it is given.
+    /// Returns the runtime tree along with the modules for the actions.
+    pub fn build(self) -> RtResult<(RuntimeTreeStarter, StdModules)> {
+        let path = self.artifact.ok_or(RuntimeError::WrongArgument(
+            "the file of the artifact is not set".to_string(),
+        ))?;
+        let artifact = TreeArtifact::load(&path)?;
+        if let Some(expected) = self.hash {
+            let actual = artifact.hash()?;
+            if actual != expected {
+                return Err(RuntimeError::IOError(format!(
+                    "the hash of the artifact {actual} does not match the expected one {expected}"
+                )));
+            }
+        }
+        Ok((artifact.starter(), self.modules))
+    }
+}
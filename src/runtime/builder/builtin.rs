@@ -1,20 +1,25 @@
-use crate::runtime::action::builtin::data::{CheckEq, LockUnlockBBKey, Locked, StoreData, StoreTick, TestBool, Less};
+use crate::runtime::action::builtin::data::{CheckEq, CheckExpr, LockUnlockBBKey, Locked, StoreData, StoreTick, TestBool, Less};
 use crate::runtime::action::builtin::http::HttpGet;
 use crate::runtime::action::builtin::ReturnResult;
 use crate::runtime::action::{Action, ActionName};
 use crate::runtime::{RtResult, RuntimeError};
 use crate::runtime::action::builtin::daemon::{CheckDaemonAction, StopDaemonAction};
-use crate::runtime::builder::{ros_core, ros_nav};
+use crate::runtime::builder::modules::StdModule;
 use crate::tree::project::FileName;
 
 
-pub(super) fn pick_action(action: &ActionName, file: &FileName) -> RtResult<Action> {
+/// The built-in actions accessible using the import `import "std::actions"`
+pub struct StdActions;
 
-    match file.as_str() {
-        "std::actions" => action_impl(action),
-        "ros::nav2" => ros_nav::action_impl(action),
-        "ros::core" => ros_core::action_impl(action),
-        _ => Err(RuntimeError::UnImplementedAction(format!("{}::{}", file, action)))
+impl StdModule for StdActions {
+    fn name(&self) -> FileName {
+        "std::actions".to_string()
+    }
+    fn text(&self) -> String {
+        builtin_actions_file()
+    }
+    fn action(&self, action: &ActionName) -> RtResult<Action> {
+        action_impl(action)
     }
 }
 
@@ -31,6 +36,7 @@ fn action_impl(action: &ActionName) -> RtResult<Action> {
         "equal" => Ok(Action::sync(CheckEq)),
         "less" => Ok(Action::sync(Less)),
         "test" => Ok(Action::sync(TestBool)),
+        "check" => Ok(Action::sync(CheckExpr)),
         "store_tick" => Ok(Action::sync(StoreTick)),
         "http_get" => Ok(Action::sync(HttpGet)),
         "http_get_async" => Ok(Action::a_sync(HttpGet)),
@@ -87,6 +93,14 @@ impl less(lhs:num, rhs:num);
 // - Returns Fail(reason) if there is no cell in bbe with the given key.
 impl test(key:string);
 
+// Evaluates the inline expression over the keys in bb, the parameters and the literals:
+// - Returns Result::Success if the expression is true
+// - Returns Fail(reason) with the values of the keys if it is false
+// - Returns Fail(reason) if there is no cell in bb with the given key or the types do not match.
+// The expression supports ==, !=, <, <=, >, >=, &&, || , !, the arithmetic and the parentheses
+// like check(battery < 20 && mode == "auto")
+cond check(expr:bool);
+
 /// Store the current tick
 impl store_tick(name:string);
 
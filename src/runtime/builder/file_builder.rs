@@ -1,4 +1,6 @@
-use crate::runtime::{RtResult, RuntimeError};
+use crate::runtime::builder::modules::StdModule;
+use crate::runtime::{RtOk, RtResult, RuntimeError};
+use crate::tree::project::libs::Libraries;
 use crate::tree::project::{FileName, Project, TreeName};
 use std::path::PathBuf;
 
@@ -7,6 +9,7 @@ pub struct FileForesterBuilder {
     main_file: Option<FileName>,
     main: Option<TreeName>,
     pub(super) root: Option<PathBuf>,
+    libs: Libraries,
 }
 
 impl FileForesterBuilder {
@@ -15,6 +18,7 @@ impl FileForesterBuilder {
             main_file: None,
             main: None,
             root: None,
+            libs: Libraries::default(),
         }
     }
 
@@ -31,11 +35,37 @@ impl FileForesterBuilder {
         self.main = Some(main_tree);
     }
 
-    /// The method to build forester
+    /// A folder to look up the libraries in
+    pub fn search_path(&mut self, path: PathBuf) {
+        self.libs.search_path(path);
+    }
+    /// A folder of the library with the given name
+    pub fn library(&mut self, name: &str, dir: PathBuf) {
+        self.libs.library(name, dir);
+    }
+    /// The module that can be imported from the files
+    pub fn register_module<M: StdModule + 'static>(&mut self, module: M) -> RtOk {
+        self.libs.modules.register(module)
+    }
+
+    /// The method to build forester.
     pub fn build(self) -> RtResult<Project> {
-        match (self.main, self.root.clone(), self.main_file) {
-            (None, Some(root), Some(mf)) => Ok(Project::build(mf, root)?),
-            (Some(mt), Some(root), Some(mf)) => Ok(Project::build_with_root(mf, mt, root)?),
+        self.project()
+    }
+
+    /// The project of the files.
+    /// The libraries set explicitly are merged over the ones from the manifest in the root folder.
+    pub fn project(&self) -> RtResult<Project> {
+        match (&self.main, &self.root, &self.main_file) {
+            (mt, Some(root), Some(mf)) => {
+                let libs = Libraries::load(root)?.merge(self.libs.clone());
+                Ok(Project::build_with_libs(
+                    mf.clone(),
+                    mt.clone(),
+                    root.clone(),
+                    libs,
+                )?)
+            }
             _ => Err(RuntimeError::UnImplementedAction(
                 "not enough arguments to initialize the project".to_string(),
             )),
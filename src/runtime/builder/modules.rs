@@ -0,0 +1,119 @@
+use crate::runtime::action::{Action, ActionName};
+use crate::runtime::builder::builtin::StdActions;
+use crate::runtime::builder::ros_core::RosCore;
+use crate::runtime::builder::ros_nav::RosNav;
+use crate::runtime::{RtOk, RtResult, RuntimeError};
+use crate::tree::project::FileName;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// The virtual file shipped from the code along with the implementations of its actions.
+/// The module is imported by its name like `import "std::actions"`.
+///
+/// # Example
+/// ```
+/// use forester_rs::runtime::action::{Action, ActionName};
+/// use forester_rs::runtime::action::builtin::ReturnResult;
+/// use forester_rs::runtime::builder::modules::StdModule;
+/// use forester_rs::runtime::{RtResult, RuntimeError};
+///
+/// struct Arm;
+///
+/// impl StdModule for Arm {
+///     fn name(&self) -> String {
+///         "mycompany::arm".to_string()
+///     }
+///     fn text(&self) -> String {
+///         "impl grasp(force:num);".to_string()
+///     }
+///     fn action(&self, action: &ActionName) -> RtResult<Action> {
+///         match action.as_str() {
+///             "grasp" => Ok(Action::sync(ReturnResult::success())),
+///             _ => Err(RuntimeError::UnImplementedAction(format!("mycompany::arm::{action}"))),
+///         }
+///     }
+/// }
+/// ```
+pub trait StdModule: Send + Sync {
+    /// the name of the module to import, namely `lib::module`
+    fn name(&self) -> FileName;
+    /// the text of the module with the declarations of the actions and the trees
+    fn text(&self) -> String;
+    /// the implementation of the action declared in the module
+    fn action(&self, action: &ActionName) -> RtResult<Action>;
+}
+
+/// The registry of the modules.
+/// By default, it has the built-in modules `std::actions`, `ros::nav2` and `ros::core`.
+#[derive(Clone)]
+pub struct StdModules {
+    modules: HashMap<FileName, Arc<dyn StdModule>>,
+}
+
+impl Default for StdModules {
+    fn default() -> Self {
+        let mut modules = StdModules {
+            modules: HashMap::new(),
+        };
+        for m in [
+            Arc::new(StdActions) as Arc<dyn StdModule>,
+            Arc::new(RosNav),
+            Arc::new(RosCore),
+        ] {
+            modules.modules.insert(m.name(), m);
+        }
+        modules
+    }
+}
+
+impl Debug for StdModules {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl StdModules {
+    /// registers the module.
+    /// The name should be qualified like `lib::module` and not be taken by another module.
+    pub fn register<M: StdModule + 'static>(&mut self, module: M) -> RtOk {
+        let name = module.name();
+        if name.split_once("::").is_none() {
+            return Err(RuntimeError::Unexpected(format!(
+                "the module {name} should have a qualified name like 'lib::{name}'"
+            )));
+        }
+        if self.modules.contains_key(&name) {
+            return Err(RuntimeError::Unexpected(format!(
+                "the module {name} is already registered"
+            )));
+        }
+        self.modules.insert(name, Arc::new(module));
+        Ok(())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.modules.contains_key(name)
+    }
+
+    /// the names of the modules in the alphabetical order
+    pub fn names(&self) -> Vec<&FileName> {
+        self.modules.keys().sorted().collect()
+    }
+
+    /// the text of the module if it is registered
+    pub fn text(&self, name: &str) -> Option<String> {
+        self.modules.get(name).map(|m| m.text())
+    }
+
+    /// the implementation of the action declared in the module
+    pub fn action(&self, action: &ActionName, name: &str) -> RtResult<Action> {
+        self.modules
+            .get(name)
+            .ok_or(RuntimeError::UnImplementedAction(format!(
+                "the module {name} is not registered"
+            )))?
+            .action(action)
+    }
+}
@@ -3,10 +3,12 @@
 
 use crate::runtime::action::{Action, ActionName};
 use crate::runtime::{RtResult, RuntimeError};
+use crate::runtime::builder::modules::StdModule;
 use crate::runtime::ros::OneTimeSender;
+use crate::tree::project::FileName;
 
 
-pub(crate) fn action_impl(action: &ActionName) -> RtResult<Action> {
+fn action_impl(action: &ActionName) -> RtResult<Action> {
     match action.as_str() {
         "publish" => Ok(Action::sync(OneTimeSender::Publish)),
         "advertise" => Ok(Action::sync(OneTimeSender::Advertise)),
@@ -16,8 +18,23 @@ pub(crate) fn action_impl(action: &ActionName) -> RtResult<Action> {
     }
 }
 
+/// The ros core actions accessible using the import `import "ros::core"`
+pub struct RosCore;
+
+impl StdModule for RosCore {
+    fn name(&self) -> FileName {
+        "ros::core".to_string()
+    }
+    fn text(&self) -> String {
+        ros_actions_file()
+    }
+    fn action(&self, action: &ActionName) -> RtResult<Action> {
+        action_impl(action)
+    }
+}
+
 pub fn ros_actions_file() -> String {
-    format!(r#"// Ros2 Core specific actions and decorators.
+    r#"// Ros2 Core specific actions and decorators.
 // The actions are accessible using the import 'import "ros::core"'
 
 // Publish message to the topic
@@ -27,24 +44,33 @@ impl advertise(topic:string, tp:string, url:string);
 // Unsubscribe topic
 impl unsubscribe(topic:string, url:string);
 
-// Subscribe to the topic
-// source_cfg:tp:string,
-//     throttle_rate:num,
-//     queue_length:num,
-//     fragment_size:num,
-//     compression:string,
-//
-// target_cfg: daemon type for subscriber - the way how the daemon will update bb
+// The configuration of the subscription
+struct SubscribeCfg {
+    tp?:string,
+    throttle_rate?:num,
+    queue_length?:num,
+    fragment_size?:num,
+    compression?:string
+}
+
+// The way how the subscriber daemon updates bb
 //   - tp: type of the daemon : 'last', buffer, 'all'
 //   - buf_size: size of the buffer if the type is buffer
 //   - dst: destination bb
+struct TargetCfg {
+    tp:string,
+    buf_size?:num,
+    dst:string
+}
 
+// Subscribe to the topic
 impl subscribe(
     topic:string,
-    source_cfg:object,
-    target_cfg:object,
+    source_cfg:SubscribeCfg,
+    target_cfg:TargetCfg,
     url:string
 );
 
-"#)
+"#
+    .to_string()
 }
@@ -3,12 +3,14 @@ use std::fmt::{Display, Formatter};
 use itertools::Itertools;
 use crate::runtime::action::{Action, ActionName};
 use crate::runtime::action::builtin::ReturnResult;
+use crate::runtime::builder::modules::StdModule;
 use crate::runtime::{RtResult, RuntimeError};
 use crate::tree::parser::ast::arg::{MesType, Param};
 use crate::tree::parser::ast::message::Message;
+use crate::tree::project::FileName;
 
 
-pub(super) fn action_impl(action: &ActionName) -> RtResult<Action> {
+fn action_impl(action: &ActionName) -> RtResult<Action> {
     if ros_actions().contains_key(action) {
         Ok(Action::sync(ReturnResult::success()))
     } else {
@@ -16,6 +18,21 @@ pub(super) fn action_impl(action: &ActionName) -> RtResult<Action> {
     }
 }
 
+/// The ros nav2 actions accessible using the import `import "ros::nav2"`
+pub struct RosNav;
+
+impl StdModule for RosNav {
+    fn name(&self) -> FileName {
+        "ros::nav2".to_string()
+    }
+    fn text(&self) -> String {
+        ros_actions_file()
+    }
+    fn action(&self, action: &ActionName) -> RtResult<Action> {
+        action_impl(action)
+    }
+}
+
 pub fn ros_actions_file() -> String {
     let actions = ros_actions().iter().map(|(_, v)| v.to_string()).join("\n\n");
     format!(r#"// Ros specific actions and decorators.
@@ -896,7 +913,7 @@ impl RosAction {
     }
 
     pub fn header(&self) -> String {
-        let params = &self.params.iter().map(|p| p.param()).join(", ");
+        let params = &self.params.iter().map(|p| p.to_param()).join(", ");
         let name = &self.name;
         format!("{name}({params})")
     }
@@ -940,6 +957,15 @@ impl RosParam {
             RosParam::Output(p) => p.tpe.clone(),
         }
     }
+    /// the parameter of the definition.
+    /// The ports can be omitted in xml since nav2 sets the defaults itself
+    /// thus only the subtrees are required.
+    pub fn to_param(&self) -> Param {
+        match self {
+            RosParam::Input(p) if p.tpe == MesType::Tree => p.clone(),
+            p => p.param().clone().optional(),
+        }
+    }
     fn param(&self) -> &Param {
         match self {
             RosParam::Input(p) => p,
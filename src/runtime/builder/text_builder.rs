@@ -1,14 +1,20 @@
-use crate::runtime::{RtResult, RuntimeError};
+use crate::runtime::builder::modules::StdModule;
+use crate::runtime::{RtOk, RtResult, RuntimeError};
+use crate::tree::project::libs::Libraries;
 use crate::tree::project::Project;
 
 /// The builder to build Forester from the given string.
 pub struct TextForesterBuilder {
     text: Option<String>,
+    libs: Libraries,
 }
 
 impl TextForesterBuilder {
     pub fn new() -> Self {
-        Self { text: None }
+        Self {
+            text: None,
+            libs: Libraries::default(),
+        }
     }
 
     /// add script on the fly.
@@ -20,9 +26,22 @@ impl TextForesterBuilder {
         self.text = Some(txt);
     }
 
+    /// The module that can be imported from the text
+    pub fn register_module<M: StdModule + 'static>(&mut self, module: M) -> RtOk {
+        self.libs.modules.register(module)
+    }
+
     pub fn build(self) -> RtResult<Project> {
-        if let Some(t) = self.text {
-            Ok(Project::build_from_text(t)?)
+        self.project()
+    }
+
+    /// The project of the text along with the registered modules.
+    pub fn project(&self) -> RtResult<Project> {
+        if let Some(t) = &self.text {
+            Ok(Project::build_from_text_with_libs(
+                t.clone(),
+                self.libs.clone(),
+            )?)
         } else {
             Err(RuntimeError::UnImplementedAction(
                 "not enough arguments to initialize the project".to_string(),
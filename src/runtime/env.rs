@@ -1,7 +1,8 @@
 pub mod daemon;
 
-use crate::runtime::action::ActionName;
 use crate::runtime::action::Tick;
+use crate::runtime::forester::schedule::{TickEvent, TickSignal};
+use crate::runtime::rtree::rnode::RNodeId;
 use crate::runtime::{RtOk, RtResult, RuntimeError};
 use std::collections::HashMap;
 use std::future::IntoFuture;
@@ -10,6 +11,8 @@ use std::sync::atomic::AtomicBool;
 use itertools::Itertools;
 use tokio::runtime::{Builder, Runtime};
 
+use tokio::sync::oneshot;
+use tokio::sync::oneshot::error::TryRecvError;
 use tokio::task::JoinError;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
@@ -27,10 +30,13 @@ pub type RtEnvRef = Arc<Mutex<RtEnv>>;
 pub struct RtEnv {
     /// The tokio runtime
     pub runtime: Runtime,
-    /// The async tasks
-    pub tasks: HashMap<ActionName, JoinHandle<Tick>>,
+    /// The async tasks by the nodes that have started them.
+    /// Every invocation of the same action has its own task.
+    pub tasks: HashMap<RNodeId, AsyncTask>,
     /// The daemons
     pub daemons: Vec<DaemonTask>,
+    /// The signal to start the next tick, see [`crate::runtime::forester::schedule`]
+    pub signal: TickSignal,
 }
 
 impl Default for RtEnv {
@@ -45,9 +51,40 @@ impl From<JoinError> for RuntimeError {
     }
 }
 
+/// The async task of the action running on the blocking pool.
+///
+/// The task sends the result before it notifies the engine,
+/// thus the tick woken up by the task always finds the result.
+pub struct AsyncTask {
+    handle: JoinHandle<()>,
+    result: oneshot::Receiver<Tick>,
+}
+
+impl AsyncTask {
+    /// Spawns the action on the blocking pool.
+    /// The signal is notified after the result is sent.
+    pub fn spawn<F>(runtime: &Runtime, signal: TickSignal, action: F) -> Self
+    where
+        F: FnOnce() -> Tick + Send + 'static,
+    {
+        let (tx, result) = oneshot::channel();
+        let handle = runtime.spawn_blocking(move || {
+            let _ = tx.send(action());
+            signal.notify(TickEvent::TaskFinished);
+        });
+        Self { handle, result }
+    }
+
+    /// Cancels the task if it has not started yet.
+    /// The task that has already started runs till the end and its result is ignored.
+    pub fn abort(&self) {
+        self.handle.abort()
+    }
+}
+
 pub enum TaskState {
     Absent,
-    Started(JoinHandle<Tick>),
+    Started(AsyncTask),
     Finished(Tick),
 }
 
@@ -57,6 +94,7 @@ impl RtEnv {
             runtime,
             tasks: HashMap::default(),
             daemons: Vec::default(),
+            signal: TickSignal::default(),
         }
     }
     pub fn try_new() -> RtResult<Self> {
@@ -65,6 +103,7 @@ impl RtEnv {
             runtime,
             tasks: HashMap::default(),
             daemons: Vec::default(),
+            signal: TickSignal::default(),
         })
     }
     fn start_daemon_impl(&mut self, daemon: Daemon, ctx: DaemonContext) -> RtResult<(JoinHandle<()>, DaemonStopSignal)> {
@@ -152,14 +191,21 @@ impl RtEnv {
         }
     }
 
-    /// the state of the async task
-    pub fn task_state(&mut self, name: &ActionName) -> RtResult<TaskState> {
-        match self.tasks.remove(name) {
+    /// the state of the async task started by the node
+    pub fn task_state(&mut self, id: &RNodeId) -> RtResult<TaskState> {
+        match self.tasks.remove(id) {
             None => Ok(TaskState::Absent),
-            Some(jh) if !jh.is_finished() => Ok(TaskState::Started(jh)),
-            Some(jh) => Ok(TaskState::Finished(
-                self.runtime.block_on(jh.into_future())?,
-            )),
+            Some(mut task) => match task.result.try_recv() {
+                Ok(tick) => Ok(TaskState::Finished(tick)),
+                Err(TryRecvError::Empty) => Ok(TaskState::Started(task)),
+                // the task has panicked before sending the result
+                Err(TryRecvError::Closed) => {
+                    self.runtime.block_on(task.handle.into_future())?;
+                    Err(RuntimeError::uex(format!(
+                        "the async task of the node {id} is finished without the result"
+                    )))
+                }
+            },
         }
     }
 }
@@ -1,5 +1,6 @@
 pub mod decorator;
 pub mod flow;
+pub mod schedule;
 pub mod serv;
 
 use crate::runtime::action::keeper::ActionKeeper;
@@ -9,8 +10,9 @@ use crate::runtime::blackboard::BlackBoard;
 use crate::runtime::context::{RNodeState, TreeContext, TreeContextRef};
 use crate::runtime::env::RtEnv;
 use crate::runtime::forester::flow::{read_cursor, run_with, run_with_par, FlowDecision};
+use crate::runtime::forester::schedule::{Scheduler, TickSignal, Ticking};
 use crate::runtime::forester::serv::ServInfo;
-use crate::runtime::rtree::rnode::RNode;
+use crate::runtime::rtree::rnode::{RNode, RNodeId};
 use crate::runtime::rtree::RuntimeTree;
 use crate::runtime::trimmer::task::TrimTask;
 use crate::runtime::trimmer::validator::TrimValidationResult;
@@ -18,7 +20,8 @@ use crate::runtime::trimmer::{RequestBody, TreeSnapshot, TrimRequest, TrimmingQu
 use crate::runtime::{trimmer, RtOk, RtResult, RuntimeError};
 use crate::tracer::{Event, Tracer};
 use log::debug;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc, Mutex};
 use tokio::task::JoinHandle;
 
 /// The entry point to process execution.
@@ -28,6 +31,7 @@ use tokio::task::JoinHandle;
 /// - ActionKeeper to execute the actions
 /// - Tracer to store the tracing information
 /// - Optimizer holds tasks to modify the tree or other components on the fly
+/// - Scheduler decides when the next tick starts, see [`schedule`]
 ///
 ///# Note:
 /// Better to use `ForesterBuilder` to create a Forester.
@@ -39,6 +43,9 @@ pub struct Forester {
     pub env: Arc<Mutex<RtEnv>>,
     pub trimmer: Arc<Mutex<TrimmingQueue>>,
     serv: Option<ServInfo>,
+    scheduler: Scheduler,
+    // the execution paused between the ticks along with the child of the root to tick next
+    paused: Option<(TreeContext, RNodeId)>,
 }
 
 impl Forester {
@@ -49,8 +56,10 @@ impl Forester {
         keeper: ActionKeeper,
         env: Arc<Mutex<RtEnv>>,
         serv: Option<ServInfo>,
+        scheduler: Scheduler,
     ) -> RtResult<Self> {
         let trimmer = Arc::new(Mutex::new(TrimmingQueue::default()));
+        bb.lock()?.watch(env.lock()?.signal.clone());
         Ok(Self {
             tree,
             bb,
@@ -59,9 +68,17 @@ impl Forester {
             env,
             trimmer,
             serv,
+            scheduler,
+            paused: None,
         })
     }
 
+    /// The signal to wake up the engine waiting for the next tick,
+    /// see [`schedule::TickMode::OnTaskFinished`] and [`schedule::TickMode::OnChange`].
+    pub fn signal(&self) -> RtResult<TickSignal> {
+        Ok(self.env.lock()?.signal.clone())
+    }
+
     /// The function to trim the tree or perform other procedures.
     /// Initially, the intention is to have an ability to change some components of the current execution on a fly.
     /// The trimming procedure performs only one task in a tick. Others are either declined or postponed.
@@ -105,7 +122,7 @@ impl Forester {
                             let RequestBody { tree_b, actions } = r;
                             for (nid, node) in tree_b.nodes {
                                 let new = format!("{:?}", node);
-                                let old = self.tree.nodes.insert(nid, node);
+                                let old = self.tree.insert(nid, node);
                                 debug!(target:"trim","The node {nid} is replaced. The previous node {:?}, the new node {new}", old);
                                 self.tracer.lock()?.trace(
                                     ctx.curr_ts(),
@@ -155,11 +172,73 @@ impl Forester {
             self.env.clone(),
         );
         ctx.push(self.tree.root)?;
+        self.paused = None;
+        self.scheduler.start();
+        let (ctx, _) = self.process(ctx, false)?;
+        self.finish(ctx)
+    }
+
+    /// Performs one tick and returns the state of the root.
+    /// The execution is paused after the tick and the next call proceeds with the next tick,
+    /// thus the host can drive the tree from its own loop.
+    /// When the root is finished, the next call starts the execution from the beginning.
+    /// The http server and the daemons keep working between the executions,
+    /// thus the host calls [`Forester::stop`] when the tree is not needed anymore.
+    ///
+    /// The tick mode is not taken into account since the host decides when to tick.
+    pub fn tick_once(&mut self) -> Tick {
+        let ctx = match self.paused.take() {
+            Some((mut ctx, child)) => {
+                self.next_tick(&mut ctx, child)?;
+                ctx
+            }
+            None => {
+                let mut ctx =
+                    TreeContext::new(self.bb.clone(), self.tracer.clone(), 0, self.env.clone());
+                ctx.push(self.tree.root)?;
+                ctx
+            }
+        };
+        match self.process(ctx, true)? {
+            (ctx, Some(child)) => {
+                let state = ctx.root_state(self.tree.root);
+                self.paused = Some((ctx, child));
+                state
+            }
+            (ctx, None) => ctx.root_state(self.tree.root),
+        }
+    }
+
+    /// Stops the http server and the daemons and drops the paused execution.
+    /// The method [`Forester::run`] does it by itself when the root is finished.
+    pub fn stop(&mut self) -> RtOk {
+        self.paused = None;
+        self.stop_http();
+        self.env.lock().map(|mut e| e.stop_all_daemons())?;
+        Ok(())
+    }
+
+    fn next_tick(&mut self, ctx: &mut TreeContext, child: RNodeId) -> RtOk {
+        ctx.next_tick()?;
+        debug!(target:"trim","attempt to trim is  {:?}", self.trim(ctx));
+        ctx.push(child)
+    }
+
+    /// Processes the nodes on the stack of the context.
+    /// If `once` is set, stops before the next tick and returns the child of the root to tick next.
+    fn process(
+        &mut self,
+        mut ctx: TreeContext,
+        once: bool,
+    ) -> RtResult<(TreeContext, Option<RNodeId>)> {
+        let _ticking = Ticking::start();
         // starts from root and pops up the element when either it is finished
         // or the root needs to make a new tick
         while let Some(id) = ctx.peek()? {
             let id = *id;
             debug!(target:"loop", "node = {}, tick = {}", id,ctx.curr_ts());
+            // the lazily built tree instantiates the node when it is ticked for the first time
+            self.tree.instantiate(id)?;
             match self.tree.node(&id)? {
                 RNode::Flow(tpe, _n, init_args, children) => match ctx.state_in_ts(&id) {
                     // do nothing, since there are no children
@@ -197,7 +276,16 @@ impl Forester {
                             // Just pass the control to the child
                             RNodeState::Ready(..) => {
                                 debug!(target:"flow[run]", "tick:{}, {tpe}. The '{child}' is ready, push it on the stack",ctx.curr_ts());
-                                ctx.push(child)?;
+                                // the concurrent parallel node ticks the ready sync actions at once
+                                // and then handles their results one by one as usual.
+                                if tpe.is_par() && flow::is_concurrent(init_args) {
+                                    let children = children.clone();
+                                    self.tick_concurrently(
+                                        &mut ctx, id, &children, cursor, tick_args,
+                                    )?;
+                                } else {
+                                    ctx.push(child)?;
+                                }
                             }
                             // child is already running and since the flow is here in the parent,
                             // he decided that it is a final state for the tick,
@@ -209,9 +297,11 @@ impl Forester {
                                 // root does not have parent so, just proceed to the next tick
                                 if tpe.is_root() {
                                     debug!(target:"flow[run]", "tick:{}, {tpe}. The '{child}' is running, tick up the flow. ",ctx.curr_ts());
-                                    ctx.next_tick()?;
-                                    debug!(target:"trim","attempt to trim is  {:?}", self.trim(&ctx));
-                                    ctx.push(child)?;
+                                    if once {
+                                        return Ok((ctx, Some(child)));
+                                    }
+                                    self.scheduler.wait(&self.env)?;
+                                    self.next_tick(&mut ctx, child)?;
                                 } else {
                                     debug!(target:"flow[run]", "tick:{}, {tpe}. The '{child}' is running, decide to go up or stay here.",ctx.curr_ts());
                                     // for parallel node we need to proceed with other children regardless of the current result
@@ -230,17 +320,19 @@ impl Forester {
                                             debug!(target:"flow[run]", "tick:{}, {tpe}. stay with the new state: {}",ctx.curr_ts(),&ns);
                                             ctx.new_state(id, ns)?;
                                         }
-                                        FlowDecision::Halt(new_state, halting_child_cursor) => {
+                                        FlowDecision::Halt(new_state, halting_child_cursors) => {
                                             // A reactively-checked child returning running will halt any other running child.
                                             // Pop ourselves then halt the previously running child.
-                                            let halting_child_id = children[halting_child_cursor];
-                                            debug!(target:"flow[run]", "tick:{}, {tpe}. Reactively checked child '{child}' has returned running. Halting previously running child '{halting_child_id}', then go up with new state: {}.", ctx.curr_ts(), new_state);
+                                            debug!(target:"flow[run]", "tick:{}, {tpe}. Reactively checked child '{child}' has returned running. Halting previously running children {:?}, then go up with new state: {}.", ctx.curr_ts(), halting_child_cursors, new_state);
 
                                             ctx.new_state(id, new_state)?;
                                             ctx.pop()?;
 
-                                            ctx.force_to_halting_state(halting_child_id)?;
-                                            ctx.push(halting_child_id)?;
+                                            for cursor in halting_child_cursors {
+                                                let halting_child_id = children[cursor];
+                                                ctx.force_to_halting_state(halting_child_id)?;
+                                                ctx.push(halting_child_id)?;
+                                            }
                                         }
                                     }
                                 }
@@ -275,15 +367,17 @@ impl Forester {
                                         debug!(target:"flow[run]", "tick:{}, {tpe}. The '{}' is finished as {}, the new state: {}. Stay at this node. ",ctx.curr_ts(),child,s, &ns);
                                         ctx.new_state(id, ns)?;
                                     }
-                                    FlowDecision::Halt(new_state, halting_child_cursor) => {
+                                    FlowDecision::Halt(new_state, halting_child_cursors) => {
                                         // Normally we would fall through to Failure or Success next tick (e.g. Stay decision), but we need to pass control to the child so it can halt properly.
-                                        // The current node can continue as normal once the child is halted.
+                                        // The current node can continue as normal once the children are halted.
                                         ctx.new_state(id, new_state.clone())?;
-                                        // Force the state of the child to be halting, so it will interrupt itself on the next tick.
-                                        let halting_child_id = children[halting_child_cursor];
-                                        debug!(target:"flow[run]", "tick:{}, {tpe}. The '{}' is finished as {}, the new state: {}. Halting child '{}'. ",ctx.curr_ts(),child,s, &new_state, &halting_child_id);
-                                        ctx.force_to_halting_state(halting_child_id)?;
-                                        ctx.push(halting_child_id)?;
+                                        // Force the state of the children to be halting, so they will interrupt themselves on the next tick.
+                                        for cursor in halting_child_cursors {
+                                            let halting_child_id = children[cursor];
+                                            debug!(target:"flow[run]", "tick:{}, {tpe}. The '{}' is finished as {}, the new state: {}. Halting child '{}'. ",ctx.curr_ts(),child,s, &new_state, &halting_child_id);
+                                            ctx.force_to_halting_state(halting_child_id)?;
+                                            ctx.push(halting_child_id)?;
+                                        }
                                     }
                                 }
                             }
@@ -292,11 +386,11 @@ impl Forester {
                     // The node's parent has commanded us to halt.
                     RNodeState::Halting(tick_args) => {
                         debug!(target:"flow[halt]", "tick:{}, {tpe}. Checking for running children to halt.",ctx.curr_ts());
-                        let (new_state, halting_child_cursor) = flow::halt(tpe, tick_args.clone());
+                        let (new_state, halting_child_cursors) = flow::halt(tpe, tick_args.clone());
                         // Halting is a one-way process, pop ourselves then push any halting children.
                         ctx.new_state(id, new_state)?;
                         ctx.pop()?;
-                        if let Some(halting_child_cursor) = halting_child_cursor {
+                        for halting_child_cursor in halting_child_cursors {
                             let halting_child_id = children[halting_child_cursor];
                             ctx.force_to_halting_state(halting_child_id)?;
                             ctx.push(halting_child_id)?;
@@ -398,6 +492,7 @@ impl Forester {
                         let ctx_ref = TreeContextRef::from_ctx(&ctx, self.trimmer.clone());
                         self.keeper.halt(
                             self.env.clone(),
+                            id,
                             f_name.name()?,
                             args.clone(),
                             ctx_ref,
@@ -412,6 +507,7 @@ impl Forester {
                             let ctx_ref = TreeContextRef::from_ctx(&ctx, self.trimmer.clone());
                             let res = recover(self.keeper.on_tick(
                                 self.env.clone(),
+                                id,
                                 f_name.name()?,
                                 args.clone(),
                                 ctx_ref,
@@ -426,13 +522,90 @@ impl Forester {
                 },
             }
         }
+        Ok((ctx, None))
+    }
+
+    fn finish(&mut self, ctx: TreeContext) -> Tick {
         // clean up the tree
-        self.stop_http();
-        self.env.lock().map(|mut e| e.stop_all_daemons())?;
+        self.stop()?;
 
         ctx.root_state(self.tree.root)
     }
 
+    /// Ticks the ready sync actions among the children of the concurrent parallel node
+    /// on the blocking pool of the runtime and waits for all of them.
+    /// The other children (flows, decorators, async and remote actions) are ticked as usual.
+    ///
+    /// The actions share the blackboard that is guarded by the mutex,
+    /// thus they need to hold the lock only for the time of reading or writing.
+    fn tick_concurrently(
+        &mut self,
+        ctx: &mut TreeContext,
+        id: RNodeId,
+        children: &[RNodeId],
+        cursor: usize,
+        tick_args: RtArgs,
+    ) -> RtOk {
+        let states = flow::read_children_state(tick_args.clone());
+        let mut batch = vec![];
+        for (idx, child) in children.iter().enumerate().skip(cursor) {
+            let finished = states.get(idx).is_some_and(|s| *s > 1);
+            if finished || !ctx.state_in_ts(child).is_ready() {
+                continue;
+            }
+            self.tree.instantiate(*child)?;
+            if let RNode::Leaf(name, args) = self.tree.node(child)? {
+                if let Some(action) = self.keeper.sync_action(name.name()?) {
+                    batch.push((idx, *child, action, args.clone()));
+                }
+            }
+        }
+        debug!(target:"flow[concurrent]", "tick:{}, tick {} actions concurrently",ctx.curr_ts(), batch.len());
+
+        let runtime = self.env.lock()?.runtime.handle().clone();
+        let (sender, receiver) = mpsc::channel();
+        for (_, child, action, args) in batch.iter().cloned() {
+            let sender = sender.clone();
+            let ctx_ref = TreeContextRef::from_ctx(ctx, self.trimmer.clone());
+            runtime.spawn_blocking(move || {
+                let _ticking = Ticking::start();
+                sender.send((child, action.tick(args, ctx_ref)))
+            });
+        }
+        drop(sender);
+        // the action that panics drops its sender without the result
+        let mut results: HashMap<RNodeId, Tick> = receiver.iter().collect();
+
+        let mut running = vec![];
+        for (idx, child, _, args) in batch {
+            let res = results
+                .remove(&child)
+                .unwrap_or(Err(RuntimeError::MultiThreadError(format!(
+                    "the action of the node {child} has not returned the result"
+                ))));
+            let new_state = RNodeState::from(args, recover(res)?);
+            debug!(target:"leaf", "tick:{}, the new state: {}",ctx.curr_ts(),&new_state);
+            // the running children are recorded at once to be halted if the policy decides earlier
+            if new_state.is_running() {
+                running.push(idx);
+            }
+            ctx.new_state(child, new_state)?;
+        }
+        if !running.is_empty() {
+            let tick_args = running.into_iter().fold(tick_args, |args, idx| {
+                flow::replace_child_state(args, idx, 1)
+            });
+            ctx.new_state(id, RNodeState::Running(tick_args))?;
+        }
+        // the child at the cursor can be something else, then it is pushed as usual
+        if let Some(child) = children.get(cursor) {
+            if ctx.state_in_ts(child).is_ready() {
+                ctx.push(*child)?;
+            }
+        }
+        Ok(())
+    }
+
     /// stops the http server
     pub fn stop_http(&mut self) {
         if let Some(serv) = self.serv.take() {
@@ -28,6 +28,16 @@ pub const RUNNING_CHILD: &str = "running_child";
 // 3 is success
 pub const CHILDREN: &str = "children";
 
+// the policy of the parallel node set with the arguments like `parallel(success = 2, failure = 2)`:
+// the number of the succeeded children to succeed, by default all children
+pub const SUCCESS_THRESHOLD: &str = "success";
+// the number of the failed children to fail, by default one child
+pub const FAILURE_THRESHOLD: &str = "failure";
+// the first finished child decides the result
+pub const FIRST_FINISHED: &str = "first";
+// the sync actions among the children are ticked concurrently on the blocking pool of the runtime
+pub const CONCURRENT: &str = "concurrent";
+
 pub fn run_with(tick_args: RtArgs, cursor: i64, len: i64) -> RtArgs {
     debug!(target:"params", "{}, cur:{cursor}, len:{len}", tick_args);
     tick_args
@@ -55,6 +65,13 @@ pub fn run_with_par(tick_args: RtArgs, len: i64) -> RtArgs {
     }
 }
 
+pub(crate) fn is_concurrent(init_args: &RtArgs) -> bool {
+    init_args
+        .find(CONCURRENT.to_string())
+        .and_then(RtValue::as_bool)
+        .unwrap_or(false)
+}
+
 pub(crate) fn read_len_or_zero(args: RtArgs) -> i64 {
     args.find(LEN.to_string())
         .and_then(|v| v.as_int())
@@ -114,7 +131,7 @@ impl Into<TickResult> for TickResultFin {
 // It starts when the child is finished and the flow needs to go farther.
 pub fn finalize(
     tpe: &FlowType,
-    _args: RtArgs,
+    args: RtArgs,
     tick_args: RtArgs,
     res: TickResultFin,
     _ctx: &mut TreeContext,
@@ -145,7 +162,7 @@ pub fn finalize(
                             // Note non-reactive sequences will always have running == p_cursor == cursor, so this will be unreachable for them.
                             return Ok(Halt(
                                 RNodeState::Failure(run_with(args, cursor, len)),
-                                running as usize,
+                                vec![running as usize],
                             ));
                         }
                     }
@@ -235,7 +252,7 @@ pub fn finalize(
                             // Note non-reactive fallbacks will always have running == p_cursor == cursor, so this will be unreachable for them.
                             return Ok(Halt(
                                 RNodeState::Success(run_with(args, cursor, len)),
-                                running as usize,
+                                vec![running as usize],
                             ));
                         }
                     }
@@ -254,6 +271,24 @@ pub fn finalize(
             };
             let tick_args = replace_child_state(tick_args, cursor as usize, st);
             let children = read_children_state(tick_args.clone());
+            // the policy decides as soon as a threshold is reached, the running children get halted
+            if let Some(res) = ParallelPolicy::read(&args, len).and_then(|p| p.decide(&children)) {
+                let args = run_with(tick_args, cursor, len)
+                    .remove(CHILDREN)
+                    .remove(P_CURSOR);
+                let state = match res {
+                    TickResultFin::Success => RNodeState::Success(args),
+                    TickResultFin::Failure(r) => {
+                        RNodeState::Failure(args.with(REASON, RtValue::str(r)))
+                    }
+                };
+                let running = find_running(&children);
+                return if running.is_empty() {
+                    Ok(Stay(state))
+                } else {
+                    Ok(Halt(state, running))
+                };
+            }
             // if some child is running or ready, we continue
             if let Some(idx) = find_next_idx(&children, cursor) {
                 Ok(Stay(RNodeState::Running(
@@ -315,7 +350,7 @@ pub fn monitor(
             // If there is we'll need to halt that before going any further.
             if let Some(prev_running_child_cursor) = previous_running_child {
                 if prev_running_child_cursor > cursor {
-                    return Ok(Halt(new_state, prev_running_child_cursor as usize));
+                    return Ok(Halt(new_state, vec![prev_running_child_cursor as usize]));
                 }
             }
 
@@ -343,7 +378,13 @@ pub fn monitor(
                     new_args.with(CURSOR, RtValue::int(idx as i64)),
                 )))
             } else {
-                Ok(PopNode(RNodeState::Running(new_args)))
+                // the next tick starts from the first running child
+                // resetting the prev cursor otherwise it will be taken being greater
+                let len = read_len_or_zero(new_args.clone());
+                let next_cursor = find_first_idx(&children, cursor).unwrap_or(cursor as usize);
+                Ok(PopNode(RNodeState::Running(
+                    run_with(new_args, next_cursor as i64, len).with(P_CURSOR, RtValue::int(0i64)),
+                )))
             }
         }
         _ => Ok(PopNode(RNodeState::Running(tick_args))),
@@ -351,8 +392,8 @@ pub fn monitor(
 }
 
 // Handle ticking a flow node with the state "Halting".
-// Returns a tuple of the new state and the cursor position of the child to be halted, if one exists.
-pub fn halt(flow_type: &FlowType, tick_args: RtArgs) -> (RNodeState, Option<usize>) {
+// Returns a tuple of the new state and the cursor positions of the children to be halted.
+pub fn halt(flow_type: &FlowType, tick_args: RtArgs) -> (RNodeState, Vec<usize>) {
     match flow_type {
         FlowType::Sequence
         | FlowType::MSequence
@@ -372,9 +413,15 @@ pub fn halt(flow_type: &FlowType, tick_args: RtArgs) -> (RNodeState, Option<usiz
             }
 
             let new_state = RNodeState::Ready(args);
-            (new_state, running_child_cursor)
+            (new_state, running_child_cursor.into_iter().collect())
         }
-        _ => (RNodeState::Ready(tick_args), None),
+        // Parallel halts all running children and starts from scratch the next time.
+        FlowType::Parallel => {
+            let running = find_running(&read_children_state(tick_args.clone()));
+            let args = tick_args.remove(CHILDREN).remove(P_CURSOR);
+            (RNodeState::Ready(args), running)
+        }
+        _ => (RNodeState::Ready(tick_args), vec![]),
     }
 }
 
@@ -386,10 +433,68 @@ pub fn halt(flow_type: &FlowType, tick_args: RtArgs) -> (RNodeState, Option<usiz
 pub enum FlowDecision {
     PopNode(RNodeState),
     Stay(RNodeState),
-    Halt(RNodeState, HaltingChildCursor),
+    Halt(RNodeState, Vec<HaltingChildCursor>),
+}
+
+/// The thresholds of the parallel node set with the arguments.
+/// The omitted threshold is the one the other threshold makes unreachable,
+/// thus `parallel(success = 2)` of 3 children fails when 2 children fail.
+struct ParallelPolicy {
+    success: i64,
+    failure: i64,
 }
 
-fn replace_child_state(args: RtArgs, idx: usize, v: i64) -> RtArgs {
+impl ParallelPolicy {
+    // the parallel node without arguments waits for all children
+    fn read(args: &RtArgs, len: i64) -> Option<Self> {
+        let first = args
+            .find(FIRST_FINISHED.to_string())
+            .and_then(RtValue::as_bool)
+            .unwrap_or(false);
+        let success = args
+            .find(SUCCESS_THRESHOLD.to_string())
+            .and_then(RtValue::as_int);
+        let failure = args
+            .find(FAILURE_THRESHOLD.to_string())
+            .and_then(RtValue::as_int);
+        match (first, success, failure) {
+            (true, _, _) => Some(ParallelPolicy {
+                success: 1,
+                failure: 1,
+            }),
+            (_, None, None) => None,
+            (_, Some(s), None) => Some(ParallelPolicy {
+                success: s,
+                failure: len - s + 1,
+            }),
+            (_, None, Some(f)) => Some(ParallelPolicy {
+                success: len - f + 1,
+                failure: f,
+            }),
+            (_, Some(s), Some(f)) => Some(ParallelPolicy {
+                success: s,
+                failure: f,
+            }),
+        }
+    }
+
+    fn decide(&self, children: &[i64]) -> Option<TickResultFin> {
+        let len = children.len() as i64;
+        let succeeded = children.iter().filter(|s| **s == 3).count() as i64;
+        let failed = children.iter().filter(|s| **s == 2).count() as i64;
+        if succeeded >= self.success {
+            Some(TickResultFin::Success)
+        } else if failed >= self.failure || len - failed < self.success {
+            Some(TickResultFin::Failure(format!(
+                "parallel failure: {failed} of {len} children failed"
+            )))
+        } else {
+            None
+        }
+    }
+}
+
+pub(crate) fn replace_child_state(args: RtArgs, idx: usize, v: i64) -> RtArgs {
     let args = args;
     let mut elems = read_children_state(args.clone());
     debug!(target:"params in child", "prev : [{args}], idx:{idx}, new state: {v}");
@@ -400,12 +505,22 @@ fn replace_child_state(args: RtArgs, idx: usize, v: i64) -> RtArgs {
     )
 }
 
-fn read_children_state(args: RtArgs) -> Vec<i64> {
+pub(crate) fn read_children_state(args: RtArgs) -> Vec<i64> {
     args.find(CHILDREN.to_string())
         .and_then(|v| v.as_vec(|v| v.as_int().unwrap()))
         .unwrap_or_default()
 }
 
+// find the running children
+fn find_running(children: &[i64]) -> Vec<usize> {
+    children
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| **s == 1)
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
 // find the next idx that is either running or ready
 fn find_next_idx(children: &Vec<i64>, current: i64) -> Option<usize> {
     find_pos(children, current + 1, children.len() as i64)
@@ -0,0 +1,207 @@
+//! The scheduling of the ticks.
+//!
+//! By default, the engine starts the next tick right after the previous one,
+//! thus it spins while the async actions are running.
+//! The other modes make the engine wait between the ticks
+//! either for the fixed period or for the events coming from the async tasks,
+//! the blackboard or the host.
+use crate::runtime::env::RtEnvRef;
+use crate::runtime::{RtOk, RtResult, RuntimeError};
+use std::cell::Cell;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How the engine proceeds to the next tick
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TickMode {
+    /// The next tick starts right after the previous one.
+    #[default]
+    Eager,
+    /// The ticks start with the given frequency in Hz.
+    Rate(f64),
+    /// The next tick starts when an async task finishes or the wakeup signal comes.
+    /// If no async tasks are running, the next tick starts right away.
+    OnTaskFinished,
+    /// The next tick starts when the blackboard changes, an async task finishes or the wakeup signal comes.
+    /// The changes the tree makes itself during the tick do not count.
+    OnChange,
+}
+
+impl TickMode {
+    // the period between the ticks of the mode `Rate`
+    fn period(hz: f64) -> RtResult<Duration> {
+        Some(hz)
+            .filter(|hz| *hz > 0.0)
+            .and_then(|hz| Duration::try_from_secs_f64(1.0 / hz).ok())
+            .ok_or(RuntimeError::WrongArgument(format!(
+                "the tick rate should be a positive number of Hz but got {hz}"
+            )))
+    }
+}
+
+/// The events that start the next tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TickEvent {
+    TaskFinished,
+    BlackBoardChanged,
+    Wakeup,
+}
+
+impl TickEvent {
+    fn bit(&self) -> u8 {
+        match self {
+            TickEvent::TaskFinished => 1,
+            TickEvent::BlackBoardChanged => 2,
+            TickEvent::Wakeup => 4,
+        }
+    }
+}
+
+/// The signal to wake up the engine waiting for the next tick.
+///
+/// The events are kept until the engine waits for them,
+/// thus the events coming in the middle of a tick are not lost.
+///
+/// # Example
+/// ```no_run
+/// use forester_rs::runtime::builder::ForesterBuilder;
+/// use forester_rs::runtime::forester::schedule::TickMode;
+/// use std::thread;
+///
+/// fn run(mut fb: ForesterBuilder) {
+///     fb.tick_mode(TickMode::OnChange);
+///     let mut forester = fb.build().unwrap();
+///     let signal = forester.signal().unwrap();
+///     thread::spawn(move || {
+///         // something has changed outside the tree
+///         signal.wake();
+///     });
+///     forester.run().unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TickSignal {
+    events: Arc<(Mutex<u8>, Condvar)>,
+}
+
+impl TickSignal {
+    /// Wakes up the engine to start the next tick.
+    pub fn wake(&self) {
+        self.notify(TickEvent::Wakeup)
+    }
+
+    pub fn notify(&self, event: TickEvent) {
+        let (events, cvar) = &*self.events;
+        if let Ok(mut events) = events.lock() {
+            *events |= event.bit();
+            cvar.notify_all();
+        }
+    }
+
+    /// Waits for any of the expected events at most for the given time
+    /// and takes all the events that have come.
+    fn wait(&self, expected: &[TickEvent], max_wait: Option<Duration>) -> RtOk {
+        let mask = expected.iter().fold(0, |mask, e| mask | e.bit());
+        let (events, cvar) = &*self.events;
+        let events = events.lock()?;
+        let mut events = match max_wait {
+            Some(max_wait) => {
+                cvar.wait_timeout_while(events, max_wait, |events| *events & mask == 0)
+                    .map_err(|e| RuntimeError::MultiThreadError(e.to_string()))?
+                    .0
+            }
+            None => cvar.wait_while(events, |events| *events & mask == 0)?,
+        };
+        *events = 0;
+        Ok(())
+    }
+}
+
+thread_local! {
+    static TICKING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks the current thread as ticking the tree until it is dropped.
+/// The changes of the blackboard made by the thread that ticks the tree
+/// do not start the next tick, otherwise the tree writing the blackboard on every tick would spin.
+pub(crate) struct Ticking(bool);
+
+impl Ticking {
+    pub(crate) fn start() -> Self {
+        Ticking(TICKING.replace(true))
+    }
+
+    pub(crate) fn is_ticking() -> bool {
+        TICKING.get()
+    }
+}
+
+impl Drop for Ticking {
+    fn drop(&mut self) {
+        TICKING.set(self.0)
+    }
+}
+
+/// Decides when the next tick starts according to the mode.
+pub(crate) struct Scheduler {
+    mode: TickMode,
+    max_wait: Option<Duration>,
+    last_tick: Instant,
+}
+
+impl Scheduler {
+    /// # Errors
+    /// The mode `Rate` with the rate that is not a positive number is an error.
+    pub(crate) fn new(mode: TickMode, max_wait: Option<Duration>) -> RtResult<Self> {
+        if let TickMode::Rate(hz) = mode {
+            TickMode::period(hz)?;
+        }
+        Ok(Self {
+            mode,
+            max_wait,
+            last_tick: Instant::now(),
+        })
+    }
+
+    /// Marks the start of the first tick.
+    pub(crate) fn start(&mut self) {
+        self.last_tick = Instant::now();
+    }
+
+    /// Blocks until the next tick can start.
+    pub(crate) fn wait(&mut self, env: &RtEnvRef) -> RtOk {
+        match self.mode {
+            TickMode::Eager => {}
+            TickMode::Rate(hz) => {
+                let period = TickMode::period(hz)?;
+                if let Some(rest) = period.checked_sub(self.last_tick.elapsed()) {
+                    thread::sleep(rest);
+                }
+            }
+            TickMode::OnTaskFinished => {
+                let (signal, running) = {
+                    let env = env.lock()?;
+                    (env.signal.clone(), !env.tasks.is_empty())
+                };
+                if running {
+                    signal.wait(&[TickEvent::TaskFinished, TickEvent::Wakeup], self.max_wait)?;
+                }
+            }
+            TickMode::OnChange => {
+                let signal = env.lock()?.signal.clone();
+                signal.wait(
+                    &[
+                        TickEvent::TaskFinished,
+                        TickEvent::BlackBoardChanged,
+                        TickEvent::Wakeup,
+                    ],
+                    self.max_wait,
+                )?;
+            }
+        }
+        debug!(target:"schedule", "the next tick starts after {:?}", self.last_tick.elapsed());
+        self.last_tick = Instant::now();
+        Ok(())
+    }
+}
@@ -17,6 +17,7 @@ use crate::runtime::blackboard::BBKey;
 use crate::runtime::env::daemon::context::DaemonContext;
 use crate::runtime::env::daemon::{Daemon, DaemonFn, StopFlag};
 use crate::runtime::ros::client::{SubscribeCfg, WS};
+use serde::Deserialize;
 
 /// abilities
 /// - subscribe to topics - > get data and put it to bb.
@@ -136,7 +137,9 @@ impl DaemonFn for SubscriberDaemon {
     }
 }
 
-#[derive(Debug, Default)]
+/// The configuration of the subscriber daemon that corresponds to the struct `TargetCfg` of `ros::core`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct TargetCfg {
     tp: String,
     buf_size: Option<usize>,
@@ -145,19 +148,7 @@ pub struct TargetCfg {
 
 impl TargetCfg {
     pub fn from(v: RtValue) -> RtResult<TargetCfg> {
-        let elems = v.as_map(|(k, v)| (k, v))
-            .ok_or(RuntimeError::fail(format!("the target_cfg should be an object")))?;
-
-        let tp = elems.get("tp").and_then(|v| v.clone().as_string())
-            .ok_or(RuntimeError::fail(format!("the tp is not found")))?;
-
-        let buf_size = elems.get("buf_size")
-            .and_then(|v| v.clone().as_int().map(|v| v as usize));
-
-        let dst = elems.get("dst").and_then(|v| v.clone().as_string())
-            .ok_or(RuntimeError::fail(format!("the dst should be a string")))?;
-
-        Ok(TargetCfg { tp, buf_size, dst })
+        v.into_struct()
     }
 }
 
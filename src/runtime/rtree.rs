@@ -1,24 +1,34 @@
 pub mod analyzer;
+pub mod artifact;
 pub mod builder;
+pub mod diff;
 pub mod iter;
 pub mod macros;
+pub mod query;
 pub mod rnode;
 pub mod transform;
 
 use crate::runtime::action::ActionName;
-use crate::runtime::args::transform::{to_dec_rt_args, to_rt_args};
+use crate::runtime::args::transform::{
+    inline_consts, to_dec_rt_args, to_lambda_rt_args, to_rt_args,
+};
+use crate::runtime::args::{RtArgs, RtArgument, RtValue};
 
-use crate::runtime::rtree::rnode::{DecoratorType, RNode, RNodeId};
+use crate::runtime::rtree::rnode::{DecoratorType, FlowType, RNode, RNodeId, RNodeName};
 use crate::runtime::rtree::transform::{StackItem, Transformer};
 use crate::runtime::{RtOk, RtResult, RuntimeError};
 use crate::tree::parser::ast::call::Call;
+use crate::tree::parser::ast::Key;
 
 use crate::runtime::rtree::analyzer::RtTreeAnalyzer;
-use crate::runtime::rtree::iter::RtTreeBfsIter;
+use crate::runtime::rtree::iter::{RtTreeBfsIter, RtTreeDfsIter};
+use crate::tree::project::graph::{CallGraph, Def};
 use crate::tree::project::imports::ImportMap;
 use crate::tree::project::{FileName, Project};
 use crate::tree::{cerr, TreeError};
+use itertools::Itertools;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Formatter};
 use std::path::PathBuf;
 use crate::converter::Converter;
 use crate::converter::to_nav::ToRosNavConverter;
@@ -40,6 +50,45 @@ pub struct RuntimeTreeStarter {
 pub struct RuntimeTree {
     pub root: RNodeId,
     pub nodes: HashMap<RNodeId, RNode>,
+    /// The nodes that are not instantiated yet if the tree is built lazily,
+    /// see [`RuntimeTree::build_lazy`].
+    pub expansion: Option<Expansion>,
+}
+
+/// The expansion of the project into the runtime tree.
+/// When the tree is built lazily, it keeps the calls of the nodes that are not instantiated yet
+/// along with the stack trace of the calls to instantiate the nodes later on.
+pub struct Expansion {
+    project: Project,
+    depth: Option<usize>,
+    builder: Transformer,
+    // the definitions of the expanded trees to count the recursive invocations
+    defs: HashMap<RNodeId, Def>,
+    // the postponed calls by the ids of the nodes
+    pending: HashMap<RNodeId, StackItem>,
+    std_actions: HashSet<(ActionName, FileName)>,
+    actions: HashSet<ActionName>,
+}
+
+impl Debug for Expansion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Expansion")
+            .field("depth", &self.depth)
+            .field("pending", &self.pending.keys().sorted().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// The expansions are equal if they have the same nodes postponed.
+impl PartialEq for Expansion {
+    fn eq(&self, other: &Self) -> bool {
+        self.depth == other.depth
+            && self
+                .pending
+                .keys()
+                .sorted()
+                .eq(other.pending.keys().sorted())
+    }
 }
 
 impl RuntimeTree {
@@ -50,6 +99,17 @@ impl RuntimeTree {
             tree: &self,
         }
     }
+    /// Returns dfs iterator over the runtime tree
+    pub fn dfs(&self) -> RtTreeDfsIter<'_> {
+        self.dfs_from(self.root)
+    }
+    /// Returns dfs iterator over the subtree starting from the given node
+    pub fn dfs_from(&self, id: RNodeId) -> RtTreeDfsIter<'_> {
+        RtTreeDfsIter {
+            stack: vec![id],
+            tree: self,
+        }
+    }
     /// Returns the analyzer for the runtime tree
     /// which provides methods to analyze the tree
     /// and find nodes by some criteria
@@ -93,89 +153,349 @@ impl RuntimeTree {
     pub fn analyze(&self) -> RtTreeAnalyzer<'_> {
         RtTreeAnalyzer::new(self)
     }
-    /// Builds the runtime tree from the project
+    /// Builds the runtime tree from the project.
+    /// The trees invoking themselves are the error since they can not be expanded,
+    /// see [`Project::check_recursion`].
     pub fn build(project: Project) -> Result<RuntimeTreeStarter, TreeError> {
-        let (file, name) = &project.main;
-        let root = project.find_root(name, file)?;
-        let mut builder = Transformer::default();
+        project.check_recursion()?;
+        RuntimeTree::expand(project, None)
+    }
+
+    /// Builds the runtime tree from the project expanding every tree
+    /// at most `depth` times along every branch, that suits the intentionally recursive trees.
+    /// The invocation exceeding the depth turns into the action `fail` from `std::actions`.
+    pub fn build_with_depth(
+        project: Project,
+        depth: usize,
+    ) -> Result<RuntimeTreeStarter, TreeError> {
+        RuntimeTree::expand(project, Some(depth))
+    }
+
+    /// Builds the runtime tree from the project instantiating the nodes only on demand.
+    /// Initially, the tree has only the root node and the rest of the nodes are instantiated
+    /// when the parent ticks them for the first time, see [`RuntimeTree::instantiate`].
+    ///
+    /// The depth bounds the recursive invocations the same way as [`RuntimeTree::build_with_depth`] does.
+    /// Without the depth, the trees invoking themselves are the error.
+    ///
+    /// # Note
+    /// The actions are taken from all trees that can be invoked from the root
+    /// since the implementations are needed before the nodes are instantiated.
+    /// The errors of the expansion that are found in the subtrees turn up only when the subtree is ticked.
+    pub fn build_lazy(
+        project: Project,
+        depth: Option<usize>,
+    ) -> Result<RuntimeTreeStarter, TreeError> {
+        if depth.is_none() {
+            project.check_recursion()?;
+        }
+        let mut expansion = Expansion::new(project, depth);
         let mut r_tree = RuntimeTree::default();
-        let mut std_actions = HashSet::new();
-        let mut actions = HashSet::new();
+        r_tree.root = expansion.root(&mut r_tree.nodes)?;
+        expansion.postpone();
+        let (std_actions, actions) = expansion.reachable_actions();
+        r_tree.expansion = Some(expansion);
+        Ok(RuntimeTreeStarter {
+            tree: r_tree,
+            std_actions,
+            actions,
+        })
+    }
+
+    fn expand(project: Project, depth: Option<usize>) -> Result<RuntimeTreeStarter, TreeError> {
+        let mut expansion = Expansion::new(project, depth);
+        let mut r_tree = RuntimeTree::default();
+        r_tree.root = expansion.root(&mut r_tree.nodes)?;
+        while let Some(item) = expansion.builder.pop() {
+            expansion.step(&mut r_tree.nodes, item)?;
+        }
+        Ok(RuntimeTreeStarter {
+            tree: r_tree,
+            std_actions: expansion.std_actions,
+            actions: expansion.actions,
+        })
+    }
 
-        let root_id = builder.next();
-        builder.add_chain_root(root_id);
+    /// Instantiates the node if the tree is built lazily and the node is not instantiated yet.
+    /// The children of the node get their ids but stay postponed until they are ticked in turn.
+    pub fn instantiate(&mut self, id: RNodeId) -> Result<(), TreeError> {
+        match self.expansion.as_mut() {
+            Some(expansion) => expansion.instantiate(&mut self.nodes, id),
+            None => Ok(()),
+        }
+    }
 
-        let children = builder.push_vec(root.calls.clone(), root_id, file.clone());
-        let root_node = RNode::root(root.name.to_string(), file.clone(), children);
-        r_tree.root = root_id;
-        r_tree.nodes.insert(root_id, root_node);
+    /// Instantiates all postponed nodes of the tree built lazily.
+    pub fn instantiate_all(&mut self) -> Result<(), TreeError> {
+        while let Some(id) = self.pending().into_iter().min() {
+            self.instantiate(id)?;
+        }
+        Ok(())
+    }
 
-        while let Some(item) = builder.pop() {
-            let StackItem {
-                id,
-                call,
-                parent_id,
-                file_name,
-            } = item;
+    /// Returns the ids of the nodes that are not instantiated yet.
+    pub fn pending(&self) -> Vec<RNodeId> {
+        self.expansion
+            .as_ref()
+            .map(|e| e.pending.keys().cloned().collect())
+            .unwrap_or_default()
+    }
 
-            let curr_file = &project.find_file(file_name.as_str())?;
-            let import_map = ImportMap::build(curr_file)?;
+    /// Puts the node into the tree replacing the previous one with the same id.
+    /// The postponed node with the same id is dropped
+    /// and the id is not given to the nodes instantiated later on.
+    pub fn insert(&mut self, id: RNodeId, node: RNode) -> Option<RNode> {
+        if let Some(expansion) = self.expansion.as_mut() {
+            expansion.pending.remove(&id);
+            expansion.builder.reserve(id);
+        }
+        self.nodes.insert(id, node)
+    }
+    /// Returns the node by id
+    pub fn node(&self, id: &RNodeId) -> RtResult<&RNode> {
+        self.nodes.get(id).ok_or(RuntimeError::uex(format!(
+            "the node {id} is not found in the rt tree"
+        )))
+    }
+
+    /// find the max given id in the tree
+    /// including the ids given to the nodes that are not instantiated yet.
+    pub fn max_id(&self) -> RNodeId {
+        let max = self.nodes.keys().max().cloned().unwrap_or_default();
+        self.expansion
+            .as_ref()
+            .map_or(max, |e| max.max(e.builder.last()))
+    }
+
+    /// Converts the runtime tree into the ROS navigation xml file
+    pub fn to_ros_nav(&self, xml: PathBuf) -> RtOk {
+        ToRosNavConverter::new(&self, xml).convert()
+    }
+}
+
+impl Expansion {
+    fn new(project: Project, depth: Option<usize>) -> Self {
+        Self {
+            project,
+            depth,
+            builder: Transformer::default(),
+            defs: HashMap::new(),
+            pending: HashMap::new(),
+            std_actions: HashSet::new(),
+            actions: HashSet::new(),
+        }
+    }
+
+    /// puts the root node into the tree and pushes its children on the stack
+    fn root(&mut self, nodes: &mut HashMap<RNodeId, RNode>) -> Result<RNodeId, TreeError> {
+        let (file, name) = &self.project.main;
+        let root = self.project.find_root(name, file)?;
+        let root_id = self.builder.next();
+        self.builder.add_chain_root(root_id);
+
+        let children = self
+            .builder
+            .push_vec(root.calls.clone(), root_id, file.clone());
+        nodes.insert(
+            root_id,
+            RNode::root(root.name.to_string(), file.clone(), children),
+        );
+        Ok(root_id)
+    }
+
+    /// moves the calls from the stack to the postponed ones
+    fn postpone(&mut self) {
+        while let Some(item) = self.builder.pop() {
+            self.pending.insert(item.id, item);
+        }
+    }
+
+    /// takes the postponed calls of the node until the node is instantiated.
+    /// The higher order invocation is resolved into another call with the same id,
+    /// thus it can take several steps.
+    fn instantiate(
+        &mut self,
+        nodes: &mut HashMap<RNodeId, RNode>,
+        id: RNodeId,
+    ) -> Result<(), TreeError> {
+        while !nodes.contains_key(&id) {
+            let Some(item) = self.pending.remove(&id) else {
+                break;
+            };
+            self.step(nodes, item)?;
+            self.postpone();
+        }
+        Ok(())
+    }
+
+    /// the actions of the trees that can be invoked from the root
+    fn reachable_actions(&self) -> (HashSet<(ActionName, FileName)>, HashSet<ActionName>) {
+        let mut std_actions = HashSet::new();
+        let mut actions = HashSet::new();
+        for (file, name) in CallGraph::build(&self.project).reachable(&self.project.main) {
+            let is_action = self
+                .project
+                .files
+                .get(&file)
+                .and_then(|f| f.definitions.get(&name))
+                .is_some_and(|tree| tree.tpe.is_action());
+            if is_action {
+                if self.project.libs.modules.contains(&file) {
+                    std_actions.insert((name.clone(), file));
+                }
+                actions.insert(name);
+            }
+        }
+        // the invocations exceeding the depth turn into the action
+        if self.depth.is_some() {
+            std_actions.insert(("fail".to_string(), "std::actions".to_string()));
+            actions.insert("fail".to_string());
+        }
+        (std_actions, actions)
+    }
+
+    /// instantiates the node of the call pushing the children on the stack
+    fn step(
+        &mut self,
+        nodes: &mut HashMap<RNodeId, RNode>,
+        item: StackItem,
+    ) -> Result<(), TreeError> {
+        let StackItem {
+            id,
+            call,
+            parent_id,
+            file_name,
+            origin,
+        } = item;
+
+        let curr_file = &self.project.find_file(file_name.as_str())?;
+        let import_map = ImportMap::build(curr_file)?;
+        // the constants are taken from the file the call is written down in
+        let origin_file = self.project.find_file(origin.as_str())?;
+        let origin_imports = ImportMap::build(origin_file)?;
+        let consts = |key: &Key| self.project.find_const(origin_file, &origin_imports, key);
+        let span = call.span();
+        let step = || -> Result<(), TreeError> {
             match call {
-                // for lambda there is not many actions since it does not have arguments so just grab a type and children
-                Call::Lambda(tpe, calls) => {
+                // for lambda there is not many actions since it has only the arguments of the parallel policy,
+                // so just grab a type, the policy and children
+                Call::Lambda(tpe, args, calls, _) => {
                     debug!(target:"tree[construct]", "found lambda {tpe}: id {id} and parent {parent_id}");
-                    let children = builder.push_vec(calls, id, file_name.clone());
-                    builder.add_chain_lambda(id, parent_id);
-                    r_tree
-                        .nodes
-                        .insert(id, RNode::lambda(tpe.try_into()?, children));
+                    let f_tpe: FlowType = tpe.try_into()?;
+                    let rt_args = if args.args.is_empty() {
+                        RtArgs::default()
+                    } else {
+                        let (_, parent_args, parent_params) =
+                            self.builder.get_chain_skip_lambda(&parent_id)?.get_tree();
+                        let tree_id = self.builder.get_tree_id_skip_lambda(&parent_id)?;
+                        let tree = nodes
+                            .get(&tree_id)
+                            .and_then(RNode::name)
+                            .and_then(|n| n.name().ok())
+                            .cloned()
+                            .unwrap_or_default();
+                        let args = inline_consts(args, &parent_params, &consts)?;
+                        to_lambda_rt_args(
+                            &f_tpe,
+                            &tree,
+                            args,
+                            calls.elems.len(),
+                            parent_args,
+                            parent_params,
+                        )?
+                    };
+                    let children = self.builder.push_vec(calls, id, file_name.clone());
+                    self.builder.add_chain_lambda(id, parent_id);
+                    nodes.insert(id, RNode::Flow(f_tpe, RNodeName::Lambda, rt_args, children));
                 }
                 // for higher order invocation there are two possible cases:
                 // - the invocation is passed as an argument from the parent (this chain can be long up)
                 //   So we need to find the initially passed call.
                 // - since we found it we transform it into a simple invocation call and process it at the next step.
                 // - if it is lambda we already found it
-                Call::HoInvocation(key) => {
+                Call::HoInvocation(key, _) => {
                     debug!(target:"tree[construct]", "found ho invocation with id {id} in parent {parent_id}");
                     let (p_id, _parent_args, _parent_params) =
-                    builder.get_chain_skip_lambda(&parent_id)?.get_tree();
-                    let call = builder.find_ho_call(&parent_id, &key)?;
+                        self.builder.get_chain_skip_lambda(&parent_id)?.get_tree();
+                    // the passed call is resolved in the file where it is written down
+                    let (call, call_file) = self.builder.find_ho_call(&parent_id, &key)?;
                     if call.is_lambda() || call.is_decorator() {
-                        builder.push_front(id, call, p_id, file_name.clone());
+                        self.builder.push_front(id, call, p_id, call_file);
                     } else {
                         let k = call
                             .key()
                             .ok_or(cerr(format!("the call {:?} does not have a name. Therefore, it is no possible to invoke it by name.", call)))?;
 
-                        builder.push_front(
+                        self.builder.push_front(
                             id,
                             Call::invocation(&k, call.arguments()),
                             p_id,
-                            file_name.clone(),
+                            call_file,
                         );
                     }
                 }
                 // just take the arguments and transform them into runtime args and push further
-                Call::Decorator(tpe, decor_args, call) => {
+                Call::Decorator(tpe, decor_args, call, _) => {
                     debug!(target:"tree[construct]", "found decorator {tpe}, id {id} in parent {parent_id}");
-                    let (_, parent_args, parent_params) =
-                        builder.get_chain_skip_lambda(&parent_id)?.get_tree();
-                    builder.add_chain(id, parent_id, parent_args.clone(), parent_params.clone());
-                    let child = builder.push(*call, id, file.clone());
+                    let parent = self.builder.get_chain_skip_lambda(&parent_id)?;
+                    let (_, parent_args, parent_params) = parent.get_tree();
+                    let parent_file = parent.file().cloned().unwrap_or(origin.clone());
+                    self.builder.add_chain(
+                        id,
+                        parent_id,
+                        parent_args.clone(),
+                        parent_params.clone(),
+                        parent_file,
+                    );
+                    let child =
+                        self.builder
+                            .push_from(*call, id, file_name.clone(), origin.clone());
                     let d_tpe: DecoratorType = tpe.try_into()?;
+                    let decor_args = inline_consts(decor_args, &parent_params, &consts)?;
                     let rt_args = to_dec_rt_args(&d_tpe, decor_args, parent_args, parent_params)?;
-                    r_tree
-                        .nodes
-                        .insert(id, RNode::decorator(d_tpe, rt_args, child));
+                    nodes.insert(id, RNode::decorator(d_tpe, rt_args, child));
                 }
                 // firstly we need to find the definition either in the file or in the imports
                 // with a consideration of a possible alias and transform the args
-                Call::Invocation(name, args) => {
+                Call::Invocation(name, args, _) => {
                     debug!(target:"tree[construct]", "found invocation , id {id} in parent {parent_id}");
-                    let (_, parent_args, parent_params) = builder
+                    let (_, parent_args, parent_params) = self
+                        .builder
                         .get_chain_skip_lambda(&parent_id)
                         .map(|e| e.get_tree())
                         .unwrap_or_default();
+                    let args = inline_consts(args, &parent_params, &consts)?;
+                    if let Some(depth) = self.depth {
+                        let def = match curr_file.definitions.get(&name) {
+                            Some(tree) => (curr_file.name.clone(), tree.name.clone()),
+                            None => {
+                                let (tree, file) = import_map.find(&name, &self.project)?;
+                                (file.clone(), tree.name.clone())
+                            }
+                        };
+                        let invoked = self
+                            .builder
+                            .count_up(parent_id, |id| self.defs.get(&id) == Some(&def))?;
+                        if invoked >= depth {
+                            let reason = format!(
+                                "the recursion depth {depth} of the tree {} is exceeded",
+                                def.1
+                            );
+                            let args = RtArgs(vec![RtArgument::new(
+                                "reason".to_string(),
+                                RtValue::str(reason),
+                            )]);
+                            self.std_actions
+                                .insert(("fail".to_string(), "std::actions".to_string()));
+                            self.actions.insert("fail".to_string());
+                            nodes.insert(
+                                id,
+                                RNode::action("fail".to_string(), "std::actions".to_string(), args),
+                            );
+                            return Ok(());
+                        }
+                        self.defs.insert(id, def);
+                    }
                     match curr_file.definitions.get(&name) {
                         Some(tree) => {
                             let (rt_args, upd_args) = to_rt_args(
@@ -185,24 +505,47 @@ impl RuntimeTree {
                                 parent_args,
                                 parent_params,
                             )?;
-                            builder.add_chain(id, parent_id, upd_args, tree.params.clone());
+                            self.builder.add_chain(
+                                id,
+                                parent_id,
+                                upd_args,
+                                tree.params.clone(),
+                                origin.clone(),
+                            );
                             if tree.tpe.is_action() {
-                                r_tree.nodes.insert(id, RNode::action(name, curr_file.name.clone(), rt_args));
-                                actions.insert(tree.name.clone());
+                                // the trees of the module call its actions
+                                if self.project.libs.modules.contains(&curr_file.name) {
+                                    self.std_actions
+                                        .insert((tree.name.clone(), curr_file.name.clone()));
+                                }
+                                nodes.insert(
+                                    id,
+                                    RNode::action(name, curr_file.name.clone(), rt_args),
+                                );
+                                self.actions.insert(tree.name.clone());
                             } else {
-                                let children =
-                                    builder.push_vec(tree.calls.clone(), id, file_name.clone());
-                                r_tree.nodes.insert(
+                                let children = self.builder.push_vec(
+                                    tree.calls.clone(),
+                                    id,
+                                    file_name.clone(),
+                                );
+                                nodes.insert(
                                     id,
-                                    RNode::flow(tree.tpe.try_into()?, name, curr_file.name.clone(), rt_args, children),
+                                    RNode::flow(
+                                        tree.tpe.try_into()?,
+                                        name,
+                                        curr_file.name.clone(),
+                                        rt_args,
+                                        children,
+                                    ),
                                 );
                             }
                         }
                         None => {
                             debug!(target:"tree[construct]", "found import from another file,  id {id} in parent {parent_id}");
-                            let (tree, file) = import_map.find(&name, &project)?;
-                            if file.contains("::") {
-                                std_actions.insert((tree.name.clone(), file.clone()));
+                            let (tree, file) = import_map.find(&name, &self.project)?;
+                            if tree.tpe.is_action() && self.project.libs.modules.contains(file) {
+                                self.std_actions.insert((tree.name.clone(), file.clone()));
                             }
                             let (rt_args, upd_args) = to_rt_args(
                                 name.as_str(),
@@ -211,19 +554,31 @@ impl RuntimeTree {
                                 parent_args,
                                 parent_params,
                             )?;
-                            builder.add_chain(id, parent_id, upd_args, tree.params.clone());
+                            self.builder.add_chain(
+                                id,
+                                parent_id,
+                                upd_args,
+                                tree.params.clone(),
+                                origin.clone(),
+                            );
+                            // the children are resolved in the file the tree is defined in
                             let children =
-                                builder.push_vec(tree.calls.clone(), id, file_name.clone());
+                                self.builder.push_vec(tree.calls.clone(), id, file.clone());
 
                             if tree.name != name {
                                 if tree.tpe.is_action() {
-                                    actions.insert(tree.name.clone());
-                                    r_tree.nodes.insert(
+                                    self.actions.insert(tree.name.clone());
+                                    nodes.insert(
                                         id,
-                                        RNode::action_alias(tree.name.clone(), file.clone(), name, rt_args),
+                                        RNode::action_alias(
+                                            tree.name.clone(),
+                                            file.clone(),
+                                            name,
+                                            rt_args,
+                                        ),
                                     );
                                 } else {
-                                    r_tree.nodes.insert(
+                                    nodes.insert(
                                         id,
                                         RNode::flow_alias(
                                             tree.tpe.try_into()?,
@@ -236,43 +591,32 @@ impl RuntimeTree {
                                     );
                                 }
                             } else if tree.tpe.is_action() {
-                                r_tree
-                                    .nodes
+                                nodes
                                     .insert(id, RNode::action(name.clone(), file.clone(), rt_args));
-                                actions.insert(name);
+                                self.actions.insert(name);
                             } else {
-                                r_tree.nodes.insert(
+                                nodes.insert(
                                     id,
-                                    RNode::flow(tree.tpe.try_into()?, name, file.clone(), rt_args, children),
+                                    RNode::flow(
+                                        tree.tpe.try_into()?,
+                                        name,
+                                        file.clone(),
+                                        rt_args,
+                                        children,
+                                    ),
                                 );
                             };
                         }
                     }
                 }
             }
-        }
-
-        Ok(RuntimeTreeStarter {
-            tree: r_tree,
-            std_actions,
-            actions,
-        })
-    }
-    /// Returns the node by id
-    pub fn node(&self, id: &RNodeId) -> RtResult<&RNode> {
-        self.nodes.get(id).ok_or(RuntimeError::uex(format!(
-            "the node {id} is not found in the rt tree"
-        )))
-    }
-
-    /// find the max given id in the tree
-    pub fn max_id(&self) -> RNodeId {
-        self.nodes.keys().max().cloned().unwrap_or_default()
-    }
-
-    /// Converts the runtime tree into the ROS navigation xml file
-    pub fn to_ros_nav(&self, xml: PathBuf) -> RtOk {
-        ToRosNavConverter::new(&self, xml).convert()
+            Ok(())
+        };
+        step().map_err(|e| match self.project.files.get(&origin) {
+            Some(origin_file) => origin_file.locate(e, span),
+            None => e,
+        })?;
+        Ok(())
     }
 }
 
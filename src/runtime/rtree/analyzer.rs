@@ -1,6 +1,8 @@
+use crate::runtime::rtree::query::Selector;
 use crate::runtime::rtree::rnode::RNodeId;
 use crate::runtime::rtree::RNode;
 use crate::runtime::rtree::RuntimeTree;
+use crate::runtime::RtResult;
 use std::collections::HashMap;
 
 /// Runtime tree analyzer
@@ -22,10 +24,104 @@ impl<'a> RtTreeAnalyzer<'a> {
 
         Self { tree, parents }
     }
+    /// Returns the tree being analyzed
+    pub fn tree(&self) -> &'a RuntimeTree {
+        self.tree
+    }
     /// Returns the parent of the node with the given id
     pub fn parent(&self, id: &RNodeId) -> Option<&RNodeId> {
         self.parents.get(id)
     }
+    /// Returns the ancestors of the node from the parent up to the root
+    pub fn ancestors(&self, id: RNodeId) -> Vec<RNodeId> {
+        let mut ancestors = vec![];
+        let mut current = id;
+        while let Some(p) = self.parents.get(&current) {
+            ancestors.push(*p);
+            current = *p;
+        }
+        ancestors
+    }
+    /// Returns the instantiated children of the node
+    pub fn children(&self, id: RNodeId) -> Vec<RNodeId> {
+        self.tree
+            .nodes
+            .get(&id)
+            .map(|n| n.children())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| self.tree.nodes.contains_key(c))
+            .collect()
+    }
+    /// Returns the descendants of the node in dfs order
+    pub fn descendants(&self, id: RNodeId) -> Vec<RNodeId> {
+        self.tree.dfs_from(id).skip(1).map(|(id, _)| id).collect()
+    }
+    /// Returns the other children of the parent of the node
+    pub fn siblings(&self, id: RNodeId) -> Vec<RNodeId> {
+        self.parents
+            .get(&id)
+            .map(|p| self.children(*p))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| *s != id)
+            .collect()
+    }
+    /// Returns the siblings standing before the node, the closest one goes first
+    pub fn prev_siblings(&self, id: RNodeId) -> Vec<RNodeId> {
+        let siblings = self
+            .parents
+            .get(&id)
+            .map(|p| self.children(*p))
+            .unwrap_or_default();
+        siblings
+            .iter()
+            .rev()
+            .skip_while(|s| **s != id)
+            .skip(1)
+            .cloned()
+            .collect()
+    }
+    /// Returns the siblings standing after the node, the closest one goes first
+    pub fn next_siblings(&self, id: RNodeId) -> Vec<RNodeId> {
+        let siblings = self
+            .parents
+            .get(&id)
+            .map(|p| self.children(*p))
+            .unwrap_or_default();
+        siblings
+            .iter()
+            .skip_while(|s| **s != id)
+            .skip(1)
+            .cloned()
+            .collect()
+    }
+    /// Returns the sibling standing right before the node
+    pub fn prev_sibling(&self, id: RNodeId) -> Option<RNodeId> {
+        self.prev_siblings(id).first().cloned()
+    }
+    /// Returns the sibling standing right after the node
+    pub fn next_sibling(&self, id: RNodeId) -> Option<RNodeId> {
+        self.next_siblings(id).first().cloned()
+    }
+
+    /// Returns the nodes matching the selector in dfs order
+    pub fn select(&self, selector: &Selector) -> Vec<RNodeId> {
+        self.tree
+            .dfs()
+            .map(|(id, _)| id)
+            .filter(|id| selector.matches(self, *id))
+            .collect()
+    }
+    /// Returns the nodes matching the selector given as a string in dfs order,
+    /// see [`crate::runtime::rtree::query`] for the syntax.
+    pub fn query(&self, selector: &str) -> RtResult<Vec<RNodeId>> {
+        Ok(self.select(&Selector::parse(selector)?))
+    }
+    /// Returns the first node matching the selector given as a string in dfs order
+    pub fn query_first(&self, selector: &str) -> RtResult<Option<RNodeId>> {
+        Ok(self.query(selector)?.first().cloned())
+    }
 
     /// Returns the node_id by filter
     pub fn find_id_by<F>(&self, filter: F) -> Option<RNodeId>
@@ -0,0 +1,335 @@
+//! The compiled runtime tree that can be saved and loaded later on
+//! without parsing and expanding the project again.
+//!
+//! The artifact keeps the nodes of the tree along with the names of the actions
+//! and comes in two formats:
+//! - json that is readable and suits the reviews
+//! - binary that starts with the magic bytes and the version followed by the bincode encoding
+//!
+//! The hash of the artifact does not depend on the format and can be used
+//! to verify what is running.
+use crate::runtime::action::ActionName;
+use crate::runtime::args::{RtArgs, RtArgument, RtValue, RtValueNumber};
+use crate::runtime::blackboard::BBKey;
+use crate::runtime::rtree::rnode::{DecoratorType, FlowType, RNode, RNodeId, RNodeName};
+use crate::runtime::rtree::{RuntimeTree, RuntimeTreeStarter};
+use crate::runtime::{RtOk, RtResult, RuntimeError};
+use crate::tree::diagnostic::Span;
+use crate::tree::parser::ast::arg::{Argument as AstArgument, ArgumentRhs, Arguments, ParamDir};
+use crate::tree::parser::ast::call::{Call, Calls};
+use crate::tree::parser::ast::expr::Expr;
+use crate::tree::project::FileName;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// The version of the artifact layout.
+/// The artifacts of the other versions are rejected when they are loaded.
+pub const ARTIFACT_VERSION: u32 = 1;
+
+/// The bytes the binary artifact starts with
+const MAGIC: &[u8; 4] = b"FRST";
+
+/// The format of the saved artifact
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArtifactFormat {
+    Json,
+    Binary,
+}
+
+/// The runtime tree compiled from the project along with the actions it needs.
+///
+/// # Example
+/// ```no_run
+/// use std::path::PathBuf;
+/// use forester_rs::runtime::builder::ForesterBuilder;
+/// use forester_rs::runtime::rtree::artifact::ArtifactFormat;
+///
+/// fn compile(root: PathBuf) {
+///     let mut fb = ForesterBuilder::from_fs();
+///     fb.main_file("main.tree".to_string());
+///     fb.root(root);
+///     let artifact = fb.compile().unwrap();
+///     artifact
+///         .save(&PathBuf::from("main.frst"), ArtifactFormat::Binary)
+///         .unwrap();
+///     println!("the hash of the tree is {}", artifact.hash().unwrap());
+/// }
+/// ```
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TreeArtifact {
+    pub version: u32,
+    pub root: RNodeId,
+    nodes: BTreeMap<RNodeId, Node>,
+    pub std_actions: BTreeSet<(ActionName, FileName)>,
+    pub actions: BTreeSet<ActionName>,
+}
+
+/// The header is read before the whole artifact to check the version.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl TreeArtifact {
+    /// Compiles the artifact from the runtime tree.
+    /// The tree built lazily needs to be instantiated entirely beforehand,
+    /// see [`RuntimeTree::instantiate_all`].
+    pub fn compile(starter: &RuntimeTreeStarter) -> RtResult<Self> {
+        let pending = starter.tree.pending();
+        if !pending.is_empty() {
+            return Err(RuntimeError::ExportError(format!(
+                "the nodes {pending:?} are not instantiated yet. The tree built lazily needs to be instantiated entirely"
+            )));
+        }
+        Ok(TreeArtifact {
+            version: ARTIFACT_VERSION,
+            root: starter.tree.root,
+            nodes: starter
+                .tree
+                .nodes
+                .iter()
+                .map(|(id, node)| (*id, Node::from(node)))
+                .collect(),
+            std_actions: starter.std_actions.iter().cloned().collect(),
+            actions: starter.actions.iter().cloned().collect(),
+        })
+    }
+
+    /// Restores the runtime tree along with the actions.
+    pub fn starter(self) -> RuntimeTreeStarter {
+        RuntimeTreeStarter {
+            tree: RuntimeTree {
+                root: self.root,
+                nodes: self
+                    .nodes
+                    .into_iter()
+                    .map(|(id, node)| (id, node.into()))
+                    .collect(),
+                expansion: None,
+            },
+            std_actions: self.std_actions.into_iter().collect(),
+            actions: self.actions.into_iter().collect(),
+        }
+    }
+
+    /// The hex encoded sha256 of the artifact.
+    /// The hash is taken from the json representation with the sorted keys,
+    /// thus it is the same for both formats.
+    pub fn hash(&self) -> RtResult<String> {
+        let value = serde_json::to_value(self)?;
+        let digest = Sha256::digest(serde_json::to_vec(&value)?);
+        Ok(digest.iter().map(|b| format!("{b:02x}")).collect())
+    }
+
+    pub fn to_json(&self) -> RtResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_bytes(&self) -> RtResult<Vec<u8>> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(self.version.to_le_bytes());
+        bytes.extend(bincode::serialize(self)?);
+        Ok(bytes)
+    }
+
+    /// Reads the artifact in either format.
+    ///
+    /// # Errors
+    /// The artifact of another version is an error.
+    pub fn from_bytes(bytes: &[u8]) -> RtResult<Self> {
+        match bytes.strip_prefix(MAGIC) {
+            Some(rest) => {
+                let version = rest
+                    .get(..4)
+                    .and_then(|v| v.try_into().ok())
+                    .map(u32::from_le_bytes)
+                    .ok_or(RuntimeError::IOError(
+                        "the binary artifact does not have a version".to_string(),
+                    ))?;
+                check_version(version)?;
+                Ok(bincode::deserialize(&rest[4..])?)
+            }
+            None => {
+                let header: Header = serde_json::from_slice(bytes)?;
+                check_version(header.version)?;
+                Ok(serde_json::from_slice(bytes)?)
+            }
+        }
+    }
+
+    pub fn save(&self, path: &PathBuf, format: ArtifactFormat) -> RtOk {
+        let bytes = match format {
+            ArtifactFormat::Json => self.to_json()?.into_bytes(),
+            ArtifactFormat::Binary => self.to_bytes()?,
+        };
+        Ok(fs::write(path, bytes)?)
+    }
+
+    pub fn load(path: &PathBuf) -> RtResult<Self> {
+        TreeArtifact::from_bytes(&fs::read(path)?)
+    }
+}
+
+fn check_version(version: u32) -> RtOk {
+    if version == ARTIFACT_VERSION {
+        Ok(())
+    } else {
+        Err(RuntimeError::IOError(format!(
+            "the artifact has the version {version} but the version {ARTIFACT_VERSION} is expected"
+        )))
+    }
+}
+
+// The runtime values are serialized into the bare values for the blackboard and the remote actions
+// losing the pointers, calls and the kinds of numbers, thus the artifact keeps its own copy of them.
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Node {
+    Leaf(RNodeName, Vec<Argument>),
+    Flow(FlowType, RNodeName, Vec<Argument>, Vec<RNodeId>),
+    Decorator(DecoratorType, Vec<Argument>, RNodeId),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Argument {
+    name: String,
+    value: Value,
+    dir: ParamDir,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Value {
+    String(String),
+    Bool(bool),
+    Array(Vec<Value>),
+    Object(BTreeMap<String, Value>),
+    Number(RtValueNumber),
+    Pointer(BBKey),
+    Call(Call),
+    Expr(Expr),
+}
+
+fn args(args: &RtArgs) -> Vec<Argument> {
+    args.0
+        .iter()
+        .map(|a| Argument {
+            name: a.name.clone(),
+            value: Value::from(&a.value),
+            dir: a.dir,
+        })
+        .collect()
+}
+
+fn rt_args(args: Vec<Argument>) -> RtArgs {
+    RtArgs(
+        args.into_iter()
+            .map(|a| RtArgument {
+                name: a.name,
+                value: a.value.into(),
+                dir: a.dir,
+            })
+            .collect(),
+    )
+}
+
+impl From<&RNode> for Node {
+    fn from(node: &RNode) -> Self {
+        match node {
+            RNode::Leaf(name, a) => Node::Leaf(name.clone(), args(a)),
+            RNode::Flow(tpe, name, a, children) => {
+                Node::Flow(*tpe, name.clone(), args(a), children.clone())
+            }
+            RNode::Decorator(tpe, a, child) => Node::Decorator(*tpe, args(a), *child),
+        }
+    }
+}
+
+impl From<Node> for RNode {
+    fn from(node: Node) -> Self {
+        match node {
+            Node::Leaf(name, a) => RNode::Leaf(name, rt_args(a)),
+            Node::Flow(tpe, name, a, children) => RNode::Flow(tpe, name, rt_args(a), children),
+            Node::Decorator(tpe, a, child) => RNode::Decorator(tpe, rt_args(a), child),
+        }
+    }
+}
+
+impl From<&RtValue> for Value {
+    fn from(value: &RtValue) -> Self {
+        match value {
+            RtValue::String(v) => Value::String(v.clone()),
+            RtValue::Bool(v) => Value::Bool(*v),
+            RtValue::Array(elems) => Value::Array(elems.iter().map(Value::from).collect()),
+            RtValue::Object(elems) => Value::Object(
+                elems
+                    .iter()
+                    .map(|(k, v)| (k.clone(), Value::from(v)))
+                    .collect(),
+            ),
+            RtValue::Number(v) => Value::Number(v.clone()),
+            RtValue::Pointer(v) => Value::Pointer(v.clone()),
+            RtValue::Call(v) => Value::Call(without_spans(v.clone())),
+            RtValue::Expr(v) => Value::Expr(v.clone()),
+        }
+    }
+}
+
+// The spans point to the places in the source text,
+// thus they are dropped to keep the hash independent of the formatting and comments.
+fn without_spans(call: Call) -> Call {
+    let args = |args: Arguments| Arguments {
+        args: args
+            .args
+            .into_iter()
+            .map(|a| {
+                let a = match a {
+                    AstArgument::Assigned(k, ArgumentRhs::Call(c), s) => {
+                        AstArgument::Assigned(k, ArgumentRhs::Call(without_spans(c)), s)
+                    }
+                    AstArgument::Unassigned(ArgumentRhs::Call(c), s) => {
+                        AstArgument::Unassigned(ArgumentRhs::Call(without_spans(c)), s)
+                    }
+                    a => a,
+                };
+                a.with_span(Span::default())
+            })
+            .collect(),
+    };
+    let call = match call {
+        Call::Invocation(k, a, s) => Call::Invocation(k, args(a), s),
+        Call::Lambda(tpe, a, calls, s) => Call::Lambda(
+            tpe,
+            args(a),
+            Calls::new(calls.elems.into_iter().map(without_spans).collect()),
+            s,
+        ),
+        Call::Decorator(tpe, a, c, s) => {
+            Call::Decorator(tpe, args(a), Box::new(without_spans(*c)), s)
+        }
+        c @ Call::HoInvocation(..) => c,
+    };
+    call.with_span(Span::default())
+}
+
+impl From<Value> for RtValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::String(v) => RtValue::String(v),
+            Value::Bool(v) => RtValue::Bool(v),
+            Value::Array(elems) => RtValue::Array(elems.into_iter().map(RtValue::from).collect()),
+            Value::Object(elems) => RtValue::Object(
+                elems
+                    .into_iter()
+                    .map(|(k, v)| (k, RtValue::from(v)))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            Value::Number(v) => RtValue::Number(v),
+            Value::Pointer(v) => RtValue::Pointer(v),
+            Value::Call(v) => RtValue::Call(v),
+            Value::Expr(v) => RtValue::Expr(v),
+        }
+    }
+}
@@ -52,6 +52,7 @@ impl RtTreeBuilder {
             RuntimeTree {
                 root,
                 nodes: self.nodes,
+                expansion: None,
             },
             self.actions,
         ))
@@ -0,0 +1,324 @@
+//! The structural difference between two runtime trees.
+//!
+//! The nodes are matched from the root down:
+//! the children of the matched nodes are matched by the type, the name and the arguments first
+//! and then by the type and the name only, that makes the node re-parameterised.
+//! The nodes that are left are matched by the type, the name and the arguments
+//! across the tree, that makes the node moved.
+//! The rest of the nodes are either added or removed along with their subtrees.
+//!
+//! The nodes that are not instantiated yet in the lazily built trees are not compared.
+use crate::runtime::args::display::ShortDisplayedRtArguments;
+use crate::runtime::args::RtArgs;
+use crate::runtime::rtree::analyzer::RtTreeAnalyzer;
+use crate::runtime::rtree::rnode::{RNode, RNodeId, RNodeName};
+use crate::runtime::rtree::RuntimeTree;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+
+/// The change of a node.
+/// The paths consist of the names of the nodes (or the types for the lambdas and decorators) from the root.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// The node of the new tree along with its subtree
+    Added { id: RNodeId, path: String },
+    /// The node of the old tree along with its subtree
+    Removed { id: RNodeId, path: String },
+    /// The node with the same arguments that has another parent in the new tree
+    Moved {
+        old: RNodeId,
+        new: RNodeId,
+        from: String,
+        to: String,
+    },
+    /// The node on the same place that has other arguments in the new tree
+    Reparameterised {
+        old: RNodeId,
+        new: RNodeId,
+        path: String,
+        from: RtArgs,
+        to: RtArgs,
+    },
+}
+
+/// The difference between the old and the new trees
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TreeDiff {
+    pub changes: Vec<Change>,
+    /// the nodes of the old tree matched with the nodes of the new tree
+    pub matched: HashMap<RNodeId, RNodeId>,
+}
+
+impl TreeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+    /// the nodes of the new tree that do not have a match in the old tree
+    pub fn added(&self, new: &RuntimeTree) -> HashSet<RNodeId> {
+        let matched: HashSet<&RNodeId> = self.matched.values().collect();
+        new.iter()
+            .map(|(id, _)| id)
+            .filter(|id| !matched.contains(id))
+            .collect()
+    }
+    /// the nodes of the old tree that do not have a match in the new tree
+    pub fn removed(&self, old: &RuntimeTree) -> HashSet<RNodeId> {
+        old.iter()
+            .map(|(id, _)| id)
+            .filter(|id| !self.matched.contains_key(id))
+            .collect()
+    }
+}
+
+impl Display for TreeDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut lines = self.changes.iter().map(|c| match c {
+            Change::Added { path, .. } => format!("+ {path}"),
+            Change::Removed { path, .. } => format!("- {path}"),
+            Change::Moved { from, to, .. } => format!("> {from} -> {to}"),
+            Change::Reparameterised { path, from, to, .. } => format!(
+                "~ {path}: {} -> {}",
+                ShortDisplayedRtArguments(from),
+                ShortDisplayedRtArguments(to)
+            ),
+        });
+        f.write_str(lines.join("\n").as_str())
+    }
+}
+
+/// finds the difference between the old and the new trees
+pub fn diff(old: &RuntimeTree, new: &RuntimeTree) -> TreeDiff {
+    let mut matcher = Matcher::new(old, new);
+    matcher.pair(old.root, new.root);
+    matcher.drain();
+    matcher.index_unmatched();
+    while matcher.find_move() {
+        matcher.drain();
+    }
+    matcher.finish()
+}
+
+impl RuntimeTree {
+    /// finds the difference between this tree and the new one, see [`diff`]
+    pub fn diff(&self, new: &RuntimeTree) -> TreeDiff {
+        diff(self, new)
+    }
+}
+
+struct Matcher<'a> {
+    old: &'a RuntimeTree,
+    new: &'a RuntimeTree,
+    old_an: RtTreeAnalyzer<'a>,
+    new_an: RtTreeAnalyzer<'a>,
+    matched: HashMap<RNodeId, RNodeId>,
+    matched_new: HashSet<RNodeId>,
+    queue: VecDeque<(RNodeId, RNodeId)>,
+    changes: Vec<Change>,
+    /// the unmatched nodes of the old tree by the type, the name and the names of the arguments
+    unmatched: HashMap<MoveKey, Vec<RNodeId>>,
+}
+
+/// the type, the name and the names of the arguments of the node.
+/// The values are compared separately since they are not hashable.
+type MoveKey = ((String, String), Vec<String>);
+
+impl<'a> Matcher<'a> {
+    fn new(old: &'a RuntimeTree, new: &'a RuntimeTree) -> Self {
+        Self {
+            old,
+            new,
+            old_an: old.analyze(),
+            new_an: new.analyze(),
+            matched: HashMap::new(),
+            matched_new: HashSet::new(),
+            queue: VecDeque::new(),
+            changes: vec![],
+            unmatched: HashMap::new(),
+        }
+    }
+
+    fn pair(&mut self, old: RNodeId, new: RNodeId) {
+        self.matched.insert(old, new);
+        self.matched_new.insert(new);
+        self.queue.push_back((old, new));
+        if let (Some(o), Some(n)) = (self.old.nodes.get(&old), self.new.nodes.get(&new)) {
+            if o.args() != n.args() {
+                self.changes.push(Change::Reparameterised {
+                    old,
+                    new,
+                    path: path(&self.new_an, self.new, new, false),
+                    from: o.args(),
+                    to: n.args(),
+                });
+            }
+        }
+    }
+
+    /// matches the children of the matched nodes
+    fn drain(&mut self) {
+        while let Some((old, new)) = self.queue.pop_front() {
+            let old_children = children(self.old, old);
+            let new_children = children(self.new, new);
+            for same_args in [true, false] {
+                for n in new_children.iter() {
+                    if self.matched_new.contains(n) {
+                        continue;
+                    }
+                    let found = old_children
+                        .iter()
+                        .find(|o| !self.matched.contains_key(o) && self.same(**o, *n, same_args));
+                    if let Some(o) = found {
+                        self.pair(*o, *n);
+                    }
+                }
+            }
+        }
+    }
+
+    /// indexes the nodes of the old tree that are left after the matching from the root
+    fn index_unmatched(&mut self) {
+        for (id, node) in self.old.iter() {
+            if !self.matched.contains_key(&id) {
+                self.unmatched.entry(move_key(node)).or_default().push(id);
+            }
+        }
+    }
+
+    /// finds the node that is moved to another parent.
+    /// Only the nodes whose parent is matched either in the old or in the new tree are considered
+    /// to not match the nodes deep in the added and removed subtrees.
+    fn find_move(&mut self) -> bool {
+        let new_candidates = self
+            .new
+            .iter()
+            .filter(|(id, _)| !self.matched_new.contains(id))
+            .collect_vec();
+        for (n, n_node) in new_candidates {
+            let n_parent_matched = self
+                .new_an
+                .parent(&n)
+                .is_some_and(|p| self.matched_new.contains(p));
+            let Some(olds) = self.unmatched.get_mut(&move_key(n_node)) else {
+                continue;
+            };
+            // the nodes matched by the children of the moved nodes are dropped on the way
+            olds.retain(|o| !self.matched.contains_key(o));
+            let found = olds.iter().position(|o| {
+                let o_parent_matched = self
+                    .old_an
+                    .parent(o)
+                    .is_some_and(|p| self.matched.contains_key(p));
+                (n_parent_matched || o_parent_matched)
+                    && self
+                        .old
+                        .nodes
+                        .get(o)
+                        .is_some_and(|o| o.args() == n_node.args())
+            });
+            if let Some(o) = found.map(|i| olds.remove(i)) {
+                self.changes.push(Change::Moved {
+                    old: o,
+                    new: n,
+                    from: path(&self.old_an, self.old, o, true),
+                    to: path(&self.new_an, self.new, n, true),
+                });
+                self.pair(o, n);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn same(&self, old: RNodeId, new: RNodeId, with_args: bool) -> bool {
+        match (self.old.nodes.get(&old), self.new.nodes.get(&new)) {
+            (Some(o), Some(n)) => key(o) == key(n) && (!with_args || o.args() == n.args()),
+            _ => false,
+        }
+    }
+
+    /// the roots of the added and removed subtrees
+    fn finish(mut self) -> TreeDiff {
+        for (id, _) in self.new.iter() {
+            let parent_matched = self
+                .new_an
+                .parent(&id)
+                .is_none_or(|p| self.matched_new.contains(p));
+            if !self.matched_new.contains(&id) && parent_matched {
+                self.changes.push(Change::Added {
+                    id,
+                    path: path(&self.new_an, self.new, id, true),
+                });
+            }
+        }
+        for (id, _) in self.old.iter() {
+            let parent_matched = self
+                .old_an
+                .parent(&id)
+                .is_none_or(|p| self.matched.contains_key(p));
+            if !self.matched.contains_key(&id) && parent_matched {
+                self.changes.push(Change::Removed {
+                    id,
+                    path: path(&self.old_an, self.old, id, true),
+                });
+            }
+        }
+        TreeDiff {
+            changes: self.changes,
+            matched: self.matched,
+        }
+    }
+}
+
+fn children(tree: &RuntimeTree, id: RNodeId) -> Vec<RNodeId> {
+    tree.nodes
+        .get(&id)
+        .map(|n| n.children())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| tree.nodes.contains_key(c))
+        .collect()
+}
+
+/// the type and the name of the node
+fn key(node: &RNode) -> (String, String) {
+    match node {
+        RNode::Leaf(name, _) => ("action".to_string(), label(name)),
+        RNode::Flow(tpe, name, _, _) => (tpe.to_string(), label(name)),
+        RNode::Decorator(tpe, _, _) => (tpe.to_string(), String::new()),
+    }
+}
+
+fn move_key(node: &RNode) -> MoveKey {
+    let names = node.args().0.into_iter().map(|a| a.name).collect();
+    (key(node), names)
+}
+
+fn label(name: &RNodeName) -> String {
+    match name {
+        RNodeName::Lambda => String::new(),
+        RNodeName::Name(name, _) => name.clone(),
+        RNodeName::Alias(_, alias, _) => alias.clone(),
+    }
+}
+
+/// the path from the root to the node optionally along with the arguments of the node
+fn path(an: &RtTreeAnalyzer, tree: &RuntimeTree, id: RNodeId, with_args: bool) -> String {
+    let mut segments = vec![];
+    let mut current = Some(id);
+    while let Some(id) = current {
+        if let Some(node) = tree.nodes.get(&id) {
+            let (tpe, name) = key(node);
+            segments.push(if name.is_empty() { tpe } else { name });
+        }
+        current = an.parent(&id).cloned();
+    }
+    segments.reverse();
+    let args = tree
+        .nodes
+        .get(&id)
+        .filter(|_| with_args)
+        .map(|n| ShortDisplayedRtArguments(&n.args()).to_string())
+        .unwrap_or_default();
+    format!("{}{args}", segments.join("/"))
+}
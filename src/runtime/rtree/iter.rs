@@ -2,6 +2,7 @@ use crate::runtime::rtree::rnode::{RNode, RNodeId};
 use crate::runtime::rtree::RuntimeTree;
 use std::collections::VecDeque;
 /// simple bfs iterator over the tree
+/// that goes over the instantiated nodes only.
 pub struct RtTreeBfsIter<'a> {
     pub queue: VecDeque<RNodeId>,
     pub tree: &'a RuntimeTree,
@@ -11,20 +12,39 @@ impl<'a> Iterator for RtTreeBfsIter<'a> {
     type Item = (RNodeId, &'a RNode);
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.queue.pop_front() {
-            None => None,
-            Some(id) => match self.tree.nodes.get(&id) {
-                None => None,
-                Some(node) => {
-                    match &node {
-                        RNode::Leaf(_, _) => {}
-                        RNode::Flow(_, _, _, children) => self.queue.extend(children.iter()),
-                        RNode::Decorator(_, _, child) => self.queue.push_back(*child),
-                    }
-                    Some((id, node))
+        while let Some(id) = self.queue.pop_front() {
+            // the nodes that are not instantiated yet are skipped along with their children
+            if let Some(node) = self.tree.nodes.get(&id) {
+                match &node {
+                    RNode::Leaf(_, _) => {}
+                    RNode::Flow(_, _, _, children) => self.queue.extend(children.iter()),
+                    RNode::Decorator(_, _, child) => self.queue.push_back(*child),
                 }
-            },
+                return Some((id, node));
+            }
         }
+        None
+    }
+}
+
+/// dfs iterator over the tree in the pre-order
+/// that goes over the instantiated nodes only.
+pub struct RtTreeDfsIter<'a> {
+    pub stack: Vec<RNodeId>,
+    pub tree: &'a RuntimeTree,
+}
+
+impl<'a> Iterator for RtTreeDfsIter<'a> {
+    type Item = (RNodeId, &'a RNode);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(id) = self.stack.pop() {
+            if let Some(node) = self.tree.nodes.get(&id) {
+                self.stack.extend(node.children().iter().rev());
+                return Some((id, node));
+            }
+        }
+        None
     }
 }
 
@@ -61,5 +81,7 @@ mod tests {
                 (3, "action1".to_string()),
             ]
         );
+        let ids: Vec<_> = tree.dfs().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
     }
 }
@@ -0,0 +1,427 @@
+//! Selectors to query the nodes of the runtime tree.
+//!
+//! A selector is a chain of steps joined by combinators in the manner of css:
+//! - `a b` - `b` is a descendant of `a`
+//! - `a > b` - `b` is a child of `a`
+//! - `a + b` - `b` is the next sibling of `a`
+//! - `a ~ b` - `b` is one of the next siblings of `a`
+//!
+//! A step consists of the kind of the node and the filters in brackets:
+//! - the kind is `action`, `flow`, `decorator`, the type of a flow or a decorator (`sequence`, `retry` etc.)
+//!   or `*` for any node. The kind can be omitted when there is at least one filter.
+//! - the filters are `[name=..]`, `[alias=..]`, `[file=..]`, `[args.<arg>=..]`
+//!   and `[args.<arg>]` to check the argument is present.
+//!   The values can be quoted.
+//!
+//! # Example
+//! ```
+//! use forester_rs::runtime::rtree::query::{Selector, Step};
+//! use forester_rs::runtime::rtree::rnode::FlowType;
+//!
+//! let selector: Selector = "sequence > action[name=move_to][args.target=door]".parse().unwrap();
+//! let same = Selector::new(Step::flow(FlowType::Sequence))
+//!     .child(Step::action().name("move_to").arg("target", "door"));
+//! assert_eq!(selector, same);
+//! ```
+use crate::runtime::rtree::analyzer::RtTreeAnalyzer;
+use crate::runtime::rtree::rnode::{DecoratorType, FlowType, RNode, RNodeId, RNodeName};
+use crate::runtime::{RtResult, RuntimeError};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+/// The kind of the node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Any,
+    Action,
+    Flow,
+    Decorator,
+    FlowOf(FlowType),
+    DecoratorOf(DecoratorType),
+}
+
+/// The filter of the node
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// the name of the node, for the aliases it is the original name
+    Name(String),
+    Alias(String),
+    /// the file the node is defined in
+    File(String),
+    /// the argument with the given name and optionally the value
+    Arg(String, Option<String>),
+}
+
+/// How the step is related to the previous one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combinator {
+    Descendant,
+    Child,
+    NextSibling,
+    Sibling,
+}
+
+/// The single step of the selector matching a node
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub kind: Kind,
+    pub filters: Vec<Filter>,
+}
+
+impl Step {
+    pub fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            filters: vec![],
+        }
+    }
+    pub fn any() -> Self {
+        Step::new(Kind::Any)
+    }
+    pub fn action() -> Self {
+        Step::new(Kind::Action)
+    }
+    pub fn flow(tpe: FlowType) -> Self {
+        Step::new(Kind::FlowOf(tpe))
+    }
+    pub fn decorator(tpe: DecoratorType) -> Self {
+        Step::new(Kind::DecoratorOf(tpe))
+    }
+    pub fn name(self, name: &str) -> Self {
+        self.filter(Filter::Name(name.to_string()))
+    }
+    pub fn alias(self, alias: &str) -> Self {
+        self.filter(Filter::Alias(alias.to_string()))
+    }
+    pub fn file(self, file: &str) -> Self {
+        self.filter(Filter::File(file.to_string()))
+    }
+    pub fn arg(self, name: &str, value: &str) -> Self {
+        self.filter(Filter::Arg(name.to_string(), Some(value.to_string())))
+    }
+    pub fn has_arg(self, name: &str) -> Self {
+        self.filter(Filter::Arg(name.to_string(), None))
+    }
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// checks the node against the step
+    pub fn matches(&self, node: &RNode) -> bool {
+        let kind = match (self.kind, node) {
+            (Kind::Any, _) => true,
+            (Kind::Action, RNode::Leaf(..)) => true,
+            (Kind::Flow, RNode::Flow(..)) => true,
+            (Kind::Decorator, RNode::Decorator(..)) => true,
+            (Kind::FlowOf(tpe), _) => node.is_flow(&tpe),
+            (Kind::DecoratorOf(tpe), _) => node.is_decorator(&tpe),
+            _ => false,
+        };
+        kind && self.filters.iter().all(|f| match (f, node.name()) {
+            (Filter::Name(name), _) => node.is_name(name),
+            (Filter::Alias(alias), Some(RNodeName::Alias(_, a, _))) => a == alias,
+            (Filter::File(file), Some(RNodeName::Name(_, p) | RNodeName::Alias(_, _, p))) => {
+                p == file
+            }
+            (Filter::Arg(name, value), _) => node
+                .args()
+                .find(name.clone())
+                .is_some_and(|v| value.as_ref().is_none_or(|e| &v.to_string() == e)),
+            _ => false,
+        })
+    }
+}
+
+/// The chain of steps that selects the nodes matching the last step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    pub first: Step,
+    pub rest: Vec<(Combinator, Step)>,
+}
+
+impl Selector {
+    pub fn new(first: Step) -> Self {
+        Self {
+            first,
+            rest: vec![],
+        }
+    }
+    pub fn then(mut self, combinator: Combinator, step: Step) -> Self {
+        self.rest.push((combinator, step));
+        self
+    }
+    pub fn descendant(self, step: Step) -> Self {
+        self.then(Combinator::Descendant, step)
+    }
+    pub fn child(self, step: Step) -> Self {
+        self.then(Combinator::Child, step)
+    }
+    pub fn next_sibling(self, step: Step) -> Self {
+        self.then(Combinator::NextSibling, step)
+    }
+    pub fn sibling(self, step: Step) -> Self {
+        self.then(Combinator::Sibling, step)
+    }
+
+    pub fn parse(text: &str) -> RtResult<Self> {
+        Parser {
+            chars: text.chars().peekable(),
+        }
+        .selector()
+    }
+
+    /// checks the node against the selector
+    pub fn matches(&self, an: &RtTreeAnalyzer, id: RNodeId) -> bool {
+        self.matches_at(an, id, self.rest.len())
+    }
+
+    fn step(&self, idx: usize) -> &Step {
+        if idx == 0 {
+            &self.first
+        } else {
+            &self.rest[idx - 1].1
+        }
+    }
+
+    fn matches_at(&self, an: &RtTreeAnalyzer, id: RNodeId, idx: usize) -> bool {
+        let node_matches = an
+            .tree()
+            .nodes
+            .get(&id)
+            .is_some_and(|n| self.step(idx).matches(n));
+        if !node_matches {
+            return false;
+        }
+        if idx == 0 {
+            return true;
+        }
+        let prev = idx - 1;
+        match self.rest[prev].0 {
+            Combinator::Child => an
+                .parent(&id)
+                .is_some_and(|p| self.matches_at(an, *p, prev)),
+            Combinator::Descendant => an
+                .ancestors(id)
+                .into_iter()
+                .any(|a| self.matches_at(an, a, prev)),
+            Combinator::NextSibling => an
+                .prev_sibling(id)
+                .is_some_and(|s| self.matches_at(an, s, prev)),
+            Combinator::Sibling => an
+                .prev_siblings(id)
+                .into_iter()
+                .any(|s| self.matches_at(an, s, prev)),
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = RuntimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Selector::parse(s)
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Any => f.write_str("*"),
+            Kind::Action => f.write_str("action"),
+            Kind::Flow => f.write_str("flow"),
+            Kind::Decorator => f.write_str("decorator"),
+            Kind::FlowOf(tpe) => write!(f, "{tpe}"),
+            Kind::DecoratorOf(tpe) => write!(f, "{tpe}"),
+        }
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let filters = self.filters.iter().map(|filter| match filter {
+            Filter::Name(v) => format!("[name=\"{v}\"]"),
+            Filter::Alias(v) => format!("[alias=\"{v}\"]"),
+            Filter::File(v) => format!("[file=\"{v}\"]"),
+            Filter::Arg(name, None) => format!("[args.{name}]"),
+            Filter::Arg(name, Some(v)) => format!("[args.{name}=\"{v}\"]"),
+        });
+        write!(f, "{}{}", self.kind, filters.collect::<String>())
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rest = self.rest.iter().map(|(c, s)| match c {
+            Combinator::Descendant => format!(" {s}"),
+            Combinator::Child => format!(" > {s}"),
+            Combinator::NextSibling => format!(" + {s}"),
+            Combinator::Sibling => format!(" ~ {s}"),
+        });
+        write!(f, "{}{}", self.first, rest.collect::<String>())
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+fn err<T>(msg: String) -> RtResult<T> {
+    Err(RuntimeError::WrongArgument(format!(
+        "the selector is invalid: {msg}"
+    )))
+}
+
+impl<'a> Parser<'a> {
+    fn selector(&mut self) -> RtResult<Selector> {
+        self.spaces();
+        let mut selector = Selector::new(self.step()?);
+        loop {
+            let spaces = self.spaces();
+            let combinator = match self.chars.peek() {
+                None => return Ok(selector),
+                Some('>') => Combinator::Child,
+                Some('+') => Combinator::NextSibling,
+                Some('~') => Combinator::Sibling,
+                Some(_) if spaces => {
+                    selector = selector.descendant(self.step()?);
+                    continue;
+                }
+                Some(c) => return err(format!("unexpected symbol '{c}'")),
+            };
+            self.chars.next();
+            self.spaces();
+            selector = selector.then(combinator, self.step()?);
+        }
+    }
+
+    fn step(&mut self) -> RtResult<Step> {
+        let kind = self.ident();
+        let mut step = match kind.as_str() {
+            "" | "*" => Step::any(),
+            "action" => Step::action(),
+            "flow" => Step::new(Kind::Flow),
+            "decorator" => Step::new(Kind::Decorator),
+            k => match (FlowType::from_str(k), DecoratorType::from_str(k)) {
+                (Ok(tpe), _) => Step::flow(tpe),
+                (_, Ok(tpe)) => Step::decorator(tpe),
+                _ => return err(format!("unknown kind of node '{k}'")),
+            },
+        };
+        while self.chars.next_if_eq(&'[').is_some() {
+            self.spaces();
+            let key = self.ident();
+            self.spaces();
+            let value = if self.chars.next_if_eq(&'=').is_some() {
+                self.spaces();
+                Some(self.value()?)
+            } else {
+                None
+            };
+            self.spaces();
+            if self.chars.next() != Some(']') {
+                return err(format!("the filter '{key}' is not closed"));
+            }
+            let filter = match (key.as_str(), value) {
+                ("name", Some(v)) => Filter::Name(v),
+                ("alias", Some(v)) => Filter::Alias(v),
+                ("file", Some(v)) => Filter::File(v),
+                (k, v) if k.starts_with("args.") => Filter::Arg(k[5..].to_string(), v),
+                (k, _) => return err(format!("unknown filter '{k}'")),
+            };
+            step = step.filter(filter);
+        }
+        if kind.is_empty() && step.filters.is_empty() {
+            return match self.chars.peek() {
+                None => err("the step is empty".to_string()),
+                Some(c) => err(format!("unexpected symbol '{c}'")),
+            };
+        }
+        Ok(step)
+    }
+
+    fn ident(&mut self) -> String {
+        let mut ident = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '*'))
+        {
+            ident.push(c);
+        }
+        ident
+    }
+
+    fn value(&mut self) -> RtResult<String> {
+        match self.chars.next_if(|c| *c == '"' || *c == '\'') {
+            Some(quote) => {
+                let value = self.chars.by_ref().take_while(|c| *c != quote).join("");
+                Ok(value)
+            }
+            None => {
+                let mut value = String::new();
+                while let Some(c) = self.chars.next_if(|c| *c != ']') {
+                    value.push(c);
+                }
+                Ok(value.trim().to_string())
+            }
+        }
+    }
+
+    /// skips the spaces returning true if there were any
+    fn spaces(&mut self) -> bool {
+        let mut skipped = false;
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {
+            skipped = true;
+        }
+        skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::rtree::query::{Combinator, Filter, Kind, Selector, Step};
+    use crate::runtime::rtree::rnode::{DecoratorType, FlowType};
+
+    #[test]
+    fn parse() {
+        let selector = Selector::parse(
+            "root sequence>retry + action[name=move_to][ args.target = 'the door' ] ~ [alias=go]",
+        )
+        .unwrap();
+        assert_eq!(
+            selector,
+            Selector::new(Step::flow(FlowType::Root))
+                .descendant(Step::flow(FlowType::Sequence))
+                .child(Step::decorator(DecoratorType::Retry))
+                .next_sibling(Step::action().name("move_to").arg("target", "the door"))
+                .sibling(Step::any().alias("go"))
+        );
+        assert_eq!(Selector::parse(&selector.to_string()).unwrap(), selector);
+
+        let selector = Selector::parse("flow[args.a]").unwrap();
+        assert_eq!(selector.first.kind, Kind::Flow);
+        assert_eq!(
+            selector.first.filters,
+            vec![Filter::Arg("a".to_string(), None)]
+        );
+        assert!(selector.rest.is_empty());
+
+        let selector = Selector::parse("* [file=std::actions]").unwrap();
+        assert_eq!(
+            selector.rest,
+            vec![(Combinator::Descendant, Step::any().file("std::actions"))]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Selector::parse("").is_err());
+        assert!(Selector::parse("sequence >").is_err());
+        assert!(Selector::parse("seq").is_err());
+        assert!(Selector::parse("action[name=a").is_err());
+        assert!(Selector::parse("action[size=1]").is_err());
+        assert!(Selector::parse("action[name]").is_err());
+    }
+}
@@ -3,6 +3,7 @@ use crate::runtime::{RtResult, RuntimeError};
 use crate::tree::parser::ast::TreeType;
 
 use crate::tree::{cerr, TreeError};
+use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 use strum_macros::EnumString;
 
@@ -11,7 +12,7 @@ pub type Name = String;
 pub type Alias = String;
 pub type Path = String;
 
-#[derive(Display, Debug, Clone, Copy, Eq, PartialEq, EnumString)]
+#[derive(Display, Debug, Clone, Copy, Eq, PartialEq, EnumString, Deserialize, Serialize)]
 #[strum(serialize_all = "snake_case")]
 pub enum DecoratorType {
     Inverter,
@@ -23,7 +24,7 @@ pub enum DecoratorType {
     Delay,
 }
 
-#[derive(Display, Debug, Clone, Copy, Eq, PartialEq, EnumString)]
+#[derive(Display, Debug, Clone, Copy, Eq, PartialEq, EnumString, Deserialize, Serialize)]
 #[strum(serialize_all = "snake_case")]
 pub enum FlowType {
     Root,
@@ -80,7 +81,7 @@ impl TryFrom<TreeType> for FlowType {
 
 /// A node name can be a lambda, a name or an alias.
 /// An alias is a name that is used to refer to a node in the import
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
 pub enum RNodeName {
     Lambda,
     Name(Name, Path),
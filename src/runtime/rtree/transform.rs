@@ -2,6 +2,7 @@ use crate::runtime::args::transform::find_arg_value;
 use crate::tree::parser::ast::arg::{Arguments, Params};
 use crate::tree::parser::ast::call::{Call, Calls};
 use crate::tree::parser::ast::Key;
+use crate::tree::project::FileName;
 use crate::tree::{cerr, TreeError};
 use std::collections::{HashMap, VecDeque};
 
@@ -25,11 +26,15 @@ pub(crate) struct StackItem {
     pub call: Call,
     pub parent_id: usize,
     pub file_name: String,
+    // the file where the call is written down. It is used only to point out the errors
+    pub origin: String,
 }
 
-/// represents a parent of the call in the stack trace
+/// represents a parent of the call in the stack trace.
+/// The tree keeps the file where its arguments are written down
+/// to resolve the calls passed through them.
 pub(crate) enum ChainItem {
-    Tree(usize, Arguments, Params),
+    Tree(usize, Arguments, Params, FileName),
     Lambda(usize),
     Root,
 }
@@ -37,20 +42,26 @@ pub(crate) enum ChainItem {
 impl ChainItem {
     pub fn get_tree(&self) -> (usize, Arguments, Params) {
         match self {
-            ChainItem::Tree(id, a, p) => (*id, a.clone(), p.clone()),
+            ChainItem::Tree(id, a, p, _) => (*id, a.clone(), p.clone()),
             _ => (0, Arguments::default(), Params::default()),
         }
     }
+    pub fn file(&self) -> Option<&FileName> {
+        match self {
+            ChainItem::Tree(_, _, _, f) => Some(f),
+            _ => None,
+        }
+    }
     pub fn is_lambda(&self) -> bool {
         match self {
             ChainItem::Lambda(_) => true,
-            ChainItem::Tree(_, _, _) => false,
+            ChainItem::Tree(..) => false,
             ChainItem::Root => false,
         }
     }
     pub fn parent(&self) -> usize {
         match self {
-            ChainItem::Tree(p, _, _) => *p,
+            ChainItem::Tree(p, ..) => *p,
             ChainItem::Lambda(p) => *p,
             ChainItem::Root => 0,
         }
@@ -63,6 +74,16 @@ impl Transformer {
         self.gen
     }
 
+    /// the last id given to the nodes
+    pub fn last(&self) -> usize {
+        self.gen
+    }
+
+    /// makes sure the ids up to the given one are not given to the nodes anymore
+    pub fn reserve(&mut self, id: usize) {
+        self.gen = self.gen.max(id);
+    }
+
     pub fn get_chain(&self, id: &usize) -> Result<&ChainItem, TreeError> {
         if *id == 0 {
             Ok(&ChainItem::Root)
@@ -79,18 +100,27 @@ impl Transformer {
     ///  `sequence another(n:tree) n(..)`
     ///  
     /// To process `n(..)` in `another` we have to climb up after  `n=op(..)` until the last ho call.
-    pub fn find_ho_call(&self, parent_id: &usize, key: &Key) -> Result<Call, TreeError> {
-        let (mut grand_parent, mut parent_args, mut parent_params) =
-            self.get_chain_skip_lambda(parent_id)?.get_tree();
+    /// Returns the call along with the file it is written down in.
+    pub fn find_ho_call(
+        &self,
+        parent_id: &usize,
+        key: &Key,
+    ) -> Result<(Call, FileName), TreeError> {
+        let mut item = self.get_chain_skip_lambda(parent_id)?;
+        let (mut grand_parent, mut parent_args, mut parent_params) = item.get_tree();
 
         let mut call = find_arg_value(key, &parent_params, &parent_args)?.get_call();
 
         while let Some(key) = call.clone().and_then(|c| c.get_ho_invocation()) {
-            (grand_parent, parent_args, parent_params) =
-                self.get_chain_skip_lambda(&grand_parent)?.get_tree();
+            item = self.get_chain_skip_lambda(&grand_parent)?;
+            (grand_parent, parent_args, parent_params) = item.get_tree();
             call = find_arg_value(&key, &parent_params, &parent_args)?.get_call();
         }
-        call.ok_or(cerr(format!("the argument {key} should be a tree")))
+        let call = call.ok_or(cerr(format!("the argument {key} should be a tree")))?;
+        let file = item.file().ok_or(cerr(format!(
+            "the argument {key} is not passed to any tree"
+        )))?;
+        Ok((call, file.clone()))
     }
 
     ///goes up on the stacktrace skipping lambda
@@ -104,9 +134,46 @@ impl Transformer {
         Ok(current)
     }
 
-    pub fn add_chain(&mut self, id: usize, parent: usize, args: Arguments, params: Params) {
+    ///goes up on the stacktrace skipping lambda and returns the id of the tree
+    pub fn get_tree_id_skip_lambda(&self, id: &usize) -> Result<usize, TreeError> {
+        let mut id = *id;
+        let mut current = self.get_chain(&id)?;
+
+        while current.is_lambda() {
+            id = current.parent();
+            current = self.get_chain(&id)?;
+        }
+
+        Ok(id)
+    }
+
+    /// goes up on the stacktrace from the given item to the root
+    /// and counts the items satisfying the predicate
+    pub fn count_up<F>(&self, id: usize, pred: F) -> Result<usize, TreeError>
+    where
+        F: Fn(usize) -> bool,
+    {
+        let mut count = 0;
+        let mut current = id;
+        while current != 0 {
+            if pred(current) {
+                count += 1;
+            }
+            current = self.get_chain(&current)?.parent();
+        }
+        Ok(count)
+    }
+
+    pub fn add_chain(
+        &mut self,
+        id: usize,
+        parent: usize,
+        args: Arguments,
+        params: Params,
+        file: FileName,
+    ) {
         self.chain_map
-            .insert(id, ChainItem::Tree(parent, args, params));
+            .insert(id, ChainItem::Tree(parent, args, params, file));
     }
     pub fn add_chain_root(&mut self, id: usize) {
         self.chain_map.insert(id, ChainItem::Root);
@@ -115,20 +182,39 @@ impl Transformer {
         self.chain_map.insert(id, ChainItem::Lambda(parent));
     }
 
-    pub fn push(&mut self, call: Call, parent_id: usize, file_name: String) -> usize {
+    /// pushes the call that is written down in the file `origin`
+    /// but should be resolved in the context of the file `file_name`
+    pub fn push_from(
+        &mut self,
+        call: Call,
+        parent_id: usize,
+        file_name: String,
+        origin: String,
+    ) -> usize {
         let id = self.next();
         self.stack.push_back(StackItem {
             id,
             call,
             parent_id,
             file_name,
+            origin,
         });
         id
     }
     pub fn push_vec(&mut self, calls: Calls, parent_id: usize, file_name: String) -> Vec<usize> {
+        let origin = file_name.clone();
+        self.push_vec_from(calls, parent_id, file_name, origin)
+    }
+    pub fn push_vec_from(
+        &mut self,
+        calls: Calls,
+        parent_id: usize,
+        file_name: String,
+        origin: String,
+    ) -> Vec<usize> {
         let mut children = vec![];
         for call in calls.elems {
-            children.push(self.push(call, parent_id, file_name.clone()));
+            children.push(self.push_from(call, parent_id, file_name.clone(), origin.clone()));
         }
 
         children
@@ -144,6 +230,7 @@ impl Transformer {
             id,
             call,
             parent_id,
+            origin: file_name.clone(),
             file_name,
         });
         id
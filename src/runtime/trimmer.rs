@@ -48,19 +48,12 @@ pub type TrimmingQueueRef = Arc<Mutex<TrimmingQueue>>;
 ///         } else {
 ///             let tree = snapshot.tree;
 ///             let id = tree
-///                 .nodes
-///                 .iter()
-///                 .find(|(_, n)| {
-///                     n.name()
-///                         .and_then(|n| n.name().ok())
-///                         .filter(|n| n.as_str() == "fail_empty")
-///                         .is_some()
-///                 })
-///                 .map(|(id, _)| id)
+///                 .analyze()
+///                 .query_first("action[name=fail_empty]")?
 ///                 .unwrap();
 ///
 ///             let mut rtb = RtTreeBuilder::new_from(tree.max_id() + 1);
-///             rtb.set_as_root(action!(node_name!("success")), id.clone());
+///             rtb.set_as_root(action!(node_name!("success")), id);
 ///
 ///             Ok(TrimRequest::attempt(RequestBody::new(
 ///                 rtb,
@@ -1,10 +1,17 @@
 mod actions;
 mod actions_re;
 mod args;
+mod artifact;
 mod decorator;
+mod diff;
 mod flow;
+mod lazy;
+mod lint;
 mod parser;
+mod query;
+mod recursion;
 mod rtree;
+mod schedule;
 mod simulator;
 mod tracer;
 mod trimmer;
@@ -13,11 +20,23 @@ mod nav;
 mod import;
 mod params;
 mod daemons;
+mod printer;
+#[cfg(feature = "lsp")]
+mod lsp;
+mod typecheck;
 
 
+use crate::runtime::action::{Impl, ImplAsync, Tick};
+use crate::runtime::args::{RtArgs, RtValue};
 use crate::runtime::builder::ForesterBuilder;
+use crate::runtime::context::TreeContextRef;
+use crate::runtime::{RtOk, TickResult};
 use log::LevelFilter;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 pub fn turn_on_logs() {
     let _ = env_logger::builder()
@@ -44,3 +63,56 @@ pub fn fb(folder: &str) -> ForesterBuilder {
 
     fb
 }
+
+/// The action `work(key, millis)` sleeps for the given millis and puts the current tick under the key.
+/// It counts how many invocations run at the same time
+/// and does not write the key once the invocation with this key is halted.
+#[derive(Default, Clone)]
+pub struct Work {
+    pub active: Arc<AtomicUsize>,
+    pub max_active: Arc<AtomicUsize>,
+    pub halted: Arc<Mutex<Vec<String>>>,
+}
+
+impl Work {
+    fn work(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+        let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_active.fetch_max(active, Ordering::SeqCst);
+        let millis = args.find("millis".to_string()).and_then(RtValue::as_int);
+        thread::sleep(Duration::from_millis(millis.unwrap_or(0) as u64));
+        let key = args
+            .find("key".to_string())
+            .and_then(RtValue::as_string)
+            .unwrap_or_default();
+        let halted = self.halted.lock().unwrap();
+        let written = if halted.contains(&key) {
+            Ok(())
+        } else {
+            ctx.bb()
+                .lock()
+                .map_err(Into::into)
+                .and_then(|mut bb| bb.put(key, RtValue::int(ctx.current_tick() as i64)))
+        };
+        drop(halted);
+        self.active.fetch_sub(1, Ordering::SeqCst);
+        written.map(|_| TickResult::success())
+    }
+}
+
+impl Impl for Work {
+    fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+        self.work(args, ctx)
+    }
+}
+
+impl ImplAsync for Work {
+    fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+        self.work(args, ctx)
+    }
+
+    fn halt(&self, args: RtArgs, _ctx: TreeContextRef) -> RtOk {
+        let key = args.find("key".to_string()).and_then(RtValue::as_string);
+        self.halted.lock()?.push(key.unwrap_or_default());
+        Ok(())
+    }
+}
@@ -1,9 +1,16 @@
+use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
-use crate::runtime::action::Impl;
-use crate::runtime::args::RtValue;
-use crate::runtime::TickResult;
-use crate::tests::fb;
+use crate::runtime::action::{Action, ActionName, Impl, ImplAsync, Tick};
+use crate::runtime::args::{RtArgs, RtValue};
+use crate::runtime::builder::modules::StdModule;
+use crate::runtime::builder::ForesterBuilder;
+use crate::runtime::context::TreeContextRef;
+use crate::runtime::rtree::rnode::{DecoratorType, RNode};
+use crate::runtime::{RtResult, RuntimeError, TickResult};
+use crate::tests::{fb, Work};
 
 struct HaltTester {
     pub err_on_halt: bool,
@@ -133,3 +140,244 @@ fn sync_action_halt_err() {
     // Check that halt was called exactly once
     assert_eq!(*halt_called.lock().unwrap(), 1);
 }
+
+struct SlowAsync {
+    pub halt_called: Arc<Mutex<i32>>,
+}
+
+impl ImplAsync for SlowAsync {
+    fn tick(&self, _args: RtArgs, _ctx: TreeContextRef) -> Tick {
+        thread::sleep(Duration::from_millis(300));
+        Ok(TickResult::success())
+    }
+
+    fn halt(&self, _args: RtArgs, _ctx: TreeContextRef) -> crate::runtime::RtOk {
+        *self.halt_called.lock().unwrap() += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn async_action_halt() {
+    let mut fb = fb("actions/async_halt");
+
+    let halt_called = Arc::new(Mutex::new(0));
+    fb.register_async_action(
+        "slow",
+        SlowAsync {
+            halt_called: halt_called.clone(),
+        },
+    );
+
+    let mut f = fb.build().unwrap();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+
+    assert_eq!(*halt_called.lock().unwrap(), 1);
+    // the task is dropped and its result is not picked up later
+    assert!(f.env.lock().unwrap().tasks.is_empty());
+}
+
+fn async_parallel(tree: &str) -> (crate::runtime::forester::Forester, Work, Tick) {
+    let mut fb = fb("actions/async_parallel");
+    fb.main_tree(tree.to_string());
+    let work = Work::default();
+    fb.register_async_action("fetch", work.clone());
+    let mut f = fb.build().unwrap();
+    let result = f.run();
+    (f, work, result)
+}
+
+#[test]
+fn async_same_action_in_parallel() {
+    let (f, work, result) = async_parallel("main");
+    assert_eq!(result, Ok(TickResult::success()));
+    // every node has its own task, thus they run at once
+    assert_eq!(work.max_active.load(Ordering::SeqCst), 2);
+
+    let bb = f.bb.lock().unwrap();
+    assert!(bb.get("a".to_string()).unwrap().is_some());
+    assert!(bb.get("b".to_string()).unwrap().is_some());
+    drop(bb);
+    assert!(f.env.lock().unwrap().tasks.is_empty());
+}
+
+#[test]
+fn async_same_action_in_parallel_halted() {
+    let (f, work, result) = async_parallel("first");
+    assert_eq!(result, Ok(TickResult::success()));
+    // the task of the slow node is dropped when it is halted
+    assert!(f.env.lock().unwrap().tasks.is_empty());
+    assert_eq!(*work.halted.lock().unwrap(), vec!["b".to_string()]);
+
+    // the dropped task still runs to the end on the blocking pool
+    while work.active.load(Ordering::SeqCst) > 0 {
+        thread::sleep(Duration::from_millis(10));
+    }
+    let bb = f.bb.lock().unwrap();
+    assert!(bb.get("a".to_string()).unwrap().is_some());
+    assert_eq!(bb.get("b".to_string()), Ok(None));
+}
+
+#[test]
+fn check_expr() {
+    let fb = fb("actions/check");
+
+    let mut f = fb.build().unwrap();
+    let result = f.run();
+    assert_eq!(
+        result,
+        Ok(TickResult::failure(
+            r#"the condition '!(battery > 10) || mode == "manual"' is false (battery = 15, mode = auto)"#
+                .to_string()
+        ))
+    );
+}
+
+#[test]
+fn check_expr_types() {
+    let mut fb = ForesterBuilder::from_text();
+    fb.text(
+        r#"
+import "std::actions"
+root main check(mode < "a" && 1 == "1")
+        "#
+        .to_string(),
+    );
+    let err = fb.build().err().unwrap();
+    assert!(format!("{err:?}").contains("different types"), "{err:?}");
+
+    let mut fb = ForesterBuilder::from_text();
+    fb.text(
+        r#"
+import "std::actions"
+root main sequence {
+    store("mode", "auto")
+    check(mode < 1)
+}
+        "#
+        .to_string(),
+    );
+    let result = fb.build().unwrap().run().unwrap();
+    assert!(matches!(result, TickResult::Failure(r) if r.contains("can not be compared")));
+}
+
+#[test]
+fn arithmetic_args() {
+    let mut f = fb("actions/arithmetic").build().unwrap();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+
+    let retry = f.tree.nodes.values().find_map(|n| match n {
+        RNode::Decorator(DecoratorType::Retry, args, _) => Some(args.clone()),
+        _ => None,
+    });
+    assert_eq!(retry.unwrap().first(), Some(RtValue::int(6)));
+
+    let bb = f.bb.lock().unwrap();
+    let value = |k: &str| bb.get(k.to_string()).unwrap().cloned().unwrap();
+    assert_eq!(value("topic"), RtValue::str("/robot_7/cmd_10".to_string()));
+    assert_eq!(value("count"), RtValue::int(16));
+    assert_eq!(value("half"), RtValue::int(1));
+    assert_eq!(value("ratio"), RtValue::float(1.5));
+    assert_eq!(value("label"), RtValue::str("robot_7".to_string()));
+}
+
+#[test]
+fn arithmetic_errors() {
+    let mut fb = ForesterBuilder::from_text();
+    fb.text(
+        r#"
+import "std::actions"
+root main store("x", "a" * 2)
+        "#
+        .to_string(),
+    );
+    let err = fb.build().err().unwrap();
+    assert!(
+        format!("{err:?}").contains("is expected to be num"),
+        "{err:?}"
+    );
+
+    let mut fb = ForesterBuilder::from_text();
+    fb.text(
+        r#"
+import "std::actions"
+root main sequence {
+    store("zero", 0)
+    store("x", 1 / zero)
+}
+        "#
+        .to_string(),
+    );
+    let result = fb.build().unwrap().run();
+    assert!(
+        format!("{result:?}").contains("divides by zero"),
+        "{result:?}"
+    );
+}
+
+struct Arm;
+
+impl StdModule for Arm {
+    fn name(&self) -> String {
+        "mycompany::arm".to_string()
+    }
+    fn text(&self) -> String {
+        r#"
+impl grasp(key:string);
+
+sequence grasp_twice(key:string) {
+    grasp(key)
+    grasp(key)
+}
+"#
+        .to_string()
+    }
+    fn action(&self, action: &ActionName) -> RtResult<Action> {
+        match action.as_str() {
+            "grasp" => Ok(Action::sync(Grasp)),
+            _ => Err(RuntimeError::UnImplementedAction(format!(
+                "mycompany::arm::{action}"
+            ))),
+        }
+    }
+}
+
+struct Grasp;
+
+impl Impl for Grasp {
+    fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+        let key = args.find("key".to_string()).unwrap().as_string().unwrap();
+        let bb = ctx.bb();
+        let mut bb = bb.lock().unwrap();
+        let count = bb
+            .get(key.clone())?
+            .and_then(|v| v.clone().as_int())
+            .unwrap_or_default();
+        bb.put(key, RtValue::int(count + 1))?;
+        Ok(TickResult::success())
+    }
+}
+
+#[test]
+fn custom_module() {
+    let mut files_fb = fb("actions/module");
+    files_fb.register_module(Arm);
+    let mut f = files_fb.build().unwrap();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+    let bb = f.bb.lock().unwrap();
+    assert_eq!(bb.get("ball".to_string()).unwrap(), Some(&RtValue::int(2)));
+    assert_eq!(
+        bb.get("done".to_string()).unwrap(),
+        Some(&RtValue::str("yes".to_string()))
+    );
+
+    let mut text_fb = ForesterBuilder::from_text();
+    text_fb.text(r#"import "mycompany::arm" root main grasp("cup")"#.to_string());
+    text_fb.register_module(Arm);
+    assert_eq!(text_fb.build().unwrap().run(), Ok(TickResult::success()));
+
+    let mut twice = fb("actions/module");
+    twice.register_module(Arm);
+    twice.register_module(Arm);
+    assert!(twice.build().is_err());
+}
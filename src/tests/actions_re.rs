@@ -104,3 +104,108 @@ fn remote_in_tree() {
 
     assert_eq!(result, Ok(TickResult::success()));
 }
+
+#[test]
+fn remote_halt() {
+    turn_on_logs();
+    let mock_env = RtEnv::try_new().unwrap();
+    let mock_server = mock_env.runtime.block_on(async {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/action"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!("Running")))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/action/halt"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        mock_server
+    });
+
+    let mut builder = fb("actions/remote_halt");
+    let action = RemoteHttpAction::new(format!("{}/action", mock_server.uri()));
+    builder.register_remote_action("action", action);
+    builder.http_serv(9998);
+    let mut f = builder.build().unwrap();
+
+    assert_eq!(f.run(), Ok(TickResult::success()));
+
+    mock_env.runtime.block_on(mock_server.verify());
+}
+
+#[test]
+fn remote_halt_tolerated() {
+    turn_on_logs();
+    let mock_env = RtEnv::try_new().unwrap();
+    // the remote side does not handle the halting
+    let mock_server = mock_env.runtime.block_on(async {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/action"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!("Running")))
+            .mount(&mock_server)
+            .await;
+        mock_server
+    });
+
+    let mut builder = fb("actions/remote_halt");
+    let action = RemoteHttpAction::new(format!("{}/action", mock_server.uri()));
+    builder.register_remote_action("action", action);
+    builder.http_serv(9997);
+    let mut f = builder.build().unwrap();
+
+    assert_eq!(f.run(), Ok(TickResult::success()));
+
+    // the remote side is not reachable
+    let env = RtEnv::try_new().unwrap();
+    let action = RemoteHttpAction::new("http://localhost:1/action".to_string());
+    let result = action.halt(
+        RtArgs::default(),
+        TreeRemoteContextRef::new(1, 9997, Arc::new(Mutex::new(env))),
+    );
+
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn remote_timeout() {
+    turn_on_logs();
+    let env = RtEnv::try_new().unwrap();
+    // the remote side does not answer in time
+    let mock_server = env.runtime.block_on(async {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!("Success"))
+                    .set_delay(Duration::from_secs(10)),
+            )
+            .mount(&mock_server)
+            .await;
+        mock_server
+    });
+
+    let action = RemoteHttpAction::new(format!("{}/action", mock_server.uri()))
+        .with_timeout(Duration::from_millis(100));
+    let env = Arc::new(Mutex::new(env));
+
+    let start = std::time::Instant::now();
+    let result = action.tick(
+        RtArgs::default(),
+        TreeRemoteContextRef::new(1, 9996, env.clone()),
+    );
+    assert!(result.is_err());
+
+    let result = action.halt(
+        RtArgs::default(),
+        TreeRemoteContextRef::new(1, 9996, env.clone()),
+    );
+    assert_eq!(result, Ok(()));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
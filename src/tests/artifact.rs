@@ -0,0 +1,130 @@
+use crate::runtime::action::builtin::ReturnResult;
+use crate::runtime::builder::ForesterBuilder;
+use crate::runtime::rtree::artifact::{ArtifactFormat, TreeArtifact, ARTIFACT_VERSION};
+use crate::runtime::rtree::RuntimeTree;
+use crate::runtime::{RuntimeError, TickResult};
+use crate::tests::{fb, test_folder};
+use crate::tree::project::Project;
+use std::fs;
+
+fn compiled(folder: &str) -> TreeArtifact {
+    let project = Project::build("main.tree".to_string(), test_folder(folder)).unwrap();
+    TreeArtifact::compile(&RuntimeTree::build(project).unwrap()).unwrap()
+}
+
+#[test]
+fn roundtrip() {
+    for folder in ["artifact", "ho_tree", "units/pointers"] {
+        let project = Project::build("main.tree".to_string(), test_folder(folder)).unwrap();
+        let starter = RuntimeTree::build(project).unwrap();
+        let artifact = TreeArtifact::compile(&starter).unwrap();
+
+        let from_json = TreeArtifact::from_bytes(artifact.to_json().unwrap().as_bytes()).unwrap();
+        let from_bytes = TreeArtifact::from_bytes(&artifact.to_bytes().unwrap()).unwrap();
+        assert_eq!(from_json, artifact, "{folder}");
+        assert_eq!(from_bytes, artifact, "{folder}");
+
+        let restored = from_bytes.starter();
+        assert_eq!(restored.tree, starter.tree, "{folder}");
+        assert_eq!(restored.actions, starter.actions, "{folder}");
+        assert_eq!(restored.std_actions, starter.std_actions, "{folder}");
+    }
+}
+
+#[test]
+fn hash() {
+    let artifact = compiled("artifact");
+    let hash = artifact.hash().unwrap();
+    assert_eq!(hash.len(), 64);
+    assert_eq!(compiled("artifact").hash().unwrap(), hash);
+    let from_json = TreeArtifact::from_bytes(artifact.to_json().unwrap().as_bytes()).unwrap();
+    assert_eq!(from_json.hash().unwrap(), hash);
+    assert_ne!(compiled("ho_tree").hash().unwrap(), hash);
+}
+
+#[test]
+fn hash_ignores_formatting() {
+    let hash = |text: &str| {
+        let project = Project::build_from_text(text.to_string()).unwrap();
+        let artifact = TreeArtifact::compile(&RuntimeTree::build(project).unwrap()).unwrap();
+        artifact.hash().unwrap()
+    };
+    let text = r#"
+import "std::actions"
+sequence wrap(op:tree){ op(..) }
+root main wrap(success())
+"#;
+    let formatted = r#"
+// the tree passed to another one
+import "std::actions"
+
+sequence wrap(op: tree) {
+    op(..)
+}
+
+root main wrap(
+    success()
+)
+"#;
+    assert_eq!(hash(text), hash(formatted));
+}
+
+#[test]
+fn version() {
+    let artifact = compiled("artifact");
+    let json = artifact.to_json().unwrap().replacen(
+        format!("\"version\": {ARTIFACT_VERSION}").as_str(),
+        "\"version\": 0",
+        1,
+    );
+    assert!(TreeArtifact::from_bytes(json.as_bytes()).is_err());
+
+    let mut bytes = artifact.to_bytes().unwrap();
+    bytes[4] = 0;
+    assert!(TreeArtifact::from_bytes(&bytes).is_err());
+    assert!(TreeArtifact::from_bytes(b"FRST").is_err());
+}
+
+#[test]
+fn lazy() {
+    let project = Project::build("main.tree".to_string(), test_folder("lazy")).unwrap();
+    let mut starter = RuntimeTree::build_lazy(project, None).unwrap();
+    assert!(TreeArtifact::compile(&starter).is_err());
+    starter.tree.instantiate_all().unwrap();
+    assert!(TreeArtifact::compile(&starter).is_ok());
+}
+
+#[test]
+fn builder() {
+    let mut fb = fb("recursion/bounded");
+    fb.recursion_depth(3);
+    let artifact = fb.compile().unwrap();
+
+    for format in [ArtifactFormat::Json, ArtifactFormat::Binary] {
+        let path = test_folder("recursion/bounded/main.frst");
+        artifact.save(&path, format).unwrap();
+
+        let mut fb = ForesterBuilder::from_artifact();
+        fb.artifact(path.clone());
+        fb.artifact_hash(artifact.hash().unwrap());
+        fb.register_sync_action("done", ReturnResult::fail_empty());
+        let mut f = fb.build().unwrap();
+        assert!(matches!(f.run(), Ok(TickResult::Failure(_))));
+
+        let mut fb = ForesterBuilder::from_artifact();
+        fb.artifact(path.clone());
+        fb.artifact_hash("0".repeat(64));
+        fb.register_sync_action("done", ReturnResult::fail_empty());
+        let res = fb.build();
+        fs::remove_file(path).unwrap();
+        assert!(res.is_err());
+    }
+
+    let mut fb = ForesterBuilder::from_artifact();
+    fb.root(test_folder("recursion/bounded"));
+    assert!(fb.build().is_err());
+
+    // the file of the artifact is not set
+    let fb = ForesterBuilder::from_artifact();
+    assert!(matches!(fb.build(), Err(RuntimeError::WrongArgument(_))));
+}
@@ -0,0 +1,138 @@
+use crate::runtime::rtree::diff::Change;
+use crate::runtime::rtree::RuntimeTree;
+use crate::tests::test_folder;
+use crate::tree::project::diff::DefChange;
+use crate::tree::project::Project;
+use crate::visualizer::Visualizer;
+
+fn tree(text: &str) -> RuntimeTree {
+    RuntimeTree::build(Project::build_from_text(text.to_string()).unwrap())
+        .unwrap()
+        .tree
+}
+
+const OLD: &str = r#"
+import "std::actions"
+
+root main sequence {
+    fallback {
+        equal("a", "a")
+        fail("no")
+    }
+    approach(target = "door")
+    pick("box")
+}
+
+sequence approach(target: string) {
+    move_to(target)
+    success()
+}
+
+impl move_to(target: string);
+impl pick(item: string);
+"#;
+
+#[test]
+fn same() {
+    let diff = tree(OLD).diff(&tree(OLD));
+    assert!(diff.is_empty());
+    assert_eq!(diff.matched.len(), 9);
+}
+
+#[test]
+fn reparameterised() {
+    let new = OLD.replace(r#"pick("box")"#, r#"pick("ball")"#);
+    let diff = tree(OLD).diff(&tree(&new));
+    assert_eq!(
+        diff.to_string(),
+        "~ main/sequence/pick: (item=box) -> (item=ball)"
+    );
+    assert!(matches!(diff.changes[0], Change::Reparameterised { .. }));
+}
+
+#[test]
+fn added_removed() {
+    let new = OLD
+        .replace(
+            r#"pick("box")"#,
+            r#"pick("box")
+    success()"#,
+        )
+        .replace(r#"fail("no")"#, "");
+    let old = tree(OLD);
+    let new = tree(&new);
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.to_string(),
+        "+ main/sequence/success\n- main/sequence/fallback/fail(reason=no)"
+    );
+    assert_eq!(diff.added(&new).len(), 1);
+    assert_eq!(diff.removed(&old).len(), 1);
+}
+
+#[test]
+fn moved() {
+    let new = OLD.replace(r#"pick("box")"#, "").replace(
+        "success()",
+        r#"success()
+    pick("box")"#,
+    );
+    let diff = tree(OLD).diff(&tree(&new));
+    assert_eq!(
+        diff.to_string(),
+        "> main/sequence/pick(item=box) -> main/sequence/approach/pick(item=box)"
+    );
+}
+
+#[test]
+fn subtree() {
+    let new = OLD.replace(r#"approach(target = "door")"#, "");
+    let old = tree(OLD);
+    let diff = old.diff(&tree(&new));
+    assert_eq!(diff.to_string(), "- main/sequence/approach(target=door)");
+    assert_eq!(diff.removed(&old).len(), 3);
+}
+
+#[test]
+fn dot() {
+    let new = OLD
+        .replace(r#"pick("box")"#, r#"pick("ball")"#)
+        .replace(r#"fail("no")"#, "");
+    let dot = Visualizer::diff_dot(&tree(OLD), &tree(&new)).unwrap();
+    assert!(dot.contains("style=filled,fillcolor=gold"));
+    assert!(dot.contains("r7[label=\"(r7) fail (reason=no)\""));
+    assert!(dot.contains("3 -> r7 [style=dashed]"));
+}
+
+#[test]
+fn project() {
+    let old = Project::build_from_text(OLD.to_string()).unwrap();
+    let new = OLD
+        .replace(
+            "impl pick(item: string);",
+            "impl pick(item: string, speed: num);",
+        )
+        .replace("sequence approach", "fallback approach")
+        .replace("impl move_to(target: string);", "impl grasp();");
+    let new = Project::build_from_text(new).unwrap();
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.to_string(),
+        "* approach (_)\n~ pick (_): (item:string) -> (item:string, speed:num)\n- move_to (_)\n+ grasp (_)"
+    );
+    assert!(matches!(diff.changes[0], DefChange::Modified(_)));
+}
+
+#[test]
+fn project_moved() {
+    let old = Project::build("main.tree".to_string(), test_folder("diff/old")).unwrap();
+    let new = Project::build("main.tree".to_string(), test_folder("diff/new")).unwrap();
+    assert_eq!(
+        old.diff(&new).to_string(),
+        "> approach: nav.tree -> motion.tree\n> move_to: nav.tree -> motion.tree"
+    );
+
+    let old = RuntimeTree::build(old).unwrap().tree;
+    let new = RuntimeTree::build(new).unwrap().tree;
+    assert!(old.diff(&new).is_empty());
+}
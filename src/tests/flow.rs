@@ -3,8 +3,12 @@ use crate::runtime::action::builtin::data::GenerateData;
 use crate::runtime::action::{Impl, Tick};
 use crate::runtime::args::{RtArgs, RtValue};
 use crate::runtime::context::TreeContextRef;
-use crate::runtime::TickResult;
-use crate::tests::{fb, test_folder, turn_on_logs};
+use crate::runtime::rtree::RuntimeTree;
+use crate::runtime::{RtOk, TickResult};
+use crate::tests::{fb, test_folder, turn_on_logs, Work};
+use crate::tree::project::Project;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
 struct StoreTick;
 
 impl Impl for StoreTick {
@@ -652,3 +656,203 @@ fn parallel_simple_w_retry() {
         .as_int();
     assert_eq!(t2, Some(3));
 }
+
+struct Sensor {
+    halted: Arc<Mutex<Vec<String>>>,
+}
+
+impl Impl for Sensor {
+    fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+        let tick = args.find("tick".to_string()).and_then(RtValue::as_int);
+        let before = (ctx.current_tick() as i64) < tick.unwrap_or(0);
+        match args.find("result".to_string()).and_then(RtValue::as_string) {
+            Some(r) if r == "success_until" && before => Ok(TickResult::success()),
+            Some(r) if r == "success_until" => Ok(TickResult::failure("timeout".to_string())),
+            _ if before => Ok(TickResult::running()),
+            Some(r) if r == "success" => Ok(TickResult::success()),
+            _ => Ok(TickResult::failure("sensor".to_string())),
+        }
+    }
+
+    fn halt(&self, args: RtArgs, _ctx: TreeContextRef) -> RtOk {
+        let name = args.find("name".to_string()).and_then(RtValue::as_string);
+        self.halted.lock().unwrap().push(name.unwrap_or_default());
+        Ok(())
+    }
+}
+
+fn parallel_policy(tree: &str) -> (Tick, Vec<String>) {
+    let halted = Arc::new(Mutex::new(vec![]));
+    let mut fb = fb("flow/parallel/policy");
+    fb.main_tree(tree.to_string());
+    fb.register_sync_action(
+        "sensor",
+        Sensor {
+            halted: halted.clone(),
+        },
+    );
+    let mut f = fb.build().unwrap();
+    let result = f.run_until(Some(20));
+    let halted = halted.lock().unwrap().clone();
+    (result, halted)
+}
+
+#[test]
+fn parallel_success_threshold() {
+    let (result, halted) = parallel_policy("two_of_three");
+    assert_eq!(result, Ok(TickResult::success()));
+    assert_eq!(halted, vec!["radar"]);
+
+    // the third sensor is not ticked at all
+    let (result, halted) = parallel_policy("from_param");
+    assert_eq!(result, Ok(TickResult::success()));
+    assert!(halted.is_empty());
+}
+
+#[test]
+fn parallel_failure_threshold() {
+    let (result, halted) = parallel_policy("two_failed");
+    assert_eq!(
+        result,
+        Ok(TickResult::failure(
+            "parallel failure: 2 of 3 children failed".to_string()
+        ))
+    );
+    assert_eq!(halted, vec!["radar"]);
+
+    let (result, halted) = parallel_policy("one_failed");
+    assert_eq!(
+        result,
+        Ok(TickResult::failure(
+            "parallel failure: 1 of 2 children failed".to_string()
+        ))
+    );
+    assert_eq!(halted, vec!["lidar"]);
+}
+
+#[test]
+fn parallel_first_finished() {
+    let (result, mut halted) = parallel_policy("first_finished");
+    assert_eq!(
+        result,
+        Ok(TickResult::failure(
+            "parallel failure: 1 of 3 children failed".to_string()
+        ))
+    );
+    halted.sort();
+    assert_eq!(halted, vec!["lidar", "radar"]);
+}
+
+#[test]
+fn parallel_halts_running_children() {
+    let (result, mut halted) = parallel_policy("halted");
+    assert_eq!(result, Ok(TickResult::failure("timeout".to_string())));
+    halted.sort();
+    assert_eq!(halted, vec!["camera", "lidar"]);
+}
+
+#[test]
+fn parallel_concurrent_halts_running_children() {
+    let (result, mut halted) = parallel_policy("concurrent_first_finished");
+    assert_eq!(
+        result,
+        Ok(TickResult::failure(
+            "parallel failure: 1 of 3 children failed".to_string()
+        ))
+    );
+    halted.sort();
+    assert_eq!(halted, vec!["lidar", "radar"]);
+}
+
+#[test]
+fn parallel_concurrent() {
+    turn_on_logs();
+
+    let work = Work::default();
+
+    let mut fb = fb("flow/parallel/concurrent");
+    fb.register_sync_action("check", work.clone());
+
+    let mut f = fb.build().unwrap();
+    let result = f.run_until(Some(5));
+
+    assert_eq!(result, Ok(TickResult::success()));
+    // three checks run at once and the one in the sequence is ticked after them
+    assert_eq!(work.max_active.load(Ordering::SeqCst), 3);
+
+    let bb = f.bb.lock().unwrap();
+    for key in ["db", "cache", "queue", "disk"] {
+        let tick = bb.get(key.to_string()).ok().flatten().cloned();
+        assert_eq!(tick.and_then(|t| t.as_int()), Some(1));
+    }
+}
+
+#[test]
+fn parallel_wrong_policy() {
+    let text = r#"
+import "std::actions"
+root main parallel(quorum = 2) {
+    success()
+    success()
+}
+"#;
+    let project = Project::build_from_text(text.to_string()).unwrap();
+    assert!(RuntimeTree::build(project).is_err());
+
+    let text = r#"
+import "std::actions"
+root main sequence(success = 2) {
+    success()
+}
+"#;
+    assert!(Project::build_from_text(text.to_string()).is_err());
+}
+
+fn parallel_build_error(policy: &str) -> String {
+    let text = format!(
+        r#"
+import "std::actions"
+root main sequence {{
+    parallel({policy}) {{
+        success()
+        success()
+    }}
+}}
+"#
+    );
+    let project = Project::build_from_text(text).unwrap();
+    RuntimeTree::build(project).err().unwrap().to_string()
+}
+
+#[test]
+fn parallel_wrong_thresholds() {
+    for policy in [
+        "success = 0",
+        "success = 3",
+        "failure = 0",
+        "failure = 3",
+        "success = 1.5",
+        "success = \"2\"",
+    ] {
+        let err = parallel_build_error(policy);
+        assert!(err.contains("in the tree main"), "{policy}: {err}");
+    }
+
+    let err = parallel_build_error("first = true, success = 1");
+    assert!(err.contains("either first or the thresholds"), "{err}");
+
+    let text = r#"
+import "std::actions"
+root main confirm(3)
+
+sequence confirm(quorum: num) {
+    parallel(success = quorum) {
+        success()
+        success()
+    }
+}
+"#;
+    let project = Project::build_from_text(text.to_string()).unwrap();
+    let err = RuntimeTree::build(project).err().unwrap().to_string();
+    assert!(err.contains("in the tree confirm"), "{err}");
+}
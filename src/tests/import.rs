@@ -1,9 +1,13 @@
 use std::collections::HashMap;
-use crate::runtime::args::RtArgs;
+use crate::runtime::action::{Impl, Tick};
+use crate::runtime::args::{RtArgs, RtValue};
+use crate::runtime::builder::ForesterBuilder;
+use crate::runtime::context::TreeContextRef;
 use crate::runtime::rtree::rnode::FlowType::{Root, Sequence};
 use crate::runtime::rtree::rnode::RNode::{Flow, Leaf};
 use crate::runtime::rtree::rnode::RNodeName::{Alias, Lambda, Name};
-use crate::tests::fb;
+use crate::runtime::TickResult;
+use crate::tests::{fb, test_folder};
 
 #[test]
 fn smoke() {
@@ -17,4 +21,62 @@ fn smoke() {
             (5, Leaf(Name("h".to_string(), "../util.tree".to_string()), RtArgs(vec![]))),
         ]
     ))
-}
\ No newline at end of file
+}
+#[test]
+fn namespace() {
+    struct Drive;
+    impl Impl for Drive {
+        fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+            let key = args.find("key".to_string()).unwrap().as_string().unwrap();
+            let mode = args.find("mode".to_string()).unwrap();
+            ctx.bb().lock().unwrap().put(key, mode)?;
+            Ok(TickResult::success())
+        }
+    }
+
+    let mut fb = fb("import/namespace");
+    fb.register_sync_action("drive", Drive);
+    let mut f = fb.build().unwrap();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+
+    let bb = f.bb.lock().unwrap();
+    let value = |k: &str| bb.get(k.to_string()).unwrap().cloned().unwrap();
+    assert_eq!(value("a"), RtValue::str("auto".to_string()));
+    assert_eq!(value("b"), RtValue::str("other".to_string()));
+    assert_eq!(value("local"), RtValue::str("main".to_string()));
+    assert_eq!(value("attempts"), RtValue::int(2));
+}
+
+#[test]
+fn collision() {
+    let err = fb("import/collision").build().err().unwrap();
+    assert!(format!("{err:?}").contains(
+        "the name go is ambiguous since it is defined in the imported files a.tree, b.tree"
+    ));
+}
+
+#[test]
+fn libraries() {
+    let target = |fb: ForesterBuilder| {
+        let mut f = fb.build().unwrap();
+        assert_eq!(f.run(), Ok(TickResult::success()));
+        let bb = f.bb.lock().unwrap();
+        bb.get("target".to_string()).unwrap().cloned()
+    };
+    assert_eq!(
+        target(fb("import/libs/project")),
+        Some(RtValue::str("shared".to_string()))
+    );
+
+    let mut fb = fb("import/libs/project");
+    fb.library("nav", test_folder("import/libs/other/nav"));
+    assert_eq!(target(fb), Some(RtValue::str("other".to_string())));
+}
+
+#[test]
+fn libraries_conflict() {
+    let mut fb = fb("import/libs/project");
+    fb.search_path(test_folder("import/libs/other"));
+    let err = format!("{:?}", fb.build().err().unwrap());
+    assert!(err.contains("the import nav::approach.tree is resolved ambiguously"));
+}
@@ -0,0 +1,102 @@
+use crate::runtime::action::builtin::ReturnResult;
+use crate::runtime::args::RtArgs;
+use crate::runtime::forester::Forester;
+use crate::runtime::rtree::rnode::RNode;
+use crate::runtime::rtree::{RuntimeTree, RuntimeTreeStarter};
+use crate::runtime::TickResult;
+use crate::tests::{fb, test_folder};
+use crate::tree::project::Project;
+
+fn project(folder: &str) -> Project {
+    Project::build("main.tree".to_string(), test_folder(folder)).unwrap()
+}
+
+fn lazy(folder: &str) -> RuntimeTreeStarter {
+    RuntimeTree::build_lazy(project(folder), None).unwrap()
+}
+
+#[test]
+fn only_root() {
+    let starter = lazy("lazy");
+    let eager = RuntimeTree::build(project("lazy")).unwrap();
+    assert_eq!(starter.tree.nodes.len(), 1);
+    assert_eq!(starter.tree.pending(), vec![2]);
+    assert_eq!(starter.tree.max_id(), 2);
+    assert_eq!(starter.actions, eager.actions);
+    assert_eq!(starter.std_actions, eager.std_actions);
+
+    let mut tree = starter.tree;
+    tree.instantiate_all().unwrap();
+    assert!(tree.pending().is_empty());
+    assert_eq!(tree.nodes.len(), eager.tree.nodes.len());
+}
+
+#[test]
+fn ho() {
+    let mut tree = lazy("ho_tree").tree;
+    tree.instantiate_all().unwrap();
+    let eager = RuntimeTree::build(project("ho_tree")).unwrap().tree;
+    let names = |t: &RuntimeTree| {
+        let mut names: Vec<_> = t
+            .nodes
+            .values()
+            .map(|n| format!("{:?}", n.name()))
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(names(&tree), names(&eager));
+}
+
+fn forester(grasp: ReturnResult) -> Forester {
+    let mut fb = fb("lazy");
+    fb.lazy();
+    fb.register_sync_action("approach", ReturnResult::success());
+    fb.register_sync_action("grasp", grasp);
+    fb.build().unwrap()
+}
+
+#[test]
+fn ticked_only() {
+    let mut f = forester(ReturnResult::success());
+    assert_eq!(f.run(), Ok(TickResult::success()));
+    // root, fallback, pick("cube") and its actions
+    assert_eq!(f.tree.nodes.len(), 5);
+    assert_eq!(f.tree.pending().len(), 2);
+
+    let mut f = forester(ReturnResult::fail_empty());
+    assert!(matches!(f.run(), Ok(TickResult::Failure(_))));
+    assert!(f.tree.pending().is_empty());
+    assert_eq!(f.tree.nodes.len(), 13);
+}
+
+#[test]
+fn trimmed() {
+    let mut tree = lazy("lazy").tree;
+    let id = tree.max_id() + 1;
+    tree.insert(
+        id,
+        RNode::action(
+            "grasp".to_string(),
+            "main.tree".to_string(),
+            RtArgs::default(),
+        ),
+    );
+    tree.instantiate(2).unwrap();
+    assert_eq!(tree.node(&2).unwrap().children(), vec![4, 5, 6]);
+    assert_eq!(tree.max_id(), 6);
+}
+
+#[test]
+fn bounded() {
+    let starter = RuntimeTree::build_lazy(project("recursion/bounded"), Some(2)).unwrap();
+    assert!(starter.actions.contains("fail"));
+    assert!(RuntimeTree::build_lazy(project("recursion/bounded"), None).is_err());
+
+    let mut fb = fb("recursion/bounded");
+    fb.lazy();
+    fb.recursion_depth(3);
+    fb.register_sync_action("done", ReturnResult::fail_empty());
+    let mut f = fb.build().unwrap();
+    assert!(matches!(f.run(), Ok(TickResult::Failure(_))));
+}
@@ -0,0 +1,120 @@
+use crate::runtime::action::builtin::ReturnResult;
+use crate::tests::{fb, test_folder};
+use crate::tree::project::lint::{check, Level, Lint, LintConfig};
+use crate::tree::project::Project;
+
+fn warnings(folder: &str, config: &LintConfig) -> Vec<String> {
+    let project = Project::build("main.tree".to_string(), test_folder(folder)).unwrap();
+    check(&project, config)
+        .warnings
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn all_lints() {
+    assert_eq!(
+        warnings("lint", &LintConfig::default()),
+        vec![
+            "warn[unused_definition] extra.tree:1:1: the tree extra is never invoked from the root main",
+            "warn[unused_definition] helpers.tree:2:1: the tree unused_one is never invoked from the root main",
+            "warn[unused_definition] helpers.tree:4:1: the tree helper_only is never invoked from the root main",
+            "warn[unused_import] main.tree:2:1: the name unused_one imported from helpers.tree is never used",
+            "warn[shadowed_alias] main.tree:6:1: the alias say for log imported from utils.tree is shadowed by the definition say of the file",
+            "warn[unused_import] main.tree:9:1: the namespace nav for the file nav.tree is never used",
+            "warn[unused_import] main.tree:10:1: the file extra.tree is imported but none of its definitions is used",
+            "warn[unused_param] main.tree:15:1: the parameter timeout of the tree go is never used",
+            "warn[unused_definition] main.tree:19:1: the tree forgotten is never invoked from the root main",
+            "warn[unreachable_child] main.tree:27:5: the children of the fallback after success() are never ticked since it always succeeds",
+            "warn[unused_definition] nav.tree:1:1: the tree dock is never invoked from the root main",
+            "warn[unused_definition] utils.tree:1:1: the tree log is never invoked from the root main",
+        ]
+    );
+}
+
+#[test]
+fn levels() {
+    let config = LintConfig::default()
+        .with(Lint::UnusedDefinition, Level::Allow)
+        .with(Lint::UnusedImport, Level::Allow)
+        .with(Lint::UnusedParam, Level::Deny);
+    assert_eq!(
+        warnings("lint", &config),
+        vec![
+            "warn[shadowed_alias] main.tree:6:1: the alias say for log imported from utils.tree is shadowed by the definition say of the file",
+            "deny[unused_param] main.tree:15:1: the parameter timeout of the tree go is never used",
+            "warn[unreachable_child] main.tree:27:5: the children of the fallback after success() are never ticked since it always succeeds",
+        ]
+    );
+
+    let project = Project::build("main.tree".to_string(), test_folder("lint")).unwrap();
+    assert!(project.lint(&config).is_denied());
+    assert!(!project.lint(&LintConfig::default()).is_denied());
+    let quiet = LintConfig {
+        default: Level::Allow,
+        ..LintConfig::default()
+    };
+    assert!(project.lint(&quiet).is_empty());
+}
+
+#[test]
+fn clean_projects() {
+    let config = LintConfig::default().with(Lint::UnusedDefinition, Level::Allow);
+    for folder in ["import/path", "ho_tree", "typecheck/enums", "params/out"] {
+        assert_eq!(warnings(folder, &config), Vec::<String>::new(), "{folder}");
+    }
+}
+
+#[test]
+fn param_in_lambda_args() {
+    let text = r#"
+impl sensor(name: string);
+
+root main confirm(2)
+
+sequence confirm(quorum: num) {
+    parallel(success = quorum) {
+        sensor("lidar")
+        sensor("camera")
+    }
+}
+"#;
+    let project = Project::build_from_text(text.to_string()).unwrap();
+    let report = check(&project, &LintConfig::default());
+    assert!(report.of(Lint::UnusedParam).is_empty());
+}
+
+#[test]
+fn unimplemented_actions() {
+    let mut fb = fb("lint");
+    fb.register_sync_action("drive", ReturnResult::success());
+    let report = fb.lint(&LintConfig::default()).unwrap();
+    let unimplemented: Vec<String> = report
+        .of(Lint::UnimplementedAction)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        unimplemented,
+        vec![
+            "warn[unimplemented_action] helpers.tree:1:1: the action approach does not have a registered implementation",
+            "warn[unimplemented_action] main.tree:13:1: the action say does not have a registered implementation",
+        ]
+    );
+}
+
+#[test]
+fn json() {
+    let config = LintConfig::default().with(Lint::UnusedDefinition, Level::Allow);
+    let project = Project::build("main.tree".to_string(), test_folder("lint")).unwrap();
+    let json = project.lint(&config).to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(json.as_str()).unwrap();
+    let first = &value["warnings"][0];
+    assert_eq!(first["lint"], "unused_import");
+    assert_eq!(first["level"], "warn");
+    assert_eq!(first["file"], "main.tree");
+    assert_eq!(first["line"], 2);
+    assert_eq!(first["col"], 1);
+    assert_eq!(value["warnings"].as_array().unwrap().len(), 6);
+}
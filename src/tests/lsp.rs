@@ -0,0 +1,108 @@
+use crate::lsp;
+use crate::tests::test_folder;
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidOpenTextDocument, Exit, Initialized, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{GotoDefinition, Initialize, Request as _, Shutdown};
+use lsp_types::{
+    DidOpenTextDocumentParams, GotoDefinitionResponse, InitializeParams, Position,
+    PublishDiagnosticsParams, TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams,
+    Url,
+};
+use serde_json::{json, Value};
+use std::fs;
+use std::thread;
+
+fn request(client: &Connection, id: i32, method: &str, params: Value) -> Response {
+    client
+        .sender
+        .send(Message::Request(Request::new(
+            RequestId::from(id),
+            method.to_string(),
+            params,
+        )))
+        .unwrap();
+    loop {
+        if let Message::Response(resp) = client.receiver.recv().unwrap() {
+            return resp;
+        }
+    }
+}
+
+fn notify(client: &Connection, method: &str, params: Value) {
+    client
+        .sender
+        .send(Message::Notification(Notification::new(
+            method.to_string(),
+            params,
+        )))
+        .unwrap();
+}
+
+#[test]
+fn stdio_session() {
+    let root = test_folder("import/path");
+    let main = root.join("main.tree");
+    let main_uri = Url::from_file_path(&main).unwrap();
+    let (server, client) = Connection::memory();
+    let handle = thread::spawn(move || lsp::run(server));
+
+    #[allow(deprecated)]
+    let init = InitializeParams {
+        root_uri: Some(Url::from_file_path(&root).unwrap()),
+        ..Default::default()
+    };
+    let resp = request(&client, 1, Initialize::METHOD, json!(init));
+    assert!(resp.error.is_none());
+    notify(&client, Initialized::METHOD, json!({}));
+
+    let text = fs::read_to_string(&main).unwrap();
+    notify(
+        &client,
+        DidOpenTextDocument::METHOD,
+        json!(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(
+                main_uri.clone(),
+                "f-tree".to_string(),
+                1,
+                format!("{text}\nsequence broken {{ absent() }}")
+            ),
+        }),
+    );
+    let Message::Notification(n) = client.receiver.recv().unwrap() else {
+        panic!("the diagnostics are expected")
+    };
+    assert_eq!(n.method, PublishDiagnostics::METHOD);
+    let diagnostics: PublishDiagnosticsParams = serde_json::from_value(n.params).unwrap();
+    assert_eq!(diagnostics.uri, main_uri);
+    let messages: Vec<String> = diagnostics
+        .diagnostics
+        .into_iter()
+        .map(|d| d.message)
+        .collect();
+    assert_eq!(messages, vec!["the call absent can not be found"]);
+
+    let resp = request(
+        &client,
+        2,
+        GotoDefinition::METHOD,
+        json!(TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(main_uri),
+            Position::new(6, 5)
+        )),
+    );
+    let def: GotoDefinitionResponse = serde_json::from_value(resp.result.unwrap()).unwrap();
+    let GotoDefinitionResponse::Scalar(loc) = def else {
+        panic!("the single location is expected")
+    };
+    assert_eq!(
+        loc.uri,
+        Url::from_file_path(root.join("nested/nested2.tree")).unwrap()
+    );
+    assert_eq!(loc.range.start, Position::new(0, 5));
+
+    request(&client, 3, Shutdown::METHOD, Value::Null);
+    notify(&client, Exit::METHOD, Value::Null);
+    assert!(handle.join().unwrap().is_ok());
+}
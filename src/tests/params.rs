@@ -1,7 +1,9 @@
 use crate::runtime::action::{Impl, Tick};
-use crate::runtime::args::{RtArgs};
+use crate::runtime::args::{RtArgs, RtValue};
 use crate::runtime::context::TreeContextRef;
+use crate::runtime::rtree::rnode::{DecoratorType, RNode};
 use crate::runtime::{TickResult};
+use std::collections::HashMap;
 
 use crate::tests::{fb, turn_on_logs};
 
@@ -40,3 +42,173 @@ fn any_type() {
     assert_eq!(b, "2".to_string());
 }
 
+
+#[test]
+fn out_params() {
+    struct Fetch;
+    impl Impl for Fetch {
+        fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+            let url = args.find("url".to_string()).unwrap().as_string().unwrap();
+            let attempts = args
+                .find("attempts".to_string())
+                .unwrap()
+                .cast(ctx.clone())
+                .int()
+                .unwrap_or_default()
+                .unwrap_or_default();
+            args.write("attempts", RtValue::int(attempts + 1), ctx.clone())?;
+            args.write("result", RtValue::str(url), ctx.clone())?;
+            assert!(args
+                .write("url", RtValue::str("x".to_string()), ctx)
+                .is_err());
+            Ok(TickResult::success())
+        }
+    }
+
+    let mut fb = fb("params/out");
+    fb.register_sync_action("fetch", Fetch);
+    let mut f = fb.build().unwrap();
+    f.bb.lock()
+        .unwrap()
+        .put("counter".to_string(), RtValue::int(0))
+        .unwrap();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+
+    let bb = f.bb.lock().unwrap();
+    let response = bb.get("response".to_string()).unwrap().cloned();
+    let counter = bb.get("counter".to_string()).unwrap().cloned();
+    assert_eq!(response, Some(RtValue::str("second".to_string())));
+    assert_eq!(counter, Some(RtValue::int(2)));
+}
+
+#[test]
+fn out_params_fail() {
+    let err = fb("params/out_fail").build().err().unwrap();
+    assert!(
+        format!("{err:?}").contains("expects a pointer to bb"),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn struct_params() {
+    #[derive(serde::Deserialize)]
+    struct Point {
+        x: i64,
+        y: i64,
+        label: Option<String>,
+    }
+    struct Follow;
+    impl Impl for Follow {
+        fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+            let points: Vec<Point> = args
+                .find("points".to_string())
+                .unwrap()
+                .cast(ctx.clone())
+                .into_struct()?;
+            let last = points.last().unwrap();
+            let key = format!(
+                "{}:{}:{}",
+                last.x,
+                last.y,
+                last.label.clone().unwrap_or_default()
+            );
+            ctx.bb()
+                .lock()
+                .unwrap()
+                .put(key, RtValue::int(points.len() as i64))?;
+            Ok(TickResult::success())
+        }
+    }
+
+    let mut fb = fb("params/schema");
+    fb.register_sync_action("follow", Follow);
+    let mut f = fb.build().unwrap();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+
+    let bb = f.bb.lock().unwrap();
+    assert_eq!(bb.get("0:0:".to_string()).unwrap(), Some(&RtValue::int(1)));
+    assert_eq!(
+        bb.get("3:4:end".to_string()).unwrap(),
+        Some(&RtValue::int(2))
+    );
+}
+
+#[test]
+fn default_params() {
+    struct Go;
+    impl Impl for Go {
+        fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+            let key = args.find("key".to_string()).unwrap().as_string().unwrap();
+            let speed = args.find("speed".to_string()).unwrap().as_int().unwrap();
+            let label = args
+                .find("label".to_string())
+                .and_then(RtValue::as_string)
+                .unwrap_or("none".to_string());
+            ctx.bb()
+                .lock()
+                .unwrap()
+                .put(key, RtValue::str(format!("{speed}:{label}")))?;
+            Ok(TickResult::success())
+        }
+    }
+
+    let mut fb = fb("params/defaults");
+    fb.register_sync_action("go", Go);
+    let mut f = fb.build().unwrap();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+
+    let bb = f.bb.lock().unwrap();
+    let value = |k: &str| bb.get(k.to_string()).unwrap().cloned().unwrap();
+    assert_eq!(value("a"), RtValue::str("10:none".to_string()));
+    assert_eq!(value("b"), RtValue::str("20:none".to_string()));
+    assert_eq!(value("c"), RtValue::str("10:fast".to_string()));
+    assert_eq!(value("d"), RtValue::str("1:none".to_string()));
+    assert_eq!(value("e"), RtValue::str("2:none".to_string()));
+}
+
+#[test]
+fn consts_and_enums() {
+    struct Drive;
+    impl Impl for Drive {
+        fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+            let key = args.find("key".to_string()).unwrap().as_string().unwrap();
+            let mode = args.find("mode".to_string()).unwrap().as_string().unwrap();
+            let speed = args
+                .find("speed".to_string())
+                .unwrap()
+                .cast(ctx.clone())
+                .int()?
+                .unwrap_or_default();
+            ctx.bb()
+                .lock()
+                .unwrap()
+                .put(key, RtValue::str(format!("{mode}:{speed}")))?;
+            Ok(TickResult::success())
+        }
+    }
+
+    let mut fb = fb("params/consts");
+    fb.register_sync_action("drive", Drive);
+    let mut f = fb.build().unwrap();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+
+    let retry = f.tree.nodes.values().find_map(|n| match n {
+        RNode::Decorator(DecoratorType::Retry, args, _) => args.first(),
+        _ => None,
+    });
+    assert_eq!(retry, Some(RtValue::int(3)));
+
+    let bb = f.bb.lock().unwrap();
+    let value = |k: &str| bb.get(k.to_string()).unwrap().cloned().unwrap();
+    assert_eq!(value("a"), RtValue::str("auto:5".to_string()));
+    assert_eq!(value("b"), RtValue::str("manual:7".to_string()));
+    assert_eq!(value("c"), RtValue::str("auto:15".to_string()));
+    assert_eq!(
+        value("home"),
+        RtValue::Object(HashMap::from_iter(vec![
+            ("x".to_string(), RtValue::int(1)),
+            ("y".to_string(), RtValue::int(2)),
+        ]))
+    );
+}
@@ -1,5 +1,7 @@
+use crate::runtime::rtree::RuntimeTree;
 use crate::tests::test_folder;
 use crate::tree::parser::Parser;
+use crate::tree::project::Project;
 use std::fs;
 
 #[test]
@@ -10,3 +12,46 @@ fn smoke() {
     let result = parser.parse().unwrap();
     assert_eq!(result.0.len(), 6);
 }
+
+#[test]
+fn error_location() {
+    let text = r#"
+import "std::actions"
+
+sequence a { success() }
+sequence a { fail("reason") }
+root main { a() }
+"#;
+    let err = Project::build_from_text(text.to_string()).unwrap_err();
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(diagnostic.message, "the tree 'a' is already presented");
+    assert_eq!(diagnostic.location.to_string(), "_:5:1");
+    assert_eq!(diagnostic.notes[0].location.to_string(), "_:4:1");
+
+    let text = r#"
+root main {
+    sequence {
+        missing()
+    }
+}
+"#;
+    let project = Project::build_from_text(text.to_string()).unwrap();
+    let err = RuntimeTree::build(project).err().unwrap();
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(diagnostic.location.to_string(), "_:4:9");
+    assert_eq!(
+        diagnostic.render(),
+        "error: the call missing can not be found among the file in the project\n \
+        --> _:4:9\n  \
+        |\n\
+        4 |         missing()\n  \
+        |         ^^^^^^^^^\n"
+    );
+}
+
+#[test]
+fn import_error_location() {
+    let err = Project::build("main.tree".to_string(), test_folder("import/absent")).unwrap_err();
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(diagnostic.location.to_string(), "main.tree:1:1");
+}
@@ -0,0 +1,47 @@
+use crate::tests::test_folder;
+use crate::tree::parser::Parser;
+use crate::tree::printer::Printer;
+use std::fs;
+use std::path::PathBuf;
+
+fn tree_files(dir: PathBuf, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            tree_files(path, files);
+        } else if path.extension().map(|e| e == "tree").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+}
+
+#[test]
+fn round_trip() {
+    let mut files = vec![];
+    tree_files(test_folder(""), &mut files);
+    assert!(!files.is_empty());
+
+    let printer = Printer::default();
+    for file in files {
+        let src = fs::read_to_string(&file).unwrap();
+        let Ok(ast) = Parser::new(src.as_str()).and_then(|p| p.parse()) else {
+            continue;
+        };
+        let formatted = printer.format(src.as_str()).unwrap();
+        let formatted_ast = Parser::new(formatted.as_str()).unwrap().parse().unwrap();
+        assert_eq!(
+            ast, formatted_ast,
+            "the file {:?} changed the meaning",
+            file
+        );
+        assert_eq!(
+            formatted,
+            printer.format(formatted.as_str()).unwrap(),
+            "the formatting of the file {:?} is not idempotent",
+            file
+        );
+
+        let printed = printer.print(&ast);
+        assert_eq!(ast, Parser::new(printed.as_str()).unwrap().parse().unwrap());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::runtime::rtree::query::{Selector, Step};
+use crate::runtime::rtree::rnode::{DecoratorType, FlowType};
+use crate::runtime::rtree::RuntimeTree;
+use crate::tree::project::Project;
+
+fn tree() -> RuntimeTree {
+    let text = r#"
+import "std::actions" {
+    fail_empty => give_up
+}
+
+root main sequence {
+    retry(3) approach("door")
+    fallback {
+        pick(item = "box")
+        give_up()
+    }
+    approach("window")
+}
+
+sequence approach(target: string) {
+    move_to(target)
+    look_around()
+}
+
+impl move_to(target: string);
+impl look_around();
+impl pick(item: string);
+"#;
+    RuntimeTree::build(Project::build_from_text(text.to_string()).unwrap())
+        .unwrap()
+        .tree
+}
+
+fn names(tree: &RuntimeTree, ids: Vec<usize>) -> Vec<String> {
+    ids.iter()
+        .map(|id| {
+            let node = tree.nodes.get(id).unwrap();
+            let args = node
+                .args()
+                .0
+                .iter()
+                .map(|a| a.value.to_string())
+                .collect::<Vec<_>>();
+            match node.name().and_then(|n| n.name().ok()) {
+                Some(name) => format!("{name}({})", args.join(",")),
+                None => format!("_({})", args.join(",")),
+            }
+        })
+        .collect()
+}
+
+fn query(tree: &RuntimeTree, selector: &str) -> Vec<String> {
+    names(tree, tree.analyze().query(selector).unwrap())
+}
+
+#[test]
+fn dfs() {
+    let tree = tree();
+    let ids = tree.dfs().map(|(id, _)| id).collect::<Vec<_>>();
+    assert_eq!(
+        names(&tree, ids),
+        vec![
+            "main()",
+            "_()",
+            "_(3)",
+            "approach(door)",
+            "move_to(door)",
+            "look_around()",
+            "_()",
+            "pick(box)",
+            "fail_empty()",
+            "approach(window)",
+            "move_to(window)",
+            "look_around()",
+        ]
+    );
+}
+
+#[test]
+fn select() {
+    let tree = tree();
+    assert_eq!(
+        query(&tree, "sequence > action[name=move_to]"),
+        vec!["move_to(door)", "move_to(window)"]
+    );
+    assert_eq!(
+        query(&tree, "retry action[name=move_to]"),
+        vec!["move_to(door)"]
+    );
+    assert_eq!(
+        query(&tree, "sequence[args.target=window] > *"),
+        vec!["move_to(window)", "look_around()"]
+    );
+    assert_eq!(query(&tree, "[alias=give_up]"), vec!["fail_empty()"]);
+    assert_eq!(
+        query(&tree, "action[file=std::actions]"),
+        vec!["fail_empty()"]
+    );
+    assert_eq!(query(&tree, "action[args.item]"), vec!["pick(box)"]);
+    assert_eq!(
+        query(&tree, "action[name=move_to] + action"),
+        vec!["look_around()", "look_around()"]
+    );
+    assert_eq!(query(&tree, "retry ~ sequence"), vec!["approach(window)"]);
+    assert_eq!(query(&tree, "retry + sequence"), Vec::<String>::new());
+    assert_eq!(query(&tree, "decorator"), vec!["_(3)"]);
+
+    let selector = Selector::new(Step::decorator(DecoratorType::Retry))
+        .descendant(Step::flow(FlowType::Sequence).arg("target", "door"));
+    assert_eq!(
+        names(&tree, tree.analyze().select(&selector)),
+        vec!["approach(door)"]
+    );
+}
+
+#[test]
+fn navigation() {
+    let tree = tree();
+    let an = tree.analyze();
+    let pick = an.query_first("action[name=pick]").unwrap().unwrap();
+    let fallback = an.query_first("fallback").unwrap().unwrap();
+    let root = an.query_first("root").unwrap().unwrap();
+
+    assert_eq!(
+        an.ancestors(pick),
+        vec![fallback, *an.parent(&fallback).unwrap(), root]
+    );
+    assert_eq!(names(&tree, an.siblings(pick)), vec!["fail_empty()"]);
+    assert_eq!(
+        names(&tree, an.next_sibling(pick).into_iter().collect()),
+        vec!["fail_empty()"]
+    );
+    assert_eq!(an.prev_sibling(pick), None);
+    assert_eq!(names(&tree, an.prev_siblings(fallback)), vec!["_(3)"]);
+    assert_eq!(
+        names(&tree, an.next_siblings(fallback)),
+        vec!["approach(window)"]
+    );
+    assert_eq!(
+        names(&tree, an.descendants(fallback)),
+        vec!["pick(box)", "fail_empty()"]
+    );
+    assert_eq!(an.children(fallback).len(), 2);
+    assert!(an.query("sequence >").is_err());
+}
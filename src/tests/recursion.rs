@@ -0,0 +1,62 @@
+use crate::runtime::action::builtin::ReturnResult;
+use crate::runtime::rtree::rnode::RNode;
+use crate::runtime::rtree::rnode::RNodeName::Name;
+use crate::runtime::rtree::RuntimeTree;
+use crate::runtime::TickResult;
+use crate::tests::{fb, test_folder};
+use crate::tree::project::Project;
+
+fn error(folder: &str) -> String {
+    let project = Project::build("main.tree".to_string(), test_folder(folder)).unwrap();
+    let err = RuntimeTree::build(project).err().unwrap();
+    err.diagnostic().unwrap().to_string()
+}
+
+#[test]
+fn mutual() {
+    assert_eq!(
+        error("recursion/mutual"),
+        "guard.tree:6:5: the tree patrol invokes itself recursively: \
+         patrol (patrol.tree) -> guard (guard.tree) -> patrol (patrol.tree). \
+         Set the recursion depth to expand it a limited number of times"
+    );
+}
+
+#[test]
+fn passed_itself() {
+    assert_eq!(
+        error("recursion/ho"),
+        "main.tree:7:10: the tree again invokes itself recursively: \
+         again (main.tree) -> again (main.tree). \
+         Set the recursion depth to expand it a limited number of times"
+    );
+}
+
+#[test]
+fn bounded() {
+    let project =
+        Project::build("main.tree".to_string(), test_folder("recursion/bounded")).unwrap();
+    assert!(RuntimeTree::build(project.clone()).is_err());
+
+    let starter = RuntimeTree::build_with_depth(project, 2).unwrap();
+    let count = |name: &str| {
+        starter
+            .tree
+            .nodes
+            .values()
+            .filter(|n| n.is_name(name))
+            .count()
+    };
+    assert_eq!(count("search"), 2);
+    assert_eq!(count("done"), 2);
+    assert!(starter.tree.nodes.values().any(|n| matches!(
+        n,
+        RNode::Leaf(Name(name, file), _) if name == "fail" && file == "std::actions"
+    )));
+
+    let mut fb = fb("recursion/bounded");
+    fb.recursion_depth(3);
+    fb.register_sync_action("done", ReturnResult::fail_empty());
+    let mut f = fb.build().unwrap();
+    assert!(matches!(f.run(), Ok(TickResult::Failure(_))));
+}
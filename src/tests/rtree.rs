@@ -62,6 +62,7 @@ mod tests {
                         )
                     )
                 ]),
+                expansion: None,
             }
         )
     }
@@ -84,7 +85,7 @@ mod tests {
                         "main.tree".to_string(),
                         RtArgs(vec![RtArgument::new(
                             "t".to_string(),
-                            RtValue::Call(Call::Lambda(
+                            RtValue::Call(Call::lambda(
                                 TreeType::Sequence,
                                 Calls {
                                     elems: vec![Call::invocation("success", Default::default())],
@@ -100,6 +101,7 @@ mod tests {
                     RNode::Leaf(Name("success".to_string(),"std::actions".to_string()), Default::default()),
                 ),
             ]),
+            expansion: None,
         };
         assert_eq!(tree, test_tree);
     }
@@ -125,6 +127,7 @@ mod tests {
                     ),
                 ),
             ]),
+            expansion: None,
         };
         assert_eq!(tree, test_tree);
     }
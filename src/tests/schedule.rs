@@ -0,0 +1,202 @@
+use crate::runtime::action::{Impl, ImplAsync, Tick};
+use crate::runtime::args::{RtArgs, RtValue};
+use crate::runtime::builder::ForesterBuilder;
+use crate::runtime::context::TreeContextRef;
+use crate::runtime::env::daemon::context::DaemonContext;
+use crate::runtime::env::daemon::{Daemon, DaemonFn, StopFlag};
+use crate::runtime::forester::schedule::TickMode;
+use crate::runtime::forester::Forester;
+use crate::runtime::{RuntimeError, TickResult};
+use crate::tests::{fb, turn_on_logs};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+struct Count(Arc<AtomicUsize>);
+
+// the tree writes the blackboard on every tick
+impl Impl for Count {
+    fn tick(&self, _args: RtArgs, ctx: TreeContextRef) -> Tick {
+        let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+        ctx.bb()
+            .lock()?
+            .put("count".to_string(), RtValue::int(count as i64))?;
+        Ok(TickResult::success())
+    }
+}
+
+struct UntilTick;
+
+impl Impl for UntilTick {
+    fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+        let tick = args.find("tick".to_string()).and_then(RtValue::as_int);
+        if ctx.current_tick() as i64 >= tick.unwrap_or(0) {
+            Ok(TickResult::success())
+        } else {
+            Ok(TickResult::running())
+        }
+    }
+}
+
+struct UntilKey;
+
+impl Impl for UntilKey {
+    fn tick(&self, args: RtArgs, ctx: TreeContextRef) -> Tick {
+        let key = args.find("key".to_string()).and_then(RtValue::as_string);
+        if ctx.bb().lock()?.contains(key.unwrap_or_default())? {
+            Ok(TickResult::success())
+        } else {
+            Ok(TickResult::running())
+        }
+    }
+}
+
+struct Slow;
+
+impl ImplAsync for Slow {
+    fn tick(&self, args: RtArgs, _ctx: TreeContextRef) -> Tick {
+        let millis = args.find("millis".to_string()).and_then(RtValue::as_int);
+        thread::sleep(Duration::from_millis(millis.unwrap_or(0) as u64));
+        Ok(TickResult::success())
+    }
+}
+
+fn builder(tree: &str, mode: TickMode) -> (ForesterBuilder, Arc<AtomicUsize>) {
+    turn_on_logs();
+    let mut fb = fb("schedule");
+    fb.main_tree(tree.to_string());
+    fb.tick_mode(mode);
+    let ticks = Arc::new(AtomicUsize::new(0));
+    fb.register_sync_action("count", Count(ticks.clone()));
+    fb.register_sync_action("until_tick", UntilTick);
+    fb.register_sync_action("until_key", UntilKey);
+    fb.register_async_action("slow", Slow);
+    (fb, ticks)
+}
+
+fn forester(tree: &str, mode: TickMode) -> (Forester, Arc<AtomicUsize>) {
+    let (fb, ticks) = builder(tree, mode);
+    (fb.build().unwrap(), ticks)
+}
+
+#[test]
+fn rate() {
+    let (mut f, ticks) = forester("ticks", TickMode::Rate(50.0));
+    let start = Instant::now();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+    assert_eq!(ticks.load(Ordering::SeqCst), 5);
+    // 4 periods of 20 millis between 5 ticks
+    assert!(start.elapsed() >= Duration::from_millis(80));
+}
+
+#[test]
+fn wrong_rate() {
+    for hz in [0.0, -1.0, f64::NAN] {
+        let (fb, ticks) = builder("ticks", TickMode::Rate(hz));
+        assert!(matches!(fb.build(), Err(RuntimeError::WrongArgument(_))));
+        assert_eq!(ticks.load(Ordering::SeqCst), 0);
+    }
+}
+
+#[test]
+fn on_task_finished() {
+    let (mut f, ticks) = forester("task", TickMode::OnTaskFinished);
+    assert_eq!(f.run(), Ok(TickResult::success()));
+    // the tick starting the task and the one picking up the result
+    assert_eq!(ticks.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn on_change_bb() {
+    let (mut f, ticks) = forester("key", TickMode::OnChange);
+    let bb = f.bb.clone();
+    let writer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        bb.lock()
+            .unwrap()
+            .put("flag".to_string(), RtValue::Bool(true))
+            .unwrap();
+    });
+    assert_eq!(f.run(), Ok(TickResult::success()));
+    writer.join().unwrap();
+    assert_eq!(ticks.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn on_change_own_writes() {
+    // the tree writes the blackboard on every tick but nothing else happens,
+    // thus every tick waits for the max wait
+    let (mut fb, ticks) = builder("ticks", TickMode::OnChange);
+    fb.max_tick_wait(Duration::from_millis(50));
+    let mut f = fb.build().unwrap();
+    let start = Instant::now();
+    assert_eq!(f.run(), Ok(TickResult::success()));
+    assert_eq!(ticks.load(Ordering::SeqCst), 5);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn on_change_wakeup() {
+    let (mut f, ticks) = forester("ticks", TickMode::OnChange);
+    let signal = f.signal().unwrap();
+    let done = Arc::new(AtomicBool::new(false));
+    let waker = {
+        let done = done.clone();
+        thread::spawn(move || {
+            while !done.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(10));
+                signal.wake();
+            }
+        })
+    };
+    assert_eq!(f.run(), Ok(TickResult::success()));
+    done.store(true, Ordering::SeqCst);
+    waker.join().unwrap();
+    assert_eq!(ticks.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn tick_once() {
+    let (mut f, ticks) = forester("ticks", TickMode::Eager);
+    for _ in 1..5 {
+        assert_eq!(f.tick_once(), Ok(TickResult::running()));
+    }
+    assert_eq!(f.tick_once(), Ok(TickResult::success()));
+    assert_eq!(ticks.load(Ordering::SeqCst), 5);
+
+    // the finished execution starts from the beginning
+    assert_eq!(f.tick_once(), Ok(TickResult::running()));
+    assert_eq!(ticks.load(Ordering::SeqCst), 6);
+}
+
+struct Idle;
+
+impl DaemonFn for Idle {
+    fn perform(&mut self, _ctx: DaemonContext, signal: StopFlag) {
+        while !signal.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+#[test]
+fn tick_once_keeps_daemons() {
+    turn_on_logs();
+    let mut fb = fb("schedule");
+    fb.main_tree("ticks".to_string());
+    fb.register_sync_action("count", Count(Arc::new(AtomicUsize::new(0))));
+    fb.register_sync_action("until_tick", UntilTick);
+    fb.register_named_daemon("idle".to_string(), Daemon::sync(Idle));
+    let mut f = fb.build().unwrap();
+    let daemon = "idle".to_string();
+
+    // two executions in a row
+    for _ in 0..2 {
+        while f.tick_once() == Ok(TickResult::running()) {}
+        assert_eq!(f.env.lock().unwrap().daemon_is_running(&daemon), Ok(true));
+    }
+
+    f.stop().unwrap();
+    assert!(f.env.lock().unwrap().daemon_is_running(&daemon).is_err());
+}
@@ -0,0 +1,113 @@
+use crate::runtime::RuntimeError;
+use crate::tests::{fb, test_folder};
+use crate::tree::project::typecheck::check;
+use crate::tree::project::Project;
+use crate::tree::TreeError;
+
+fn errors(folder: &str) -> Vec<String> {
+    let project = Project::build("main.tree".to_string(), test_folder(folder)).unwrap();
+    check(&project)
+        .iter()
+        .map(|e| e.diagnostic().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn all_errors() {
+    assert_eq!(
+        errors("typecheck/errors"),
+        vec![
+            "main.tree:8:5: the tree 'ho' does not have the parameter 'task' of the type tree to invoke",
+            "main.tree:15:9: the parameter 'text:string' of 'say' expects string but got 'counter' of the type num; note: 'counter' is written as num by 'forward' (main.tree:14:13)",
+            "main.tree:16:5: the call say expects 1 arguments but got 2",
+            "main.tree:17:11: the operands of 'counter == \"many\"' have different types num and string",
+            "main.tree:18:12: the parameter 'text:string' of 'say' expects string but got '1'",
+            "main.tree:19:8: the parameter 'op:tree' of 'ho' expects a tree but got '&counter'",
+            "main.tree:20:11: the decorator retry expects a number or a pointer but got 'often'",
+            "main.tree:21:5: the decorator inverter does not have arguments",
+            "main.tree:22:13: the parameter 'res:any' of 'forward' expects a pointer to bb to write to but got '1'",
+            "main.tree:23:5: the call absent can not be found",
+        ]
+    );
+}
+
+#[test]
+fn valid_projects() {
+    for folder in ["ho_tree", "params/out", "actions/check", "import/path"] {
+        assert_eq!(errors(folder), Vec::<String>::new(), "{folder}");
+    }
+}
+
+#[test]
+fn before_build() {
+    match fb("typecheck/errors").build() {
+        Err(RuntimeError::CompileError(TreeError::Multiple(errors))) => {
+            assert_eq!(errors.len(), 10)
+        }
+        Err(e) => panic!("all errors are expected but got {e:?}"),
+        Ok(_) => panic!("the errors are expected"),
+    }
+}
+
+#[test]
+fn schema_errors() {
+    assert_eq!(
+        errors("typecheck/schema"),
+        vec![
+            "main.tree:9:9: the parameter 'values:array<num>' of 'sum' expects array<num> but the element 1 of array<num> is wrong: '\"2\"' is not num",
+            "main.tree:10:12: the parameter 'path:Path' of 'follow' expects Path but the field 'points' of Path is absent",
+            "main.tree:11:12: the parameter 'path:Path' of 'follow' expects Path but the field 'speed' is not declared in Path",
+            "main.tree:12:12: the parameter 'path:Path' of 'follow' expects Path but the field 'points' of Path is wrong: the element 0 of array<Point> is wrong: the field 'y' of Point is absent",
+        ]
+    );
+}
+
+#[test]
+fn schema_unknown_types() {
+    let project = Project::build(
+        "main.tree".to_string(),
+        test_folder("typecheck/schema_unknown"),
+    );
+    match project {
+        Err(TreeError::Multiple(errors)) => assert_eq!(
+            errors
+                .iter()
+                .map(|e| e.diagnostic().unwrap().to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "main.tree:6:1: the type Goal can not be found",
+                "main.tree:1:1: the struct Node refers to itself",
+            ]
+        ),
+        other => panic!("the errors are expected but got {other:?}"),
+    }
+}
+
+#[test]
+fn default_errors() {
+    assert_eq!(
+        errors("typecheck/defaults"),
+        vec![
+            "main.tree:1:1: the parameter 'speed' of 'go' has the wrong default value: '\"fast\"' is not num",
+            "main.tree:2:1: the parameter 'res' of 'fetch' can not have a default value",
+            "main.tree:3:1: the parameter 'task' of 'wrap' can not have a default value",
+            "main.tree:8:5: the call go misses the argument for the required parameter 'key:string'",
+            "main.tree:9:5: the call go misses the argument for the required parameter 'key:string'",
+            "main.tree:10:5: the call go expects 3 arguments but got 4",
+        ]
+    );
+}
+
+#[test]
+fn enum_errors() {
+    assert_eq!(
+        errors("typecheck/enums"),
+        vec![
+            "main.tree:10:19: the parameter 'speed:num' of 'drive' expects num but got '\"fast\"'",
+            "main.tree:11:11: the parameter 'mode:Mode' of 'drive' expects Mode but 'fast' is not a variant of Mode",
+            "main.tree:12:11: the enum Mode does not have the variant turbo",
+            "main.tree:13:11: the enum Gear can not be found",
+            "main.tree:14:11: the operands of '\"fast\" > 1' have different types string and num",
+        ]
+    );
+}
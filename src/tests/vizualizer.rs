@@ -19,3 +19,12 @@ fn manual() {
     let r = Visualizer::rt_tree_svg_to_file(&tree, graph).unwrap();
     assert!(r.is_empty());
 }
+
+#[test]
+fn out_params() {
+    let project = Project::build("main.tree".to_string(), test_folder("params/out")).unwrap();
+    let tree = RuntimeTree::build(project).unwrap().tree;
+    let dot = Visualizer::dot(&tree).unwrap();
+    assert!(dot.contains("fetch (url=first,result->&response,attempts<->&counter)"));
+    assert!(dot.contains("fetch_twice (res->&response)"));
+}
@@ -1,19 +1,26 @@
 use crate::runtime::RuntimeError;
+use crate::tree::diagnostic::{Diagnostic, Location, Span};
+use itertools::Itertools;
 use parsit::error::ParseError;
+use std::fmt::{Display, Formatter};
 
+pub mod diagnostic;
 pub mod parser;
+pub mod printer;
 pub mod project;
 
 pub fn cerr(v: String) -> TreeError {
-    TreeError::CompileError(v)
+    TreeError::CompileError(Diagnostic::new(v).into())
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TreeError {
-    ParseError(String),
-    CompileError(String),
+    ParseError(Box<Diagnostic>),
+    CompileError(Box<Diagnostic>),
     VisualizationError(String),
     IOError(String),
+    /// several errors found at once like the errors of the type checking
+    Multiple(Vec<TreeError>),
 }
 
 impl TreeError {
@@ -21,18 +28,111 @@ impl TreeError {
     where
         F: Fn(&String) -> String,
     {
+        self.modify_with(&f)
+    }
+
+    fn modify_with(&self, f: &dyn Fn(&String) -> String) -> Self {
         match self {
-            TreeError::ParseError(s) => TreeError::ParseError(f(s)),
-            TreeError::CompileError(s) => TreeError::CompileError(f(s)),
+            TreeError::ParseError(d) => TreeError::ParseError(Box::new(Diagnostic {
+                message: f(&d.message),
+                ..*d.clone()
+            })),
+            TreeError::CompileError(d) => TreeError::CompileError(Box::new(Diagnostic {
+                message: f(&d.message),
+                ..*d.clone()
+            })),
             TreeError::VisualizationError(s) => TreeError::VisualizationError(f(s)),
             TreeError::IOError(s) => TreeError::IOError(f(s)),
+            TreeError::Multiple(errors) => {
+                TreeError::Multiple(errors.iter().map(|e| e.modify_with(f)).collect())
+            }
+        }
+    }
+
+    /// combines the errors into one error if there are any
+    pub fn from_errors(mut errors: Vec<TreeError>) -> Option<TreeError> {
+        match errors.len() {
+            0 => None,
+            1 => errors.pop(),
+            _ => Some(TreeError::Multiple(errors)),
+        }
+    }
+
+    /// the diagnostic for the parsing and compilation errors
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            TreeError::ParseError(d) | TreeError::CompileError(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    fn map_diagnostic<F>(self, f: F) -> Self
+    where
+        F: FnOnce(Diagnostic) -> Diagnostic,
+    {
+        match self {
+            TreeError::ParseError(d) => TreeError::ParseError(Box::new(f(*d))),
+            TreeError::CompileError(d) => TreeError::CompileError(Box::new(f(*d))),
+            other => other,
+        }
+    }
+
+    /// attaches the file to the error unless the error already points somewhere
+    pub fn in_file(self, file: &str) -> Self {
+        self.map_diagnostic(|d| d.in_file(file))
+    }
+
+    /// attaches the span to the error unless the error already points somewhere
+    pub fn with_span(self, span: Span) -> Self {
+        self.map_diagnostic(|d| d.with_span(span))
+    }
+
+    /// attaches the place to the error unless the error already points somewhere.
+    /// The text of the file is used to calculate the lines and columns.
+    pub fn locate(self, file: &str, span: Span, src: &str) -> Self {
+        self.map_diagnostic(|d| {
+            if d.location.file.is_none() {
+                d.in_file(file).with_span(span).resolve(src)
+            } else {
+                d
+            }
+        })
+    }
+
+    /// calculates the lines and columns of the diagnostic using the text of the file
+    pub fn resolve(self, src: &str) -> Self {
+        self.map_diagnostic(|d| d.resolve(src))
+    }
+
+    pub fn with_note(self, message: String, location: Location) -> Self {
+        self.map_diagnostic(|d| d.with_note(message, location))
+    }
+}
+
+impl Display for TreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeError::ParseError(d) => write!(f, "parse error: {d}"),
+            TreeError::CompileError(d) => write!(f, "compile error: {d}"),
+            TreeError::VisualizationError(s) => write!(f, "visualization error: {s}"),
+            TreeError::IOError(s) => write!(f, "io error: {s}"),
+            TreeError::Multiple(errors) => {
+                write!(f, "{}", errors.iter().map(ToString::to_string).join("\n"))
+            }
         }
     }
 }
 
 impl From<ParseError<'_>> for TreeError {
     fn from(value: ParseError) -> Self {
-        TreeError::ParseError(value.to_string())
+        match value {
+            ParseError::BadToken(t, range) => TreeError::ParseError(
+                Diagnostic::new(format!("the token `{t}` is not recognized"))
+                    .with_span(Span::new(range.start, range.end))
+                    .into(),
+            ),
+            e => TreeError::ParseError(Diagnostic::new(e.to_string()).into()),
+        }
     }
 }
 impl From<RuntimeError> for TreeError {
@@ -0,0 +1,284 @@
+use crate::tree::project::FileName;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+/// The byte range of a syntax element in the source text.
+///
+/// # Note
+/// The span is a piece of meta information, thus it does not take part in comparing or hashing
+/// of the ast elements: two calls written in different places are still the same call.
+#[derive(Debug, Clone, Copy, Default, Eq, Deserialize, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl PartialEq for Span {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Hash for Span {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+    /// the span covering both spans
+    pub fn join(&self, other: &Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+    /// the span is not taken from the source (created in code or absent)
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// A position in the text. Both the line and the column start from 1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl LineCol {
+    /// calculates the line and column for the byte offset in the text
+    pub fn from_offset(src: &str, offset: usize) -> Self {
+        let offset = offset.min(src.len());
+        let before = &src[..offset];
+        let line = before.matches('\n').count() + 1;
+        let col = before
+            .rfind('\n')
+            .map(|idx| before[idx + 1..].chars().count())
+            .unwrap_or(before.chars().count())
+            + 1;
+        LineCol { line, col }
+    }
+}
+
+impl Display for LineCol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+/// The place in the project the diagnostic points at.
+/// The line and column range along with the text of the line
+/// get calculated when the source text is known.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Location {
+    pub file: Option<FileName>,
+    pub span: Option<Span>,
+    pub range: Option<(LineCol, LineCol)>,
+    pub line_text: Option<String>,
+}
+
+impl Location {
+    pub fn new(file: Option<FileName>, span: Option<Span>) -> Self {
+        Location {
+            file,
+            span,
+            range: None,
+            line_text: None,
+        }
+    }
+
+    /// fills the line and column range in if the span is known
+    pub fn resolve(mut self, src: &str) -> Self {
+        if let Some(span) = self.span {
+            let start = LineCol::from_offset(src, span.start);
+            self.range = Some((start, LineCol::from_offset(src, span.end)));
+            self.line_text = src.lines().nth(start.line - 1).map(ToString::to_string);
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.file.is_none() && self.span.is_none()
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, &self.range, &self.span) {
+            (Some(file), Some((start, _)), _) => write!(f, "{file}:{start}"),
+            (Some(file), None, _) => write!(f, "{file}"),
+            (None, Some((start, _)), _) => write!(f, "{start}"),
+            (None, None, Some(span)) => write!(f, "[{}..{}]", span.start, span.end),
+            (None, None, None) => Ok(()),
+        }
+    }
+}
+
+/// The secondary message attached to a diagnostic like 'the first definition is here'.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note {
+    pub message: String,
+    pub location: Location,
+}
+
+/// The structured error of the tree compilation.
+/// It keeps the primary message and the place where the error occurs
+/// along with the secondary notes.
+///
+/// # Example
+/// The rendered diagnostic looks like that:
+/// ```text
+/// error: the tree 'a' is already presented
+///  --> main.tree:3:1
+///   |
+/// 3 | sequence a { b() }
+///   | ^^^^^^^^^^^^^^^^^^
+///   = note: the first definition of 'a' (main.tree:1:1)
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub location: Location,
+    pub notes: Vec<Note>,
+}
+
+impl Diagnostic {
+    pub fn new(message: String) -> Self {
+        Diagnostic {
+            message,
+            location: Location::default(),
+            notes: vec![],
+        }
+    }
+
+    /// sets the file if it is not set yet
+    pub fn in_file(mut self, file: &str) -> Self {
+        if self.location.file.is_none() {
+            self.location.file = Some(file.to_string());
+        }
+        self
+    }
+    /// sets the span if it is not set yet
+    pub fn with_span(mut self, span: Span) -> Self {
+        if self.location.span.is_none() && !span.is_empty() {
+            self.location.span = Some(span);
+        }
+        self
+    }
+
+    pub fn with_note(mut self, message: String, location: Location) -> Self {
+        self.notes.push(Note { message, location });
+        self
+    }
+
+    /// calculates the line and column range using the text of the file
+    pub fn resolve(mut self, src: &str) -> Self {
+        if self.location.range.is_none() {
+            self.location = self.location.resolve(src);
+        }
+        self
+    }
+
+    /// Renders the diagnostic with the snippet of the source underlined with carets.
+    /// The snippet is available only when the diagnostic has been resolved against the source text.
+    pub fn render(&self) -> String {
+        let location = &self.location;
+        let mut out = format!("error: {}\n", self.message);
+        if !location.is_empty() {
+            out.push_str(format!(" --> {}\n", location).as_str());
+        }
+        if let (Some((start, end)), Some(line_text)) = (location.range, &location.line_text) {
+            let line_no = start.line.to_string();
+            let pad = " ".repeat(line_no.len());
+            let line_len = line_text.chars().count();
+            let end_col = if end.line == start.line {
+                end.col.min(line_len + 1)
+            } else {
+                line_len + 1
+            };
+            let width = end_col.saturating_sub(start.col).max(1);
+            out.push_str(format!("{pad} |\n").as_str());
+            out.push_str(format!("{line_no} | {line_text}\n").as_str());
+            out.push_str(
+                format!(
+                    "{pad} | {}{}\n",
+                    " ".repeat(start.col - 1),
+                    "^".repeat(width)
+                )
+                .as_str(),
+            );
+        }
+        for note in self.notes.iter() {
+            if note.location.is_empty() {
+                out.push_str(format!("  = note: {}\n", note.message).as_str());
+            } else {
+                out.push_str(format!("  = note: {} ({})\n", note.message, note.location).as_str());
+            }
+        }
+        out
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.location.is_empty() {
+            write!(f, "{}", self.message)?;
+        } else {
+            write!(f, "{}: {}", self.location, self.message)?;
+        }
+        for note in self.notes.iter() {
+            if note.location.is_empty() {
+                write!(f, "; note: {}", note.message)?;
+            } else {
+                write!(f, "; note: {} ({})", note.message, note.location)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<String> for Diagnostic {
+    fn from(value: String) -> Self {
+        Diagnostic::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::diagnostic::{Diagnostic, LineCol, Location, Span};
+
+    #[test]
+    fn line_col() {
+        let src = "import \"a\"\nroot main {\n  call()\n}";
+        assert_eq!(LineCol::from_offset(src, 0), LineCol { line: 1, col: 1 });
+        assert_eq!(LineCol::from_offset(src, 11), LineCol { line: 2, col: 1 });
+        assert_eq!(LineCol::from_offset(src, 25), LineCol { line: 3, col: 3 });
+    }
+
+    #[test]
+    fn render() {
+        let src = "root main {\n  call(a)\n}";
+        let d = Diagnostic::new("the call is not found".to_string())
+            .in_file("main.tree")
+            .with_span(Span::new(14, 21))
+            .with_note(
+                "the tree is defined here".to_string(),
+                Location::new(Some("main.tree".to_string()), None),
+            );
+
+        let d = d.resolve(src);
+        assert_eq!(
+            d.render(),
+            "error: the call is not found\n \
+            --> main.tree:2:3\n  \
+            |\n\
+            2 |   call(a)\n  \
+            |   ^^^^^^^\n  \
+            = note: the tree is defined here (main.tree)\n"
+        );
+        assert_eq!(
+            d.to_string(),
+            "main.tree:2:3: the call is not found; note: the tree is defined here (main.tree)"
+        );
+    }
+}
@@ -1,13 +1,16 @@
 pub mod ast;
-mod lexer;
+pub(crate) mod lexer;
 mod tests;
 
+use crate::tree::diagnostic::{Diagnostic, Span};
 use crate::tree::parser::ast::*;
 use crate::tree::parser::lexer::Token;
 use crate::tree::TreeError;
-use ast::arg::{Argument, ArgumentRhs, Arguments, MesType, Param, Params};
+use ast::arg::{Argument, ArgumentRhs, Arguments, Field, MesType, Param, ParamDir, Params};
 use ast::call::{Call, Calls};
+use ast::expr::{BinOp, Expr, TemplatePart};
 use ast::message::{Bool, Message, Number, StringLit};
+use logos::Logos;
 use parsit::error::ParseError;
 use parsit::parser::{EmptyToken, Parsit};
 use parsit::step::Step;
@@ -22,7 +25,7 @@ use std::str::FromStr;
 /// // built in library        
 /// import "std:actions"
 ///
-/// r_fallback retry_with_delay(delay:num, attempts:num,trg:tree){
+/// r_fallback retry_with_delay(trg:tree, delay:num = 1000, attempts:num = 10){
 ///     retry(attempts) fallback {
 ///         trg(..)
 ///         wait(delay)
@@ -30,10 +33,15 @@ use std::str::FromStr;
 ///     }
 ///     wait(delay)
 /// }
+///
+/// root main retry_with_delay(move_to(charger_station), attempts = 3)
 /// ```
 
 pub struct Parser<'a> {
     inner: Parsit<'a, Token>,
+    src: &'a str,
+    // the byte ranges of the tokens in the same order as the tokens are parsed
+    spans: Vec<Span>,
 }
 
 impl<'a> Parser<'a> {
@@ -81,6 +89,13 @@ impl<'a> Parser<'a> {
     fn id(&self, pos: usize) -> Step<'a, Key> {
         token!(self.token(pos) => Token::Id(v) => v.clone() )
     }
+    /// the id that can be qualified like `Mode::auto` or `nav::approach`
+    fn path(&self, pos: usize) -> Step<'a, Key> {
+        let segment = |p| token!(self.token(p) => Token::ColonColon).then(|p| self.id(p));
+        self.id(pos).then_multi_combine(segment, |head, tail| {
+            tail.into_iter().fold(head, |acc, s| format!("{acc}::{s}"))
+        })
+    }
     fn str(&self, pos: usize) -> Step<'a, StringLit> {
         token!(self.token(pos) => Token::StringLit(v) => StringLit(v.clone()) )
     }
@@ -122,16 +137,42 @@ impl<'a> Parser<'a> {
 
         wrap!(pos => l; elems or def; r)
     }
-    fn params(&self, pos: usize) -> Step<'a, Params> {
+    fn param_dir(&self, pos: usize) -> Step<'a, ParamDir> {
+        self.id(pos)
+            .flat_map(|p| ParamDir::from_str(&p), |_pe| Step::Fail(pos))
+    }
+
+    fn params(&'a self, pos: usize) -> Step<'a, Params> {
         let l = |p| self.l_pr(p);
         let r = |p| self.r_pr(p);
         let comma = |p| self.comma(p);
 
-        let param = |p| {
+        let default = |p| self.assign(p).then(|p| self.message(p)).or_none();
+        let typed = |p| {
             self.id(p)
+                .then_or_none_zip(|p| token!(self.token(p) => Token::Question).or_none())
                 .then_skip(|p| self.colon(p))
                 .then_zip(|p| self.mes_type(p))
-                .map(|(name, tpe)| Param { name, tpe })
+                .then_or_none_zip(default)
+                .map(|(((name, optional), tpe), default)| {
+                    let param = Param {
+                        optional: optional.is_some(),
+                        ..Param::new(&name, tpe)
+                    };
+                    match default {
+                        Some(m) => param.with_default(m),
+                        None => param,
+                    }
+                })
+        };
+        // the direction is optional and a param can be named like a direction
+        let param = |p| -> Step<Param> {
+            self.param_dir(p)
+                .then_zip(typed)
+                .map(|(dir, param)| param.with_dir(dir))
+                .or_from(p)
+                .or(typed)
+                .into()
         };
 
         let elems = |p| seq!(p => param, comma,).map(|params| Params { params });
@@ -140,47 +181,252 @@ impl<'a> Parser<'a> {
         wrap!(pos => l; elems or def; r)
     }
 
+    fn bin_op(&self, pos: usize, ops: &[BinOp]) -> Step<'a, BinOp> {
+        let op = token!(self.token(pos) =>
+                Token::Or => BinOp::Or,
+                Token::And => BinOp::And,
+                Token::Eq => BinOp::Eq,
+                Token::Ne => BinOp::Ne,
+                Token::Lt => BinOp::Lt,
+                Token::Le => BinOp::Le,
+                Token::Gt => BinOp::Gt,
+                Token::Ge => BinOp::Ge,
+                Token::Plus => BinOp::Add,
+                Token::Minus => BinOp::Sub,
+                Token::Star => BinOp::Mul,
+                Token::Slash => BinOp::Div,
+                Token::Percent => BinOp::Mod
+        );
+        match op {
+            Step::Success(op, _) if !ops.contains(&op) => Step::Fail(pos),
+            other => other,
+        }
+    }
+
+    fn expr_atom(&'a self, pos: usize) -> Step<'a, Expr> {
+        let l = |p| self.l_pr(p);
+        let r = |p| self.r_pr(p);
+        let nested = |p| self.expr(p);
+        let not = |p| {
+            token!(self.token(p) => Token::Not)
+                .then(|p| self.expr_atom(p))
+                .map(Expr::negate)
+        };
+
+        not(pos)
+            .or_from(pos)
+            .or(|p| wrap!(p => l; nested; r))
+            .or(|p| self.template(p))
+            .or(|p| self.message(p).map(Expr::Mes))
+            .or(|p| self.path(p).map(Expr::Id))
+            .into()
+    }
+
+    /// the template string like `$"/robot_{id}/cmd"` where the expressions are in the braces
+    /// and the braces themselves are doubled.
+    fn template(&self, pos: usize) -> Step<'a, Expr> {
+        let parts = |text: &String| -> Result<Vec<TemplatePart>, ()> {
+            let mut parts = vec![];
+            let mut chars = text.chars().peekable();
+            let mut curr = String::new();
+            while let Some(c) = chars.next() {
+                match (c, chars.peek()) {
+                    ('{', Some('{')) | ('}', Some('}')) => {
+                        chars.next();
+                        curr.push(c);
+                    }
+                    ('{', _) => {
+                        let mut src = String::new();
+                        loop {
+                            match chars.next() {
+                                Some('}') => break,
+                                Some(c) => src.push(c),
+                                None => return Err(()),
+                            }
+                        }
+                        let parser = Parser::new(src.as_str()).map_err(|_| ())?;
+                        let expr = match parser.inner.validate_eof(parser.expr(0)) {
+                            Step::Success(e, _) => e,
+                            _ => return Err(()),
+                        };
+                        if !curr.is_empty() {
+                            parts.push(TemplatePart::Text(std::mem::take(&mut curr)));
+                        }
+                        parts.push(TemplatePart::Expr(expr));
+                    }
+                    ('}', _) => return Err(()),
+                    _ => curr.push(c),
+                }
+            }
+            if !curr.is_empty() {
+                parts.push(TemplatePart::Text(curr));
+            }
+            Ok(parts)
+        };
+        token!(self.token(pos) => Token::Template(t) => t.clone())
+            .flat_map(|t| parts(&t).map(Expr::Template), |_| Step::Fail(pos))
+    }
+
+    /// the negative number right after the operand like `a -1` that is lexed as a number
+    fn neg_number(&self, pos: usize) -> Step<'a, Expr> {
+        let neg = |n: &Number| match n {
+            Number::Int(v) if *v < 0 => Some(Number::Int(-v)),
+            Number::Float(v) if *v < 0.0 => Some(Number::Float(-v)),
+            Number::Hex(v) if *v < 0 => Some(Number::Hex(-v)),
+            _ => None,
+        };
+        match self.num(pos) {
+            Step::Success(n, p) => match neg(&n) {
+                Some(n) => Step::Success(Expr::Mes(Message::Num(n)), p),
+                None => Step::Fail(pos),
+            },
+            other => other.map(|n| Expr::Mes(Message::Num(n))),
+        }
+    }
+
+    fn expr_mul(&'a self, pos: usize) -> Step<'a, Expr> {
+        let ops = [BinOp::Mul, BinOp::Div, BinOp::Mod];
+        self.expr_atom(pos).then_multi_combine(
+            |p| self.bin_op(p, &ops).then_zip(|p| self.expr_atom(p)),
+            |lhs, rest| {
+                rest.into_iter()
+                    .fold(lhs, |lhs, (op, rhs)| Expr::binary(lhs, op, rhs))
+            },
+        )
+    }
+
+    fn expr_add(&'a self, pos: usize) -> Step<'a, Expr> {
+        let ops = [BinOp::Mul, BinOp::Div, BinOp::Mod];
+        let fold = |lhs, rest: Vec<(BinOp, Expr)>| {
+            rest.into_iter()
+                .fold(lhs, |lhs, (op, rhs)| Expr::binary(lhs, op, rhs))
+        };
+        // `a -1 * 2` is `a - 1 * 2`
+        let neg = |p| {
+            self.neg_number(p)
+                .then_multi_combine(
+                    |p| self.bin_op(p, &ops).then_zip(|p| self.expr_atom(p)),
+                    fold,
+                )
+                .map(|rhs| (BinOp::Sub, rhs))
+        };
+        let op = |p| -> Step<(BinOp, Expr)> {
+            self.bin_op(p, &[BinOp::Add, BinOp::Sub])
+                .then_zip(|p| self.expr_mul(p))
+                .or_from(p)
+                .or(neg)
+                .into()
+        };
+        self.expr_mul(pos).then_multi_combine(op, fold)
+    }
+
+    fn expr_cmp(&'a self, pos: usize) -> Step<'a, Expr> {
+        let ops = [
+            BinOp::Eq,
+            BinOp::Ne,
+            BinOp::Lt,
+            BinOp::Le,
+            BinOp::Gt,
+            BinOp::Ge,
+        ];
+        self.expr_add(pos)
+            .then_or_none_zip(|p| {
+                self.bin_op(p, &ops)
+                    .then_zip(|p| self.expr_add(p))
+                    .or_none()
+            })
+            .map(|(lhs, rhs)| match rhs {
+                None => lhs,
+                Some((op, rhs)) => Expr::binary(lhs, op, rhs),
+            })
+    }
+
+    fn expr_and(&'a self, pos: usize) -> Step<'a, Expr> {
+        self.expr_cmp(pos).then_multi_combine(
+            |p| self.bin_op(p, &[BinOp::And]).then_zip(|p| self.expr_cmp(p)),
+            |lhs, rest| {
+                rest.into_iter()
+                    .fold(lhs, |lhs, (op, rhs)| Expr::binary(lhs, op, rhs))
+            },
+        )
+    }
+
+    /// the expression ordered by priority: `||` then `&&` then comparisons,
+    /// `+ -`, `* / %` and then `!`
+    fn expr(&'a self, pos: usize) -> Step<'a, Expr> {
+        self.expr_and(pos).then_multi_combine(
+            |p| self.bin_op(p, &[BinOp::Or]).then_zip(|p| self.expr_and(p)),
+            |lhs, rest| {
+                rest.into_iter()
+                    .fold(lhs, |lhs, (op, rhs)| Expr::binary(lhs, op, rhs))
+            },
+        )
+    }
+
+    /// the expression as an argument should not be a single id or message
+    /// since they are the arguments on their own
+    fn arg_expr(&'a self, pos: usize) -> Step<'a, Expr> {
+        match self.expr(pos) {
+            Step::Success(e, _) if e.is_atom() => Step::Fail(pos),
+            other => other,
+        }
+    }
+
     fn arg(&'a self, pos: usize) -> Step<'a, Argument> {
         let assign = |p| self.assign(p);
         let assigned = |p| self.id(p).then_skip(assign);
 
         let assign_id = |p| {
             assigned(p)
-                .then_zip(|p| self.id(p).map(ArgumentRhs::Id))
-                .map(|(a, b)| Argument::Assigned(a, b))
+                .then_zip(|p| self.path(p).map(ArgumentRhs::Id))
+                .map(|(a, b)| Argument::assigned(a, b))
         };
 
         let assign_mes = |p| {
             assigned(p)
                 .then_zip(|p| self.message(p).map(ArgumentRhs::Mes))
-                .map(|(a, b)| Argument::Assigned(a, b))
+                .map(|(a, b)| Argument::assigned(a, b))
         };
         let assign_call = |p| {
             assigned(p)
                 .then_zip(|p| self.call(p).map(ArgumentRhs::Call))
-                .map(|(a, b)| Argument::Assigned(a, b))
+                .map(|(a, b)| Argument::assigned(a, b))
+        };
+
+        let assign_expr = |p| {
+            assigned(p)
+                .then_zip(|p| self.arg_expr(p).map(ArgumentRhs::Expr))
+                .map(|(a, b)| Argument::assigned(a, b))
+        };
+        let expr = |p| {
+            self.arg_expr(p)
+                .map(ArgumentRhs::Expr)
+                .map(Argument::unassigned)
         };
 
         let mes = |p| {
             self.message(p)
                 .map(ArgumentRhs::Mes)
-                .map(Argument::Unassigned)
+                .map(Argument::unassigned)
         };
-        let id = |p| self.id(p).map(ArgumentRhs::Id).map(Argument::Unassigned);
+        let id = |p| self.path(p).map(ArgumentRhs::Id).map(Argument::unassigned);
         let call = |p| {
             self.call(p)
                 .map(ArgumentRhs::Call)
-                .map(Argument::Unassigned)
+                .map(Argument::unassigned)
         };
 
-        assign_mes(pos)
+        let arg: Step<Argument> = assign_expr(pos)
             .or_from(pos)
+            .or(expr)
+            .or(assign_mes)
             .or(assign_call)
             .or(assign_id)
             .or(call)
             .or(mes)
             .or(id)
-            .into()
+            .into();
+        self.spanned(pos, arg).map(|(a, span)| a.with_span(span))
     }
 
     fn args(&'a self, pos: usize) -> Step<'a, Arguments> {
@@ -202,7 +448,15 @@ impl<'a> Parser<'a> {
     }
 
     fn mes_type(&self, pos: usize) -> Step<'a, MesType> {
-        token!(self.token(pos) =>
+        let array_of = |p| {
+            token!(self.token(p) => Token::ArrayT)
+                .then_skip(|p| token!(self.token(p) => Token::Lt))
+                .then(|p| self.mes_type(p))
+                .then_skip(|p| token!(self.token(p) => Token::Gt))
+                .map(MesType::array_of)
+        };
+        let base = |p| {
+            token!(self.token(p) =>
                 Token::StringT => MesType::String ,
                 Token::NumT => MesType::Num,
                 Token::ArrayT => MesType::Array,
@@ -210,7 +464,13 @@ impl<'a> Parser<'a> {
                 Token::TreeT => MesType::Tree,
                 Token::ObjectT => MesType::Object,
                 Token::AnyT => MesType::Any
-        )
+            )
+        };
+        array_of(pos)
+            .or_from(pos)
+            .or(base)
+            .or(|p| self.path(p).map(MesType::Named))
+            .into()
     }
 
     fn message(&'a self, pos: usize) -> Step<'a, Message> {
@@ -233,11 +493,11 @@ impl<'a> Parser<'a> {
 
     fn call(&'a self, pos: usize) -> Step<'a, Call> {
         let inv = |p| {
-            self.id(p)
+            self.path(p)
                 .then_zip(|p| self.args(p))
-                .map(|(id, args)| Call::Invocation(id, args))
+                .map(|(id, args)| Call::invocation(&id, args))
                 .or_from(p)
-                .or(|p| self.call_partial(p).map(Call::HoInvocation))
+                .or(|p| self.call_partial(p).map(|id| Call::ho_invocation(&id)))
                 .into()
         };
 
@@ -250,12 +510,13 @@ impl<'a> Parser<'a> {
                     if t.is_decorator() {
                         Call::decorator(t, args, calls.elems[0].clone())
                     } else {
-                        Call::lambda(t, calls)
+                        Call::lambda_with(t, args, calls)
                     }
                 })
         };
 
-        anon(pos).or_from(pos).or(inv).into()
+        let call: Step<Call> = anon(pos).or_from(pos).or(inv).into();
+        self.spanned(pos, call).map(|(c, span)| c.with_span(span))
     }
     fn calls(&'a self, pos: usize) -> Step<'a, Calls> {
         let calls = |p| {
@@ -272,16 +533,13 @@ impl<'a> Parser<'a> {
     }
 
     fn tree(&'a self, pos: usize) -> Step<'a, Tree> {
-        self.tree_type(pos)
+        let tree = self
+            .tree_type(pos)
             .then_zip(|p| self.id(p))
             .then_or_default_zip(|p| self.params(p))
             .then_or_default_zip(|p| self.semi(p).map(|_| Calls::default()).or(|p| self.calls(p)))
-            .map(|(((tpe, name), params), calls)| Tree {
-                tpe,
-                name,
-                params,
-                calls,
-            })
+            .map(|(((tpe, name), params), calls)| Tree::new(tpe, name, params, calls));
+        self.spanned(pos, tree).map(|(t, span)| t.with_span(span))
     }
 
     fn import(&'a self, pos: usize) -> Step<'a, Import> {
@@ -300,87 +558,313 @@ impl<'a> Parser<'a> {
 
         let part = |p| {
             let def = vec![];
-            wrap!(p => l;names or def; r ).or_none()
+            wrap!(p => l;names or def; r )
         };
+        // the whole file under the namespace like `import "nav.tree" as nav`
+        let namespace = |p| {
+            token!(self.token(p) => Token::As)
+                .then(|p| self.id(p))
+                .map(|ns| vec![ImportName::Namespace(ns)])
+        };
+        let parts = |p| -> Step<Vec<ImportName>> { namespace(p).or_from(p).or(part).into() };
 
-        self.import_tk(pos)
+        let import = self
+            .import_tk(pos)
             .then_zip(|p| self.str(p))
             .take_right()
-            .then_or_none_zip(part)
+            .then_or_none_zip(|p| parts(p).or_none())
             .map(|(file, parts)| match parts {
                 None => Import::file(file.0.as_str()),
-                Some(names) => Import(file.0, names),
-            })
+                Some(names) => Import::names_mixed(file.0.as_str(), names),
+            });
+        self.spanned(pos, import).map(|(i, span)| i.with_span(span))
     }
 
-    fn file(&'a self, pos: usize) -> Step<'a, AstFile> {
-        let entity = |p| {
-            let entity: Step<FileEntity> = self
-                .tree(p)
-                .map(FileEntity::Tree)
-                .or_from(p)
-                .or(|p| self.import(p).map(FileEntity::Import))
-                .into();
-            entity
+    fn struct_def(&'a self, pos: usize) -> Step<'a, StructDef> {
+        let l = |p| self.l_brc(p);
+        let r = |p| self.r_brc(p);
+        let comma = |p| self.comma(p);
+        let field = |p| {
+            self.id(p)
+                .then_or_none_zip(|p| token!(self.token(p) => Token::Question).or_none())
+                .then_skip(|p| self.colon(p))
+                .then_zip(|p| self.mes_type(p))
+                .map(|((name, optional), tpe)| Field {
+                    name,
+                    tpe,
+                    optional: optional.is_some(),
+                })
         };
+        let fields = |p| seq!(p => field, comma,);
+        let no_fields = vec![];
+
+        let def = token!(self.token(pos) => Token::Struct)
+            .then(|p| self.id(p))
+            .then_zip(|p| wrap!(p => l; fields or no_fields; r))
+            .map(|(name, fields)| StructDef::new(&name, fields));
+        self.spanned(pos, def).map(|(d, span)| d.with_span(span))
+    }
+
+    fn const_def(&'a self, pos: usize) -> Step<'a, ConstDef> {
+        let def = token!(self.token(pos) => Token::Const)
+            .then(|p| self.id(p))
+            .then_skip(|p| self.assign(p))
+            .then_zip(|p| self.message(p))
+            .then_skip(|p| self.semi(p))
+            .map(|(name, value)| ConstDef::new(&name, value));
+        self.spanned(pos, def).map(|(d, span)| d.with_span(span))
+    }
+
+    fn enum_def(&'a self, pos: usize) -> Step<'a, EnumDef> {
+        let l = |p| self.l_brc(p);
+        let r = |p| self.r_brc(p);
+        let comma = |p| self.comma(p);
+        let variant = |p| self.id(p);
+        let variants = |p| seq!(p => variant, comma,);
+        let no_variants = vec![];
+
+        let def = token!(self.token(pos) => Token::Enum)
+            .then(|p| self.id(p))
+            .then_zip(|p| wrap!(p => l; variants or no_variants; r))
+            .map(|(name, variants)| EnumDef {
+                name,
+                variants,
+                span: Span::default(),
+            });
+        self.spanned(pos, def).map(|(d, span)| d.with_span(span))
+    }
+
+    fn entity(&'a self, pos: usize) -> Step<'a, FileEntity> {
+        self.tree(pos)
+            .map(FileEntity::Tree)
+            .or_from(pos)
+            .or(|p| self.import(p).map(FileEntity::Import))
+            .or(|p| self.struct_def(p).map(FileEntity::Struct))
+            .or(|p| self.const_def(p).map(FileEntity::Const))
+            .or(|p| self.enum_def(p).map(FileEntity::Enum))
+            .into()
+    }
 
-        self.inner.zero_or_more(pos, entity).map(AstFile::new)
+    fn file(&'a self, pos: usize) -> Step<'a, AstFile> {
+        self.inner
+            .zero_or_more(pos, |p| self.entity(p))
+            .map(AstFile::new)
     }
 }
 
 impl<'a> Parser<'a> {
     pub fn new(src: &'a str) -> Result<Self, TreeError> {
-        Ok(Parser {
-            inner: Parsit::new(src)?,
-        })
+        let inner = Parsit::new(src).map_err(|e| TreeError::from(e).resolve(src))?;
+        let spans = Token::lexer(src)
+            .spanned()
+            .map(|(_, r)| Span::new(r.start, r.end))
+            .collect();
+        Ok(Parser { inner, src, spans })
     }
 
     fn token(&self, pos: usize) -> Result<(&Token, usize), ParseError<'a>> {
         self.inner.token(pos)
     }
 
+    /// the span of the token on the position or the end of the text if the position is out of the stream
+    fn token_span(&self, pos: usize) -> Span {
+        self.spans
+            .get(pos)
+            .cloned()
+            .unwrap_or(Span::new(self.src.len(), self.src.len()))
+    }
+
+    /// the span covering the tokens from the position `from` till the position `to` (exclusive)
+    fn span(&self, from: usize, to: usize) -> Span {
+        if to > from {
+            self.token_span(from).join(&self.token_span(to - 1))
+        } else {
+            let start = self.token_span(from).start;
+            Span::new(start, start)
+        }
+    }
+
+    /// attaches the span of the consumed tokens to the result of the given step
+    fn spanned<T>(&self, pos: usize, step: Step<'a, T>) -> Step<'a, (T, Span)> {
+        match step {
+            Step::Success(v, end) => Step::Success((v, self.span(pos, end)), end),
+            Step::Fail(p) => Step::Fail(p),
+            Step::Error(e) => Step::Error(e),
+        }
+    }
+
+    fn error_at(&self, pos: usize, message: String) -> TreeError {
+        let span = self.token_span(pos);
+        TreeError::ParseError(
+            Diagnostic::new(message)
+                .with_span(span)
+                .resolve(self.src)
+                .into(),
+        )
+    }
+
+    fn token_text(&self, pos: usize) -> &str {
+        let span = self.token_span(pos);
+        &self.src[span.start..span.end]
+    }
+
     pub fn parse(&'a self) -> Result<AstFile, TreeError> {
         let step: Step<AstFile> = self.inner.validate_eof(self.file(0));
 
-        match step.clone() {
+        match step {
             Step::Success(file, _pos) => Ok(file),
-            Step::Fail(pos) => {
-                let env = self.inner.env(&step);
-                Err(TreeError::ParseError(format!(
-                    "Parse error on the position: {pos} with the env: `{env}`"
-                )))
-            }
-            Step::Error(ParseError::BadToken(t, _)) => {
-                let env = self.inner.env(&step);
-                Err(TreeError::ParseError(format!(
-                    "Parse error the token: `{t}` is not recognized with the env: `{env}`"
-                )))
+            Step::Fail(pos) => Err(self.unexpected(pos)),
+            Step::Error(err) => Err(self.step_error(err)),
+        }
+    }
+
+    /// Parses the text in the recovery mode.
+    /// When a definition or an import can not be parsed, the error is recorded and
+    /// the parser resynchronises at the next definition boundary
+    /// (a `;`, the closing `}` of the broken definition or the beginning of the next definition or import).
+    ///
+    /// Returns the definitions and imports that have been parsed successfully along with all errors found in the text
+    /// ordered by the position. The definition with a broken body is kept but without the body.
+    /// The tokens that can not be recognized are reported as well and skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use forester_rs::tree::parser::Parser;
+    ///
+    /// let (file, errors) = Parser::parse_recovering(
+    ///     r#"
+    ///     root main sequence { a() b() }
+    ///     sequence broken { a( }
+    ///     impl a;
+    ///     impl b;
+    ///     "#,
+    /// );
+    /// assert_eq!(file.0.len(), 4);
+    /// assert_eq!(errors.len(), 1);
+    /// ```
+    pub fn parse_recovering(src: &str) -> (AstFile, Vec<TreeError>) {
+        let mut errors = vec![];
+        // the unrecognized tokens are replaced with spaces to keep the positions of the rest of the text
+        let mut clean = src.to_string();
+        for (token, range) in Token::lexer(src).spanned() {
+            if token.is_err() {
+                errors.push(TreeError::ParseError(
+                    Diagnostic::new(format!(
+                        "the token `{}` is not recognized",
+                        &src[range.clone()]
+                    ))
+                    .with_span(Span::new(range.start, range.end))
+                    .resolve(src)
+                    .into(),
+                ));
+                clean.replace_range(range.clone(), " ".repeat(range.len()).as_str());
             }
-            Step::Error(ParseError::ExternalError(ext_t, pos)) => {
-                let env = self.inner.env(&step);
-                Err(TreeError::ParseError(format!(
-                    "Parse error the token: `{ext_t}` on the pos:`{pos}` is not recognized with the env: `{env}`"
-                )))
+        }
+
+        let inner = match Parsit::new(clean.as_str()) {
+            Ok(inner) => inner,
+            Err(e) => {
+                errors.push(TreeError::from(e).resolve(src));
+                return (AstFile::new(vec![]), errors);
             }
-            Step::Error(ParseError::FailedOnValidation(ext_t, pos)) => {
-                let env = self.inner.env(&step);
-                Err(TreeError::ParseError(format!(
-                    "Parse error on the validation `{ext_t}` on the pos:`{pos}` is not recognized with the env: `{env}`"
-                )))
+        };
+        let spans = Token::lexer(clean.as_str())
+            .spanned()
+            .map(|(_, r)| Span::new(r.start, r.end))
+            .collect();
+        let parser = Parser { inner, src, spans };
+
+        let mut entities = vec![];
+        let mut pos = 0;
+        while pos < parser.spans.len() {
+            match parser.entity(pos) {
+                Step::Success(entity, next) => {
+                    entities.push(entity);
+                    pos = next;
+                }
+                Step::Fail(p) => {
+                    errors.push(parser.unexpected(p.max(pos)));
+                    pos = parser.synchronize(pos);
+                }
+                Step::Error(err) => {
+                    errors.push(parser.step_error(err));
+                    pos = parser.synchronize(pos);
+                }
             }
-            Step::Error(ParseError::ReachedEOF(pos)) => {
-                let env = self.inner.env(&step);
-                Err(TreeError::ParseError(format!(
-                    "Parse error on the pos:`{pos}` is reached eof with the env: `{env}`"
-                )))
+        }
+        errors.sort_by_key(|e| {
+            e.diagnostic()
+                .and_then(|d| d.location.span)
+                .map(|s| s.start)
+        });
+        (AstFile::new(entities), errors)
+    }
+
+    /// finds the position to resume parsing after the broken entity starting at the position `pos`.
+    /// It is the position after `;` or after the closing `}` of the entity
+    /// or the beginning of the next definition or import whichever comes first.
+    fn synchronize(&self, pos: usize) -> usize {
+        let mut depth = 0;
+        let mut p = pos;
+        while let Ok((token, _)) = self.token(p) {
+            match token {
+                Token::LBrace => depth += 1,
+                Token::RBrace if depth <= 1 => return p + 1,
+                Token::RBrace => depth -= 1,
+                Token::Semi if depth == 0 => return p + 1,
+                Token::Import | Token::Struct if p > pos => return p,
+                Token::Id(_) if p > pos && self.is_definition_start(p) => return p,
+                _ => {}
             }
-            Step::Error(ParseError::UnreachedEOF(pos)) => {
-                let env = self.inner.env(&step);
-                Err(TreeError::ParseError(format!(
-                    "Parse error on the pos:`{pos}` is unreached eof with the env: `{env}`"
-                )))
+            p += 1;
+        }
+        p
+    }
+
+    /// the definition starts with the type followed by the name like `sequence name`.
+    /// The calls never look like that, thus it is safe to check it on any level of nesting.
+    fn is_definition_start(&self, pos: usize) -> bool {
+        matches!(self.tree_type(pos), Step::Success(..))
+            && matches!(self.id(pos + 1), Step::Success(..))
+    }
+
+    fn unexpected(&self, pos: usize) -> TreeError {
+        self.error_at(
+            pos,
+            format!("the token `{}` is unexpected", self.token_text(pos)),
+        )
+    }
+
+    fn step_error(&self, err: ParseError<'a>) -> TreeError {
+        match err {
+            ParseError::BadToken(t, range) => TreeError::ParseError(
+                Diagnostic::new(format!("the token `{t}` is not recognized"))
+                    .with_span(Span::new(range.start, range.end))
+                    .resolve(self.src)
+                    .into(),
+            ),
+            ParseError::ExternalError(ext_t, pos) => self.error_at(
+                pos,
+                format!(
+                    "the token `{}` is not recognized: {ext_t}",
+                    self.token_text(pos)
+                ),
+            ),
+            ParseError::FailedOnValidation(ext_t, pos) => {
+                self.error_at(pos, format!("the validation is failed: {ext_t}"))
             }
-            Step::Error(err) => Err(TreeError::ParseError(err.to_string())),
+            ParseError::ReachedEOF(pos) => self.error_at(
+                pos,
+                "the end of the file is reached but some tokens are expected".to_string(),
+            ),
+            ParseError::UnreachedEOF(pos) => self.error_at(
+                pos,
+                format!(
+                    "the token `{}` is unexpected, the definition or import is expected",
+                    self.token_text(pos)
+                ),
+            ),
+            err => TreeError::ParseError(Diagnostic::new(err.to_string()).into()),
         }
     }
 }
@@ -1,11 +1,14 @@
 pub mod arg;
 pub mod call;
+pub mod expr;
 pub mod invocation;
 pub mod message;
+use crate::tree::diagnostic::Span;
 use crate::tree::parser::ast::invocation::Invocation;
 use crate::tree::project::{AliasName, TreeName};
-use arg::{Arguments, Params};
+use arg::{Arguments, Field, Params};
 use call::{Call, Calls};
+use message::Message;
 use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 use strum_macros::EnumString;
@@ -71,11 +74,13 @@ pub fn validate_lambda<'a, 'b>(
             }
         }
 
+        TreeType::Parallel => Ok(()),
+
         _ => {
             if args.args.is_empty() {
                 Ok(())
             } else {
-                Err("any lambda invocation should not have arguments")
+                Err("any lambda invocation except parallel should not have arguments")
             }
         }
     }
@@ -87,6 +92,7 @@ pub struct Tree {
     pub name: Key,
     pub params: Params,
     pub calls: Calls,
+    pub span: Span,
 }
 
 impl Tree {
@@ -110,8 +116,12 @@ impl Tree {
             name,
             params,
             calls,
+            span: Span::default(),
         }
     }
+    pub fn with_span(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
     pub fn to_inv(&self) -> Invocation {
         self.into()
     }
@@ -121,13 +131,16 @@ impl Tree {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct Import(pub String, pub Vec<ImportName>);
+pub struct Import(pub String, pub Vec<ImportName>, pub Span);
 
 #[derive(Clone, Debug, PartialEq, Hash, Eq)]
 pub enum ImportName {
     Id(String),
     Alias(TreeName, AliasName),
     WholeFile,
+    /// the whole file is imported under the namespace, like `import "nav.tree" as nav`,
+    /// and the names of it are qualified like `nav::approach`
+    Namespace(AliasName),
 }
 
 impl ImportName {
@@ -142,20 +155,104 @@ impl ImportName {
 impl Import {
     pub fn f_name(&self) -> &str {
         match self {
-            Import(n, _) => n,
+            Import(n, ..) => n,
         }
     }
+    pub fn span(&self) -> Span {
+        self.2
+    }
+    pub fn with_span(self, span: Span) -> Self {
+        Import(self.0, self.1, span)
+    }
     pub fn file(f: &str) -> Self {
-        Import(f.to_string(), vec![ImportName::WholeFile])
+        Import(f.to_string(), vec![ImportName::WholeFile], Span::default())
     }
     pub fn names(f: &str, names: Vec<&str>) -> Self {
         Import(
             f.to_string(),
             names.into_iter().map(ImportName::id).collect(),
+            Span::default(),
+        )
+    }
+    pub fn namespace(f: &str, ns: &str) -> Self {
+        Import(
+            f.to_string(),
+            vec![ImportName::Namespace(ns.to_string())],
+            Span::default(),
         )
     }
     pub fn names_mixed(f: &str, names: Vec<ImportName>) -> Self {
-        Import(f.to_string(), names)
+        Import(f.to_string(), names, Span::default())
+    }
+}
+
+/// The declaration of the struct type that can be used as a type of the parameters
+/// # Example
+/// `struct Cfg { rate:num, tp?:string }`
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructDef {
+    pub name: Key,
+    pub fields: Vec<Field>,
+    pub span: Span,
+}
+
+impl StructDef {
+    pub fn new(name: &str, fields: Vec<Field>) -> Self {
+        StructDef {
+            name: name.to_string(),
+            fields,
+            span: Span::default(),
+        }
+    }
+    pub fn with_span(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
+}
+
+/// The named constant that is inlined into the arguments referring to it
+/// # Example
+/// `const timeout = 1000;`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstDef {
+    pub name: Key,
+    pub value: Message,
+    pub span: Span,
+}
+
+impl ConstDef {
+    pub fn new(name: &str, value: Message) -> Self {
+        ConstDef {
+            name: name.to_string(),
+            value,
+            span: Span::default(),
+        }
+    }
+    pub fn with_span(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
+}
+
+/// The declaration of the enum type that can be used as a type of the parameters.
+/// The variants are referred as `Mode::auto` and turn into the strings at runtime.
+/// # Example
+/// `enum Mode { auto, manual }`
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumDef {
+    pub name: Key,
+    pub variants: Vec<Key>,
+    pub span: Span,
+}
+
+impl EnumDef {
+    pub fn new(name: &str, variants: Vec<&str>) -> Self {
+        EnumDef {
+            name: name.to_string(),
+            variants: variants.into_iter().map(ToString::to_string).collect(),
+            span: Span::default(),
+        }
+    }
+    pub fn with_span(self, span: Span) -> Self {
+        Self { span, ..self }
     }
 }
 
@@ -163,6 +260,9 @@ impl Import {
 pub enum FileEntity {
     Tree(Tree),
     Import(Import),
+    Struct(StructDef),
+    Const(ConstDef),
+    Enum(EnumDef),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -1,26 +1,69 @@
+use crate::tree::diagnostic::{Diagnostic, Span};
 use crate::tree::parser::ast::arg::ArgumentsType::{Named, Unnamed};
 use crate::tree::parser::ast::call::Call;
+use crate::tree::parser::ast::expr::Expr;
 use crate::tree::parser::ast::message::Message;
 use crate::tree::parser::ast::Key;
-use crate::tree::{cerr, TreeError};
+use crate::tree::printer::message;
+use crate::tree::TreeError;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use strum_macros::{Display, EnumString};
 
-/// Just a pair of name and type
+/// The direction of the parameter that shows how the action treats the argument.
+/// The output parameters accept only the pointers to bb
+/// since the action writes the result to the given cell.
+/// # Example
+/// `impl fetch(url:string, out result:any, inout attempts:num);`
+#[derive(
+    Display, Debug, Clone, Copy, Default, PartialEq, Eq, EnumString, Deserialize, Serialize,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum ParamDir {
+    /// the default direction, the action only reads the argument
+    #[default]
+    In,
+    /// the action writes the result to the given cell
+    Out,
+    /// the action reads and writes the given cell
+    #[strum(serialize = "inout")]
+    InOut,
+}
+
+impl ParamDir {
+    /// the action can write to the argument
+    pub fn is_out(&self) -> bool {
+        matches!(self, ParamDir::Out | ParamDir::InOut)
+    }
+}
+
+/// Just a pair of name and type along with the direction and the default value
 /// This is a representation of a tree parameter
 /// # Example
-/// `sequence tree(a:string){...}`
-/// and a:string is a param
+/// `sequence tree(a:string, delay:num = 100, label?:string){...}`
+/// and a:string is a param.
+/// The arguments for the params with the default value or marked with `?` can be omitted.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Param {
     pub name: Key,
     pub tpe: MesType,
+    pub dir: ParamDir,
+    pub default: Option<Message>,
+    pub optional: bool,
 }
 
 impl Display for Param {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}:{}", self.name, self.tpe)
+        let mark = if self.optional { "?" } else { "" };
+        match self.dir {
+            ParamDir::In => write!(f, "{}{mark}:{}", self.name, self.tpe)?,
+            dir => write!(f, "{} {}{mark}:{}", dir, self.name, self.tpe)?,
+        }
+        match &self.default {
+            Some(m) => write!(f, " = {}", message(m)),
+            None => Ok(()),
+        }
     }
 }
 
@@ -29,8 +72,30 @@ impl Param {
         Param {
             name: id.to_string(),
             tpe,
+            dir: ParamDir::In,
+            default: None,
+            optional: false,
+        }
+    }
+    pub fn with_dir(self, dir: ParamDir) -> Self {
+        Param { dir, ..self }
+    }
+    pub fn with_default(self, default: Message) -> Self {
+        Param {
+            default: Some(default),
+            ..self
+        }
+    }
+    pub fn optional(self) -> Self {
+        Param {
+            optional: true,
+            ..self
         }
     }
+    /// the argument for the parameter can be omitted
+    pub fn is_optional(&self) -> bool {
+        self.optional || self.default.is_some()
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -53,6 +118,8 @@ pub enum ArgumentRhs {
     Mes(Message),
     /// A call to a tree
     Call(Call),
+    /// An inline expression like `battery < 20 && mode == "auto"`
+    Expr(Expr),
 }
 
 impl ArgumentRhs {
@@ -69,17 +136,18 @@ impl Display for ArgumentRhs {
         match self {
             ArgumentRhs::Id(id) => write!(f, "&{}", id),
             ArgumentRhs::Mes(m) => write!(f, "{}", m),
+            ArgumentRhs::Expr(e) => write!(f, "{}", e),
             ArgumentRhs::Call(c) => match c {
-                Call::Invocation(name, args) => {
+                Call::Invocation(name, args, _) => {
                     write!(f, "{}({})", name, args)
                 }
-                Call::HoInvocation(name) => {
+                Call::HoInvocation(name, _) => {
                     write!(f, "{}(..)", name)
                 }
-                Call::Lambda(tpe, _) => {
+                Call::Lambda(tpe, ..) => {
                     write!(f, "{}...", tpe)
                 }
-                Call::Decorator(tpe, args, _call) => {
+                Call::Decorator(tpe, args, ..) => {
                     write!(f, "{}({})...", tpe, args)
                 }
             },
@@ -88,27 +156,39 @@ impl Display for ArgumentRhs {
 }
 
 /// An argument is a pair of name and value or just a value
+/// along with the place in the source text.
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum Argument {
-    Assigned(Key, ArgumentRhs),
-    Unassigned(ArgumentRhs),
+    Assigned(Key, ArgumentRhs, Span),
+    Unassigned(ArgumentRhs, Span),
 }
 
 impl Argument {
     pub fn has_name(&self, key: &Key) -> bool {
-        matches!(self, Argument::Assigned(k, _) if k == key)
+        matches!(self, Argument::Assigned(k, ..) if k == key)
     }
 
     pub fn name(&self) -> Option<&Key> {
         match self {
-            Argument::Assigned(k, _) => Some(k),
-            Argument::Unassigned(_) => None,
+            Argument::Assigned(k, ..) => Some(k),
+            Argument::Unassigned(..) => None,
         }
     }
 
     pub fn value(&self) -> &ArgumentRhs {
         match self {
-            Argument::Assigned(_, v) | Argument::Unassigned(v) => v,
+            Argument::Assigned(_, v, _) | Argument::Unassigned(v, _) => v,
+        }
+    }
+    pub fn span(&self) -> Span {
+        match self {
+            Argument::Assigned(.., s) | Argument::Unassigned(.., s) => *s,
+        }
+    }
+    pub fn with_span(self, span: Span) -> Self {
+        match self {
+            Argument::Assigned(k, v, _) => Argument::Assigned(k, v, span),
+            Argument::Unassigned(v, _) => Argument::Unassigned(v, span),
         }
     }
 }
@@ -116,30 +196,42 @@ impl Argument {
 impl Display for Argument {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Argument::Assigned(k, rhs) => write!(f, "{}={}", k, rhs),
-            Argument::Unassigned(rhs) => write!(f, "{}", rhs),
+            Argument::Assigned(k, rhs, _) => write!(f, "{}={}", k, rhs),
+            Argument::Unassigned(rhs, _) => write!(f, "{}", rhs),
         }
     }
 }
 
 impl Argument {
+    pub fn assigned(lhs: Key, rhs: ArgumentRhs) -> Self {
+        Argument::Assigned(lhs, rhs, Span::default())
+    }
+    pub fn unassigned(rhs: ArgumentRhs) -> Self {
+        Argument::Unassigned(rhs, Span::default())
+    }
     pub fn id(v: &str) -> Self {
-        Argument::Unassigned(ArgumentRhs::Id(v.to_string()))
+        Argument::unassigned(ArgumentRhs::Id(v.to_string()))
     }
     pub fn mes(v: Message) -> Self {
-        Argument::Unassigned(ArgumentRhs::Mes(v))
+        Argument::unassigned(ArgumentRhs::Mes(v))
     }
     pub fn call(v: Call) -> Self {
-        Argument::Unassigned(ArgumentRhs::Call(v))
+        Argument::unassigned(ArgumentRhs::Call(v))
+    }
+    pub fn expr(v: Expr) -> Self {
+        Argument::unassigned(ArgumentRhs::Expr(v))
     }
     pub fn id_id(lhs: &str, rhs: &str) -> Self {
-        Argument::Assigned(lhs.to_string(), ArgumentRhs::Id(rhs.to_string()))
+        Argument::assigned(lhs.to_string(), ArgumentRhs::Id(rhs.to_string()))
     }
     pub fn id_mes(lhs: &str, rhs: Message) -> Self {
-        Argument::Assigned(lhs.to_string(), ArgumentRhs::Mes(rhs))
+        Argument::assigned(lhs.to_string(), ArgumentRhs::Mes(rhs))
     }
     pub fn id_call(lhs: &str, rhs: Call) -> Self {
-        Argument::Assigned(lhs.to_string(), ArgumentRhs::Call(rhs))
+        Argument::assigned(lhs.to_string(), ArgumentRhs::Call(rhs))
+    }
+    pub fn id_expr(lhs: &str, rhs: Expr) -> Self {
+        Argument::assigned(lhs.to_string(), ArgumentRhs::Expr(rhs))
     }
 }
 
@@ -163,13 +255,18 @@ impl Arguments {
 
         for a in &self.args {
             match (a, &curr) {
-                (Argument::Assigned(_, _), None) => curr = Some(Named),
-                (Argument::Unassigned(_), None) => curr = Some(Unnamed),
-                (Argument::Assigned(_, _), Some(Named)) => {}
-                (Argument::Unassigned(_), Some(Unnamed)) => {}
+                (Argument::Assigned(..), None) => curr = Some(Named),
+                (Argument::Unassigned(..), None) => curr = Some(Unnamed),
+                (Argument::Assigned(..), Some(Named)) => {}
+                (Argument::Unassigned(..), Some(Unnamed)) => {}
                 _ => {
-                    return Err(cerr(
-                        format!("the arguments ({}) should be either named ot unnamed but not a mix",self)
+                    return Err(TreeError::CompileError(
+                        Diagnostic::new(format!(
+                            "the arguments ({}) should be either named ot unnamed but not a mix",
+                            self
+                        ))
+                        .with_span(a.span())
+                        .into(),
                     ))
                 }
             }
@@ -200,6 +297,40 @@ pub enum MesType {
     String,
     Bool,
     Tree,
+    /// the array with the given type of the elements like `array<num>`
+    ArrayOf(Box<MesType>),
+    /// the name of the struct or the enum declared in a file as it is written in the parameter.
+    /// It gets replaced with the declaration when the project is built.
+    Named(Key),
+    /// the struct with the name and the fields
+    Struct(Key, Vec<Field>),
+    /// the enum with the name and the variants. The values are the strings at runtime.
+    Enum(Key, Vec<Key>),
+}
+
+impl MesType {
+    pub fn array_of(tpe: MesType) -> Self {
+        MesType::ArrayOf(Box::new(tpe))
+    }
+
+    /// the types can hold the same value.
+    /// The untyped arrays and objects are compatible with the typed ones
+    /// and the strings are compatible with the enums.
+    pub fn compatible(&self, other: &MesType) -> bool {
+        match (self, other) {
+            (MesType::Any, _) | (_, MesType::Any) => true,
+            (MesType::ArrayOf(lhs), MesType::ArrayOf(rhs)) => lhs.compatible(rhs),
+            (MesType::Array, MesType::ArrayOf(_)) | (MesType::ArrayOf(_), MesType::Array) => true,
+            (MesType::Object, MesType::Named(_) | MesType::Struct(..))
+            | (MesType::Named(_) | MesType::Struct(..), MesType::Object) => true,
+            (MesType::String, MesType::Enum(..)) | (MesType::Enum(..), MesType::String) => true,
+            (
+                MesType::Named(lhs) | MesType::Struct(lhs, _) | MesType::Enum(lhs, _),
+                MesType::Named(rhs) | MesType::Struct(rhs, _) | MesType::Enum(rhs, _),
+            ) => lhs == rhs,
+            (lhs, rhs) => lhs == rhs,
+        }
+    }
 }
 
 impl Display for MesType {
@@ -212,6 +343,47 @@ impl Display for MesType {
             MesType::String => write!(f, "string"),
             MesType::Bool => write!(f, "bool"),
             MesType::Tree => write!(f, "tree"),
+            MesType::ArrayOf(tpe) => write!(f, "array<{}>", tpe),
+            MesType::Named(name) | MesType::Struct(name, _) | MesType::Enum(name, _) => {
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+/// The field of the struct.
+/// The optional fields can be absent in the object.
+/// # Example
+/// `struct Cfg { rate:num, tp?:string }`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: Key,
+    pub tpe: MesType,
+    pub optional: bool,
+}
+
+impl Field {
+    pub fn new(name: &str, tpe: MesType) -> Self {
+        Field {
+            name: name.to_string(),
+            tpe,
+            optional: false,
+        }
+    }
+    pub fn optional(name: &str, tpe: MesType) -> Self {
+        Field {
+            optional: true,
+            ..Field::new(name, tpe)
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.optional {
+            write!(f, "{}?:{}", self.name, self.tpe)
+        } else {
+            write!(f, "{}:{}", self.name, self.tpe)
         }
     }
 }
@@ -1,27 +1,30 @@
-use crate::tree::parser::ast::arg::Arguments;
+use crate::tree::diagnostic::Span;
+use crate::tree::parser::ast::arg::{ArgumentRhs, Arguments};
 use crate::tree::parser::ast::{Key, TreeType};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 
 /// A call to a tree
+/// Every call keeps the span in the source text (the last field)
 #[derive(Clone, PartialEq, Deserialize, Serialize)]
 pub enum Call {
     /// An invocation of a tree like 'root main { invocation()}'
-    Invocation(Key, Arguments),
+    Invocation(Key, Arguments, Span),
     /// An Higher order invocation of a tree like 'root main { ho-invocation(..)}'
-    HoInvocation(Key),
-    /// A lambda call like 'root main { sequence {...} }'
-    Lambda(TreeType, Calls),
+    HoInvocation(Key, Span),
+    /// A lambda call like 'root main { sequence {...} }'.
+    /// Only the parallel lambda accepts arguments like 'parallel(success = 2) {...}'
+    Lambda(TreeType, Arguments, Calls, Span),
     /// A decorator call like 'root main { decorator(..) child() }'
-    Decorator(TreeType, Arguments, Box<Call>),
+    Decorator(TreeType, Arguments, Box<Call>, Span),
 }
 
 impl Debug for Call {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Call::Invocation(id, args) => write!(f, "{}({})", id, args),
-            Call::HoInvocation(id) => write!(f, "{}(..)", id),
-            Call::Lambda(tpe, calls) => {
+            Call::Invocation(id, args, _) => write!(f, "{}({})", id, args),
+            Call::HoInvocation(id, _) => write!(f, "{}(..)", id),
+            Call::Lambda(tpe, args, calls, _) if args.args.is_empty() => {
                 let _ = write!(f, "{} :", tpe);
                 let mut elems = f.debug_list();
                 for call in calls.elems.iter() {
@@ -30,7 +33,16 @@ impl Debug for Call {
                 let _ = elems.finish();
                 Ok(())
             }
-            Call::Decorator(tpe, args, call) => {
+            Call::Lambda(tpe, args, calls, _) => {
+                let _ = write!(f, "{}({}) :", tpe, args);
+                let mut elems = f.debug_list();
+                for call in calls.elems.iter() {
+                    elems.entry(call);
+                }
+                let _ = elems.finish();
+                Ok(())
+            }
+            Call::Decorator(tpe, args, call, _) => {
                 let _ = write!(f, "{}({}) :", tpe, args);
                 let mut elems = f.debug_list();
                 elems.entry(call);
@@ -43,47 +55,67 @@ impl Debug for Call {
 
 impl Call {
     pub fn is_lambda(&self) -> bool {
-        matches!(self, Call::Lambda(_, _))
+        matches!(self, Call::Lambda(..))
     }
     pub fn is_decorator(&self) -> bool {
-        matches!(self, Call::Decorator(_,_, _))
+        matches!(self, Call::Decorator(..))
     }
 
     pub fn get_ho_invocation(&self) -> Option<Key> {
         match self {
-            Call::HoInvocation(k) => Some(k.clone()),
+            Call::HoInvocation(k, _) => Some(k.clone()),
             _ => None,
         }
     }
 
     pub fn key(&self) -> Option<Key> {
         match self {
-            Call::Invocation(k, _) => Some(k.clone()),
-            Call::HoInvocation(k) => Some(k.clone()),
-            Call::Lambda(_, _) => None,
-            Call::Decorator(_, _, _) => None,
+            Call::Invocation(k, ..) => Some(k.clone()),
+            Call::HoInvocation(k, _) => Some(k.clone()),
+            Call::Lambda(..) => None,
+            Call::Decorator(..) => None,
         }
     }
     pub fn arguments(&self) -> Arguments {
         match self {
-            Call::Invocation(_, args) => args.clone(),
-            Call::HoInvocation(_) => Arguments::default(),
-            Call::Lambda(_, _) => Arguments::default(),
-            Call::Decorator(_, args, _) => args.clone(),
+            Call::Invocation(_, args, _) => args.clone(),
+            Call::HoInvocation(..) => Arguments::default(),
+            Call::Lambda(_, args, ..) => args.clone(),
+            Call::Decorator(_, args, ..) => args.clone(),
+        }
+    }
+    /// the place of the call in the source text
+    pub fn span(&self) -> Span {
+        match self {
+            Call::Invocation(.., s)
+            | Call::HoInvocation(.., s)
+            | Call::Lambda(.., s)
+            | Call::Decorator(.., s) => *s,
+        }
+    }
+    pub fn with_span(self, span: Span) -> Self {
+        match self {
+            Call::Invocation(k, args, _) => Call::Invocation(k, args, span),
+            Call::HoInvocation(k, _) => Call::HoInvocation(k, span),
+            Call::Lambda(tpe, args, calls, _) => Call::Lambda(tpe, args, calls, span),
+            Call::Decorator(tpe, args, call, _) => Call::Decorator(tpe, args, call, span),
         }
     }
 
     pub fn invocation(id: &str, args: Arguments) -> Self {
-        Call::Invocation(id.to_string(), args)
+        Call::Invocation(id.to_string(), args, Span::default())
     }
     pub fn ho_invocation(id: &str) -> Self {
-        Call::HoInvocation(id.to_string())
+        Call::HoInvocation(id.to_string(), Span::default())
     }
     pub fn lambda(tpe: TreeType, calls: Calls) -> Self {
-        Call::Lambda(tpe, calls)
+        Call::Lambda(tpe, Arguments::default(), calls, Span::default())
+    }
+    pub fn lambda_with(tpe: TreeType, args: Arguments, calls: Calls) -> Self {
+        Call::Lambda(tpe, args, calls, Span::default())
     }
     pub fn decorator(tpe: TreeType, args: Arguments, call: Call) -> Self {
-        Call::Decorator(tpe, args, Box::new(call))
+        Call::Decorator(tpe, args, Box::new(call), Span::default())
     }
 }
 
@@ -96,4 +128,33 @@ impl Calls {
     pub fn new(elems: Vec<Call>) -> Self {
         Calls { elems }
     }
+
+    /// all calls in the order they are written down
+    /// including the nested ones in the lambdas, the decorators and the arguments
+    pub fn flatten<'a>(&'a self) -> Vec<&'a Call> {
+        let mut all = vec![];
+        let mut stack: Vec<&Call> = self.elems.iter().rev().collect();
+        let passed = |args: &'a Arguments| {
+            args.args.iter().rev().filter_map(|a| match a.value() {
+                ArgumentRhs::Call(call) => Some(call),
+                _ => None,
+            })
+        };
+        while let Some(call) = stack.pop() {
+            all.push(call);
+            match call {
+                Call::Invocation(_, args, _) => stack.extend(passed(args)),
+                Call::HoInvocation(..) => {}
+                Call::Lambda(_, args, calls, _) => {
+                    stack.extend(calls.elems.iter().rev());
+                    stack.extend(passed(args));
+                }
+                Call::Decorator(_, args, call, _) => {
+                    stack.push(call);
+                    stack.extend(passed(args));
+                }
+            }
+        }
+        all
+    }
 }
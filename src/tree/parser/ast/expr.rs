@@ -0,0 +1,280 @@
+use crate::tree::parser::ast::arg::MesType;
+use crate::tree::parser::ast::message::Message;
+use crate::tree::parser::ast::Key;
+use crate::tree::printer::message;
+use crate::tree::{cerr, TreeError};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// The binary operators of the expressions ordered by the priority
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum BinOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+impl BinOp {
+    /// the higher the value the tighter the operator binds
+    pub fn priority(&self) -> u8 {
+        match self {
+            BinOp::Or => 1,
+            BinOp::And => 2,
+            BinOp::Add | BinOp::Sub => 4,
+            BinOp::Mul | BinOp::Div | BinOp::Mod => 5,
+            _ => 3,
+        }
+    }
+    pub fn is_logic(&self) -> bool {
+        matches!(self, BinOp::Or | BinOp::And)
+    }
+    pub fn is_ordering(&self) -> bool {
+        matches!(self, BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge)
+    }
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(
+            self,
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod
+        )
+    }
+}
+
+impl Display for BinOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            BinOp::Or => "||",
+            BinOp::And => "&&",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Mod => "%",
+        };
+        write!(f, "{op}")
+    }
+}
+
+/// The part of the template string
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum TemplatePart {
+    Text(String),
+    Expr(Expr),
+}
+
+/// The inline expression that is evaluated against the blackboard at the tick time.
+/// The ids are either the parameters of the enclosing tree or the keys in the blackboard.
+///
+/// # Example
+/// `check(battery < 20 && mode == "auto")`
+/// `publish($"/robot_{id}/cmd", attempts * 2)`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Expr {
+    Id(Key),
+    Mes(Message),
+    Not(Box<Expr>),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+    Template(Vec<TemplatePart>),
+}
+
+impl Expr {
+    pub fn id(v: &str) -> Self {
+        Expr::Id(v.to_string())
+    }
+    pub fn negate(e: Expr) -> Self {
+        Expr::Not(Box::new(e))
+    }
+    pub fn binary(lhs: Expr, op: BinOp, rhs: Expr) -> Self {
+        Expr::Binary(Box::new(lhs), op, Box::new(rhs))
+    }
+
+    /// an id or a message that is not worth being an expression on its own
+    pub fn is_atom(&self) -> bool {
+        matches!(self, Expr::Id(_) | Expr::Mes(_))
+    }
+
+    fn priority(&self) -> u8 {
+        match self {
+            Expr::Binary(_, op, _) => op.priority(),
+            Expr::Not(_) => 6,
+            _ => 7,
+        }
+    }
+
+    /// all ids in the order of appearance without duplicates
+    pub fn ids(&self) -> Vec<Key> {
+        let mut ids = vec![];
+        self.collect_ids(&mut ids);
+        ids
+    }
+    fn collect_ids(&self, ids: &mut Vec<Key>) {
+        match self {
+            Expr::Id(id) if !ids.contains(id) => ids.push(id.clone()),
+            Expr::Id(_) | Expr::Mes(_) => {}
+            Expr::Not(e) => e.collect_ids(ids),
+            Expr::Binary(lhs, _, rhs) => {
+                lhs.collect_ids(ids);
+                rhs.collect_ids(ids);
+            }
+            Expr::Template(parts) => {
+                for part in parts {
+                    if let TemplatePart::Expr(e) = part {
+                        e.collect_ids(ids)
+                    }
+                }
+            }
+        }
+    }
+
+    /// replaces every id with the result of the function.
+    pub fn try_map_ids<F>(self, f: &F) -> Result<Expr, TreeError>
+    where
+        F: Fn(Key) -> Result<Expr, TreeError>,
+    {
+        match self {
+            Expr::Id(id) => f(id),
+            Expr::Mes(m) => Ok(Expr::Mes(m)),
+            Expr::Not(e) => Ok(Expr::negate(e.try_map_ids(f)?)),
+            Expr::Binary(lhs, op, rhs) => {
+                Ok(Expr::binary(lhs.try_map_ids(f)?, op, rhs.try_map_ids(f)?))
+            }
+            Expr::Template(parts) => Ok(Expr::Template(
+                parts
+                    .into_iter()
+                    .map(|part| match part {
+                        TemplatePart::Expr(e) => e.try_map_ids(f).map(TemplatePart::Expr),
+                        text => Ok(text),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+        }
+    }
+
+    /// infers the type of the expression.
+    /// The type of the ids is unknown till the runtime, thus only the literals are checked.
+    /// Returns None if the type can not be inferred.
+    pub fn tpe(&self) -> Result<Option<MesType>, TreeError> {
+        self.tpe_with(&|_| None)
+    }
+
+    /// infers the type of the expression taking the types of the ids from the given function.
+    /// Returns None if the type can not be inferred.
+    pub fn tpe_with<F>(&self, ids: &F) -> Result<Option<MesType>, TreeError>
+    where
+        F: Fn(&Key) -> Option<MesType>,
+    {
+        let literal = |m: &Message| match m {
+            Message::Num(_) => MesType::Num,
+            Message::String(_) => MesType::String,
+            Message::Bool(_) => MesType::Bool,
+            Message::Array(_) => MesType::Array,
+            Message::Object(_) => MesType::Object,
+        };
+        let expect = |e: &Expr, tpe: MesType| match e.tpe_with(ids)? {
+            Some(t) if t != tpe => Err(cerr(format!(
+                "the expression '{e}' is expected to be {tpe} but it is {t}"
+            ))),
+            _ => Ok(()),
+        };
+        match self {
+            Expr::Id(id) => Ok(ids(id)),
+            Expr::Mes(m) => Ok(Some(literal(m))),
+            Expr::Not(e) => expect(e, MesType::Bool).map(|_| Some(MesType::Bool)),
+            Expr::Template(parts) => {
+                for part in parts {
+                    if let TemplatePart::Expr(e) = part {
+                        e.tpe_with(ids)?;
+                    }
+                }
+                Ok(Some(MesType::String))
+            }
+            Expr::Binary(lhs, op, rhs) if op.is_logic() => {
+                expect(lhs, MesType::Bool)?;
+                expect(rhs, MesType::Bool)?;
+                Ok(Some(MesType::Bool))
+            }
+            // the strings are concatenated with any value
+            Expr::Binary(lhs, BinOp::Add, rhs) => match (lhs.tpe_with(ids)?, rhs.tpe_with(ids)?) {
+                (Some(MesType::String), _) | (_, Some(MesType::String)) => {
+                    Ok(Some(MesType::String))
+                }
+                (Some(MesType::Num), Some(MesType::Num)) => Ok(Some(MesType::Num)),
+                (Some(t), _) | (_, Some(t)) if t != MesType::Num && t != MesType::Any => Err(cerr(
+                    format!("the operands of '{self}' can not be added since they are {t}"),
+                )),
+                _ => Ok(None),
+            },
+            Expr::Binary(lhs, op, rhs) if op.is_arithmetic() => {
+                expect(lhs, MesType::Num)?;
+                expect(rhs, MesType::Num)?;
+                Ok(Some(MesType::Num))
+            }
+            Expr::Binary(lhs, op, rhs) => match (lhs.tpe_with(ids)?, rhs.tpe_with(ids)?) {
+                (Some(l), Some(r)) if !l.compatible(&r) => Err(cerr(format!(
+                    "the operands of '{self}' have different types {l} and {r}"
+                ))),
+                (Some(t), _) | (_, Some(t))
+                    if op.is_ordering() && t != MesType::Num && t != MesType::String =>
+                {
+                    Err(cerr(format!(
+                        "the operands of '{self}' can not be ordered since they are {t}"
+                    )))
+                }
+                _ => Ok(Some(MesType::Bool)),
+            },
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let wrapped = |e: &Expr, min: u8| {
+            if e.priority() < min {
+                format!("({e})")
+            } else {
+                format!("{e}")
+            }
+        };
+        match self {
+            Expr::Id(id) => write!(f, "{id}"),
+            Expr::Mes(m) => write!(f, "{}", message(m)),
+            Expr::Not(e) => write!(f, "!{}", wrapped(e, 6)),
+            Expr::Binary(lhs, op, rhs) => {
+                let p = op.priority();
+                // the comparisons are not associative and the rest are left associative
+                let lhs_min = if op.is_logic() || op.is_arithmetic() {
+                    p
+                } else {
+                    p + 1
+                };
+                write!(f, "{} {op} {}", wrapped(lhs, lhs_min), wrapped(rhs, p + 1))
+            }
+            Expr::Template(parts) => {
+                let parts = parts
+                    .iter()
+                    .map(|part| match part {
+                        TemplatePart::Text(t) => t.replace('{', "{{").replace('}', "}}"),
+                        TemplatePart::Expr(e) => format!("{{{e}}}"),
+                    })
+                    .join("");
+                write!(f, "$\"{parts}\"")
+            }
+        }
+    }
+}
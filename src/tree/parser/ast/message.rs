@@ -1,4 +1,6 @@
 use crate::tree::parser::ast::arg::MesType;
+use crate::tree::printer::message;
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
@@ -74,14 +76,55 @@ impl Display for Message {
 
 impl Message {
     pub fn same(&self, mt: &MesType) -> bool {
-        matches!(
-            (&self, mt),
-            (Message::Num(_), MesType::Num)
-                | (Message::String(_), MesType::String)
-                | (Message::Bool(_), MesType::Bool)
-                | (Message::Array(_), MesType::Array)
-                | (Message::Object(_), MesType::Object))
-            || matches!(mt, MesType::Any)
+        self.conform(mt).is_ok()
+    }
+
+    /// checks the message against the type including the elements of the arrays and the fields of the structs.
+    /// Returns the reason if the message does not correspond to the type.
+    pub fn conform(&self, mt: &MesType) -> Result<(), String> {
+        match (self, mt) {
+            (_, MesType::Any)
+            | (Message::Num(_), MesType::Num)
+            | (Message::String(_), MesType::String)
+            | (Message::Bool(_), MesType::Bool)
+            | (Message::Array(_), MesType::Array)
+            | (Message::Object(_), MesType::Object | MesType::Named(_)) => Ok(()),
+            (Message::Array(elems), MesType::ArrayOf(tpe)) => {
+                elems.iter().enumerate().try_for_each(|(i, e)| {
+                    e.conform(tpe)
+                        .map_err(|r| format!("the element {i} of array<{tpe}> is wrong: {r}"))
+                })
+            }
+            (Message::Object(obj), MesType::Struct(name, fields)) => {
+                if let Some(key) = obj
+                    .keys()
+                    .sorted()
+                    .find(|k| !fields.iter().any(|f| &f.name == *k))
+                {
+                    return Err(format!("the field '{key}' is not declared in {name}"));
+                }
+                for field in fields {
+                    match obj.get(&field.name) {
+                        None if field.optional => {}
+                        None => {
+                            return Err(format!("the field '{}' of {name} is absent", field.name))
+                        }
+                        Some(v) => v.conform(&field.tpe).map_err(|r| {
+                            format!("the field '{}' of {name} is wrong: {r}", field.name)
+                        })?,
+                    }
+                }
+                Ok(())
+            }
+            (Message::String(StringLit(v)), MesType::Enum(name, variants)) => {
+                if variants.contains(v) {
+                    Ok(())
+                } else {
+                    Err(format!("'{v}' is not a variant of {name}"))
+                }
+            }
+            (m, mt) => Err(format!("'{}' is not {mt}", message(m))),
+        }
     }
 
     pub fn str(v: &str) -> Self {
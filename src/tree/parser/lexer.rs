@@ -13,6 +13,9 @@ pub enum Token {
     #[regex(r#""(?:[^"\\]|\\.)*""#, parse_qt_lit)]
     StringLit(String),
 
+    #[regex(r#"\$"(?:[^"\\]|\\.)*""#, parse_template)]
+    Template(String),
+
     #[regex(r"-?(?&digit)", number)]
     #[regex(r"-?(?&digit)(?&exp)", number)]
     #[regex(r"-?(?&digit)?\.(?&digit)(?&exp)?[fFdD]?", float)]
@@ -47,6 +50,9 @@ pub enum Token {
     #[token(":")]
     Colon,
 
+    #[token("::")]
+    ColonColon,
+
     #[token(";")]
     Semi,
 
@@ -56,6 +62,51 @@ pub enum Token {
     #[token("..")]
     DotDot,
 
+    #[token("==")]
+    Eq,
+
+    #[token("!=")]
+    Ne,
+
+    #[token("<")]
+    Lt,
+
+    #[token("<=")]
+    Le,
+
+    #[token(">")]
+    Gt,
+
+    #[token(">=")]
+    Ge,
+
+    #[token("&&")]
+    And,
+
+    #[token("||")]
+    Or,
+
+    #[token("!")]
+    Not,
+
+    #[token("+")]
+    Plus,
+
+    #[token("-")]
+    Minus,
+
+    #[token("*")]
+    Star,
+
+    #[token("/")]
+    Slash,
+
+    #[token("%")]
+    Percent,
+
+    #[token("?")]
+    Question,
+
     #[token("false")]
     False,
 
@@ -81,6 +132,18 @@ pub enum Token {
     #[token("import")]
     Import,
 
+    #[token("as")]
+    As,
+
+    #[token("struct")]
+    Struct,
+
+    #[token("const")]
+    Const,
+
+    #[token("enum")]
+    Enum,
+
     #[regex(r"(?s)/\*[^*/]*\*/", logos::skip)]
     #[regex(r"//[^\r\n]*", logos::skip)]
     Comment,
@@ -113,6 +176,10 @@ fn parse_qt_lit(lexer: &mut Lexer<Token>) -> String {
     let qt_lit: &str = lexer.slice();
     qt_lit[1..qt_lit.len() - 1].to_string()
 }
+fn parse_template(lexer: &mut Lexer<Token>) -> String {
+    let template: &str = lexer.slice();
+    template[2..template.len() - 1].to_string()
+}
 fn parse_id(lexer: &mut Lexer<Token>) -> String {
     let qt_lit: &str = lexer.slice();
     qt_lit.to_string()
@@ -134,6 +201,68 @@ mod tests {
         );
     }
     #[test]
+    fn operators() {
+        lt::expect::<Token>(
+            r#"a<=1&&!b||c!=d"#,
+            vec![
+                Token::Id("a".to_string()),
+                Token::Le,
+                Token::Digit(Number::Int(1)),
+                Token::And,
+                Token::Not,
+                Token::Id("b".to_string()),
+                Token::Or,
+                Token::Id("c".to_string()),
+                Token::Ne,
+                Token::Id("d".to_string()),
+            ],
+        );
+    }
+    #[test]
+    fn arithmetic() {
+        lt::expect::<Token>(
+            r#"a+1 - b*2/c%d-1 $"x_{a}""#,
+            vec![
+                Token::Id("a".to_string()),
+                Token::Plus,
+                Token::Digit(Number::Int(1)),
+                Token::Minus,
+                Token::Id("b".to_string()),
+                Token::Star,
+                Token::Digit(Number::Int(2)),
+                Token::Slash,
+                Token::Id("c".to_string()),
+                Token::Percent,
+                Token::Id("d".to_string()),
+                Token::Digit(Number::Int(-1)),
+                Token::Template("x_{a}".to_string()),
+            ],
+        );
+    }
+    #[test]
+    fn declarations() {
+        lt::expect::<Token>(
+            r#"enum Mode{auto} const m = Mode::auto; a:b"#,
+            vec![
+                Token::Enum,
+                Token::Id("Mode".to_string()),
+                Token::LBrace,
+                Token::Id("auto".to_string()),
+                Token::RBrace,
+                Token::Const,
+                Token::Id("m".to_string()),
+                Token::Assign,
+                Token::Id("Mode".to_string()),
+                Token::ColonColon,
+                Token::Id("auto".to_string()),
+                Token::Semi,
+                Token::Id("a".to_string()),
+                Token::Colon,
+                Token::Id("b".to_string()),
+            ],
+        );
+    }
+    #[test]
     fn string() {
         lt::expect::<Token>(
             "\"C:\\projects\"",
@@ -3,6 +3,7 @@ use crate::tree::parser::ast::call::{Call, Calls};
 use crate::tree::parser::ast::message::Message;
 use crate::tree::parser::ast::*;
 use crate::tree::parser::Parser;
+use parsit::step::Step;
 use parsit::test::parser_test::expect;
 
 #[test]
@@ -70,3 +71,93 @@ fn call_arg_part() {
         Argument::id_call("a", Call::ho_invocation("x")),
     );
 }
+
+#[test]
+fn expr_arg() {
+    use crate::tree::parser::ast::expr::{BinOp, Expr};
+
+    let parser = Parser::new(r#"battery < 20 && mode == "auto" || !(a != b)"#).unwrap();
+    expect(
+        parser.arg(0),
+        Argument::expr(Expr::binary(
+            Expr::binary(
+                Expr::binary(Expr::id("battery"), BinOp::Lt, Expr::Mes(Message::int(20))),
+                BinOp::And,
+                Expr::binary(Expr::id("mode"), BinOp::Eq, Expr::Mes(Message::str("auto"))),
+            ),
+            BinOp::Or,
+            Expr::negate(Expr::binary(Expr::id("a"), BinOp::Ne, Expr::id("b"))),
+        )),
+    );
+
+    let parser = Parser::new(r#"c = a >= 1.5"#).unwrap();
+    expect(
+        parser.arg(0),
+        Argument::id_expr(
+            "c",
+            Expr::binary(Expr::id("a"), BinOp::Ge, Expr::Mes(Message::float(1.5))),
+        ),
+    );
+
+    let parser = Parser::new(r#"check(!ready)"#).unwrap();
+    expect(
+        parser.call(0),
+        Call::invocation(
+            "check",
+            Arguments::new(vec![Argument::expr(Expr::negate(Expr::id("ready")))]),
+        ),
+    );
+}
+
+#[test]
+fn arithmetic_arg() {
+    use crate::tree::parser::ast::expr::{BinOp, Expr, TemplatePart};
+    let num = |v| Expr::Mes(Message::int(v));
+
+    let parser = Parser::new(r#"a + b * 2 - c % 3 > 1"#).unwrap();
+    expect(
+        parser.arg(0),
+        Argument::expr(Expr::binary(
+            Expr::binary(
+                Expr::binary(
+                    Expr::id("a"),
+                    BinOp::Add,
+                    Expr::binary(Expr::id("b"), BinOp::Mul, num(2)),
+                ),
+                BinOp::Sub,
+                Expr::binary(Expr::id("c"), BinOp::Mod, num(3)),
+            ),
+            BinOp::Gt,
+            num(1),
+        )),
+    );
+
+    // the negative number is lexed together with the minus
+    let parser = Parser::new(r#"attempts -1 * 2"#).unwrap();
+    expect(
+        parser.arg(0),
+        Argument::expr(Expr::binary(
+            Expr::id("attempts"),
+            BinOp::Sub,
+            Expr::binary(num(1), BinOp::Mul, num(2)),
+        )),
+    );
+
+    let parser = Parser::new(r#"topic = $"/robot_{id}/cmd_{n + 1}{{x}}""#).unwrap();
+    expect(
+        parser.arg(0),
+        Argument::id_expr(
+            "topic",
+            Expr::Template(vec![
+                TemplatePart::Text("/robot_".to_string()),
+                TemplatePart::Expr(Expr::id("id")),
+                TemplatePart::Text("/cmd_".to_string()),
+                TemplatePart::Expr(Expr::binary(Expr::id("n"), BinOp::Add, num(1))),
+                TemplatePart::Text("{x}".to_string()),
+            ]),
+        ),
+    );
+
+    let parser = Parser::new(r#"$"{a""#).unwrap();
+    assert!(matches!(parser.arg(0), Step::Fail(_) | Step::Error(_)));
+}
@@ -1,4 +1,4 @@
-use crate::tree::parser::ast::arg::{Argument, Arguments};
+use crate::tree::parser::ast::arg::{Argument, ArgumentRhs, Arguments};
 use crate::tree::parser::ast::call::{Call, Calls};
 use crate::tree::parser::ast::message::{Message, Number};
 use crate::tree::parser::ast::*;
@@ -72,6 +72,31 @@ fn lambda() {
     expect(parser.call(0), Call::ho_invocation("call"));
 }
 
+#[test]
+fn parallel_with_args() {
+    let parser = Parser::new(r#"parallel(success = 2) { call() }"#).unwrap();
+    expect(
+        parser.call(0),
+        Call::lambda_with(
+            TreeType::Parallel,
+            Arguments::new(vec![Argument::assigned(
+                "success".to_string(),
+                ArgumentRhs::Mes(Message::Num(Number::Int(2))),
+            )]),
+            Calls::new(vec![Call::invocation("call", Arguments::default())]),
+        ),
+    );
+
+    let parser = Parser::new(r#"sequence(success = 2) { call() }"#).unwrap();
+    assert_eq!(
+        parser.call(0).error(),
+        Some(ParseError::FailedOnValidation(
+            "any lambda invocation except parallel should not have arguments",
+            11
+        ))
+    );
+}
+
 #[test]
 fn decorator() {
     let parser = Parser::new(r#"inverter() call()"#).unwrap();
@@ -155,14 +180,14 @@ fn calls() {
     expect(
         parser.calls(0),
         Calls::new(vec![
-            Call::Lambda(
+            Call::lambda(
                 TreeType::Fallback,
                 Calls::new(vec![
                     Call::invocation("ball_found", Arguments::new(vec![Argument::id("obj")])),
                     Call::invocation("find_ball", Arguments::new(vec![Argument::id("obj")])),
                 ]),
             ),
-            Call::Lambda(
+            Call::lambda(
                 TreeType::Fallback,
                 Calls::new(vec![
                     Call::invocation("close", Arguments::new(vec![Argument::id("obj")])),
@@ -96,3 +96,64 @@ cond ball_found {}
         ]),
     );
 }
+
+#[test]
+fn recovering() {
+    let (file, errors) = Parser::parse_recovering(
+        r#"
+import "std::actions"
+
+root main sequence {
+    first()
+    second(
+}
+
+sequence first {
+    success()
+}
+
+fallback second(a:num) {
+    fail(a))
+    success()
+}
+
+impl third(a:num) }
+impl fourth;
+
+cond fifth(x:num) $ success()
+"#,
+    );
+
+    let names: Vec<String> = file
+        .0
+        .iter()
+        .flat_map(|e| match e {
+            FileEntity::Tree(t) => Some(t.name.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        names,
+        vec!["main", "first", "second", "third", "fourth", "fifth"]
+    );
+
+    let locations: Vec<String> = errors
+        .iter()
+        .map(|e| e.diagnostic().unwrap().location.to_string())
+        .collect();
+    assert_eq!(locations, vec!["4:20", "13:24", "18:19", "21:19"]);
+}
+
+#[test]
+fn recovering_valid() {
+    let (file, errors) = Parser::parse_recovering(
+        r#"
+import "std::actions"
+root main sequence {
+    success()
+}
+"#,
+    );
+    assert_eq!(file.0.len(), 2);
+    assert!(errors.is_empty());
+}
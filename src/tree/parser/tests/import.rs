@@ -55,3 +55,17 @@ fn import_names_alias() {
         ),
     );
 }
+
+#[test]
+fn import_namespace() {
+    let parser = Parser::new(
+        r#"
+    import "nav/common.tree" as nav
+    "#,
+    )
+    .unwrap();
+    expect(
+        parser.import(0),
+        Import::namespace("nav/common.tree", "nav"),
+    );
+}
@@ -1,4 +1,8 @@
-use crate::tree::parser::ast::arg::{MesType, Param, Params};
+use crate::tree::parser::ast::arg::{Argument, ArgumentRhs};
+use crate::tree::parser::ast::arg::{Field, MesType, Param, ParamDir, Params};
+use crate::tree::parser::ast::expr::{BinOp, Expr};
+use crate::tree::parser::ast::message::Message;
+use crate::tree::parser::ast::{ConstDef, EnumDef, StructDef};
 
 use crate::tree::parser::Parser;
 use parsit::test::parser_test::expect;
@@ -30,3 +34,134 @@ fn params() {
         ]),
     );
 }
+
+#[test]
+fn directions() {
+    let parser = Parser::new(r#"(in a:num, out b:any, inout c:string, out:bool)"#).unwrap();
+    expect(
+        parser.params(0),
+        Params::new(vec![
+            Param::new("a", MesType::Num),
+            Param::new("b", MesType::Any).with_dir(ParamDir::Out),
+            Param::new("c", MesType::String).with_dir(ParamDir::InOut),
+            Param::new("out", MesType::Bool),
+        ]),
+    );
+}
+
+#[test]
+fn defaults() {
+    let parser =
+        Parser::new(r#"(a:num = 100, b?:string, out c?:any, d:array<num> = [1, 2])"#).unwrap();
+    expect(
+        parser.params(0),
+        Params::new(vec![
+            Param::new("a", MesType::Num).with_default(Message::int(100)),
+            Param::new("b", MesType::String).optional(),
+            Param::new("c", MesType::Any)
+                .with_dir(ParamDir::Out)
+                .optional(),
+            Param::new("d", MesType::array_of(MesType::Num))
+                .with_default(Message::array(vec![Message::int(1), Message::int(2)])),
+        ]),
+    );
+}
+
+#[test]
+fn schemas() {
+    let parser = Parser::new(r#"(a:array<num>, b:array<array<Cfg>>, c:Cfg)"#).unwrap();
+    expect(
+        parser.params(0),
+        Params::new(vec![
+            Param::new("a", MesType::array_of(MesType::Num)),
+            Param::new(
+                "b",
+                MesType::array_of(MesType::array_of(MesType::Named("Cfg".to_string()))),
+            ),
+            Param::new("c", MesType::Named("Cfg".to_string())),
+        ]),
+    );
+
+    let parser =
+        Parser::new(r#"struct Cfg { tp:string, rate?:num, points:array<Point> }"#).unwrap();
+    expect(
+        parser.struct_def(0),
+        StructDef::new(
+            "Cfg",
+            vec![
+                Field::new("tp", MesType::String),
+                Field::optional("rate", MesType::Num),
+                Field::new(
+                    "points",
+                    MesType::array_of(MesType::Named("Point".to_string())),
+                ),
+            ],
+        ),
+    );
+
+    let parser = Parser::new(r#"struct Empty {}"#).unwrap();
+    expect(parser.struct_def(0), StructDef::new("Empty", vec![]));
+}
+
+#[test]
+fn conform() {
+    let point = MesType::Struct(
+        "Point".to_string(),
+        vec![
+            Field::new("x", MesType::Num),
+            Field::optional("y", MesType::Num),
+        ],
+    );
+    let m = |x: Message| Message::object(vec![("x".to_string(), x)]);
+
+    assert!(m(Message::int(1)).same(&point));
+    assert!(!m(Message::str("1")).same(&point));
+    assert!(!Message::object(vec![]).same(&point));
+    assert_eq!(
+        Message::object(vec![("z".to_string(), Message::int(1))]).conform(&point),
+        Err("the field 'z' is not declared in Point".to_string())
+    );
+    assert!(Message::array(vec![m(Message::int(1))]).same(&MesType::array_of(point.clone())));
+    assert!(Message::array(vec![Message::int(1)]).same(&MesType::array_of(MesType::Num)));
+    assert!(!Message::array(vec![Message::bool(true)]).same(&MesType::array_of(MesType::Num)));
+    assert!(Message::array(vec![Message::bool(true)]).same(&MesType::Array));
+}
+
+#[test]
+fn consts_and_enums() {
+    let parser = Parser::new(r#"const timeout = 1000;"#).unwrap();
+    expect(
+        parser.const_def(0),
+        ConstDef::new("timeout", Message::int(1000)),
+    );
+
+    let parser = Parser::new(r#"const modes = ["auto", "manual"];"#).unwrap();
+    expect(
+        parser.const_def(0),
+        ConstDef::new(
+            "modes",
+            Message::array(vec![Message::str("auto"), Message::str("manual")]),
+        ),
+    );
+
+    let parser = Parser::new(r#"enum Mode { auto, manual, }"#).unwrap();
+    expect(
+        parser.enum_def(0),
+        EnumDef::new("Mode", vec!["auto", "manual"]),
+    );
+
+    let parser = Parser::new(r#"mode = Mode::auto"#).unwrap();
+    expect(
+        parser.arg(0),
+        Argument::assigned(
+            "mode".to_string(),
+            ArgumentRhs::Id("Mode::auto".to_string()),
+        ),
+    );
+
+    let parser = Parser::new(r#"mode == Mode::auto"#).unwrap();
+    expect(
+        parser.expr(0),
+        Expr::binary(Expr::id("mode"), BinOp::Eq, Expr::id("Mode::auto")),
+    );
+}
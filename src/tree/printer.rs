@@ -0,0 +1,680 @@
+use crate::tree::diagnostic::Span;
+use crate::tree::parser::ast::arg::{Argument, ArgumentRhs, Arguments, Params};
+use crate::tree::parser::ast::call::{Call, Calls};
+use crate::tree::parser::ast::message::{Bool, Message, Number};
+use crate::tree::parser::ast::{
+    AstFile, ConstDef, EnumDef, FileEntity, Import, ImportName, StructDef, Tree,
+};
+use crate::tree::parser::lexer::Token;
+use crate::tree::parser::Parser;
+use crate::tree::TreeError;
+use itertools::Itertools;
+use logos::Logos;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+
+/// The canonical printer for the tree language.
+/// It turns the ast back into the source text with the stable indentation and formatting.
+///
+/// # Rules
+///  - every call is placed on a separate line and nested calls are indented
+///  - the arguments are placed on the same line if they fit the width
+///    and do not contain lambdas, otherwise every argument is placed on a separate line
+///  - the definitions are separated with an empty line
+///  - the single lambda or decorator body of the definition is placed on the same line as the header
+///  - the comments and the single empty lines between calls are preserved when the source text is given
+///
+/// The output is idempotent: formatting the formatted text does not change it.
+///
+/// # Example
+/// ```
+/// use forester_rs::tree::printer::Printer;
+///
+/// let text = Printer::default()
+///     .format(r#"root main sequence{ a(1,"b") // call a
+///     b(x=1)}"#)
+///     .unwrap();
+/// assert_eq!(
+///     text,
+///     "root main sequence {\n    a(1, \"b\") // call a\n    b(x = 1)\n}\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Printer {
+    /// the number of spaces for one level of indentation
+    pub indent: usize,
+    /// the max length of the line when the arguments are placed on the same line
+    pub width: usize,
+}
+
+impl Default for Printer {
+    fn default() -> Self {
+        Printer {
+            indent: 4,
+            width: 100,
+        }
+    }
+}
+
+impl Printer {
+    pub fn new(indent: usize, width: usize) -> Self {
+        Printer { indent, width }
+    }
+
+    /// prints the ast. The comments are absent in the ast, thus they are not printed.
+    pub fn print(&self, file: &AstFile) -> String {
+        let mut writer = Writer::new(self, None);
+        writer.file(file);
+        writer.out
+    }
+
+    /// parses and prints the text preserving the comments.
+    pub fn format(&self, src: &str) -> Result<String, TreeError> {
+        let file = Parser::new(src)?.parse()?;
+        let mut writer = Writer::new(self, Some(src));
+        writer.file(&file);
+        Ok(writer.out)
+    }
+}
+
+impl Display for AstFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Printer::default().print(self))
+    }
+}
+
+/// A comment from the source text along with the place of it.
+#[derive(Debug, Clone)]
+struct Comment {
+    text: String,
+    span: Span,
+    // the comment follows some code on the same line
+    trailing: bool,
+}
+
+/// finds the comments in the gaps between the tokens
+fn comments(src: &str) -> VecDeque<Comment> {
+    let mut gaps = vec![];
+    let mut last = 0;
+    for (_, range) in Token::lexer(src).spanned() {
+        gaps.push((last, range.start));
+        last = range.end;
+    }
+    gaps.push((last, src.len()));
+
+    let mut comments = VecDeque::new();
+    for (from, to) in gaps {
+        let gap = &src[from..to];
+        let mut idx = 0;
+        while idx < gap.len() {
+            let rest = &gap[idx..];
+            let len = if rest.starts_with("//") {
+                rest.find(['\r', '\n']).unwrap_or(rest.len())
+            } else if rest.starts_with("/*") {
+                rest.find("*/").map(|e| e + 2).unwrap_or(rest.len())
+            } else {
+                idx += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+                continue;
+            };
+            let start = from + idx;
+            let line_start = src[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            comments.push_back(Comment {
+                text: rest[..len].trim_end().to_string(),
+                span: Span::new(start, start + len),
+                trailing: !src[line_start..start].trim().is_empty(),
+            });
+            idx += len;
+        }
+    }
+    comments
+}
+
+/// The way to separate the next element from the previous one
+#[derive(Clone, Copy, PartialEq)]
+enum Gap {
+    /// always put an empty line
+    Empty,
+    /// put an empty line if the source text has it
+    Preserve,
+    /// never put an empty line
+    None,
+}
+
+struct Writer<'a> {
+    cfg: &'a Printer,
+    src: Option<&'a str>,
+    comments: VecDeque<Comment>,
+    out: String,
+    // the position in the source text right after the last printed element
+    pos: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn new(cfg: &'a Printer, src: Option<&'a str>) -> Self {
+        Writer {
+            cfg,
+            src,
+            comments: src.map(comments).unwrap_or_default(),
+            out: String::new(),
+            pos: 0,
+        }
+    }
+
+    fn pad(&self, level: usize) -> String {
+        " ".repeat(level * self.cfg.indent)
+    }
+
+    fn line(&mut self, level: usize, text: &str) {
+        let pad = self.pad(level);
+        self.out.push_str(format!("{pad}{text}\n").as_str());
+    }
+
+    fn empty_line(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn has_empty_line(&self, from: usize, to: usize) -> bool {
+        match self.src {
+            Some(src) if from < to && to <= src.len() => {
+                let lines: Vec<&str> = src[from..to].split('\n').collect();
+                lines.len() > 2
+                    && lines[1..lines.len() - 1]
+                        .iter()
+                        .any(|l| l.trim().is_empty())
+            }
+            _ => false,
+        }
+    }
+
+    /// moves the position in the source text forward
+    fn passed(&mut self, span: Span) {
+        if !span.is_empty() {
+            self.pos = self.pos.max(span.end);
+        }
+    }
+
+    /// prints the comments that are placed before the position `to`
+    /// and separates the next element according to the gap.
+    fn comments_before(&mut self, to: usize, level: usize, gap: Gap) {
+        self.flush(to, usize::MAX, level, gap)
+    }
+
+    /// prints the comments that are placed before the end of the element
+    /// that is printed on one line, so the comments inside the element are moved above it.
+    fn comments_inside(&mut self, span: Span, level: usize, gap: Gap) {
+        self.flush(span.end.max(span.start), span.start, level, gap)
+    }
+
+    fn flush(&mut self, to: usize, inside_from: usize, level: usize, gap: Gap) {
+        let mut separated = false;
+        while let Some(c) = self.comments.front().filter(|c| c.span.start < to).cloned() {
+            self.comments.pop_front();
+            let trailing = c.trailing && c.span.start < inside_from;
+            if trailing && self.out.ends_with('\n') && !self.out.ends_with("\n\n") {
+                self.out.pop();
+                self.out.push_str(format!(" {}\n", c.text).as_str());
+            } else {
+                if !separated {
+                    self.separate(c.span.start, gap);
+                    separated = true;
+                }
+                self.line(level, c.text.as_str());
+            }
+            self.pos = self.pos.max(c.span.end);
+        }
+        if !separated {
+            self.separate(to.min(inside_from), gap);
+        }
+    }
+
+    fn separate(&mut self, to: usize, gap: Gap) {
+        match gap {
+            Gap::Empty => self.empty_line(),
+            Gap::Preserve if self.has_empty_line(self.pos, to) => self.empty_line(),
+            _ => {}
+        }
+    }
+
+    fn file(&mut self, file: &AstFile) {
+        // the imports, the constants and the declarations of actions are grouped together,
+        // the rest of the definitions are separated with an empty line
+        let group = |e: &FileEntity| match e {
+            FileEntity::Import(_) => 0,
+            FileEntity::Const(_) => 1,
+            FileEntity::Tree(t) if t.tpe.is_action() && t.calls.elems.is_empty() => 2,
+            FileEntity::Tree(_) | FileEntity::Struct(_) | FileEntity::Enum(_) => 3,
+        };
+        let mut prev: Option<u8> = None;
+        for entity in file.0.iter() {
+            let gap = match prev {
+                None => Gap::None,
+                Some(p) if p == group(entity) && p < 3 => Gap::Preserve,
+                _ => Gap::Empty,
+            };
+            match entity {
+                FileEntity::Tree(tree) => self.tree(tree, gap),
+                FileEntity::Import(import) => self.import(import, gap),
+                FileEntity::Struct(def) => self.struct_def(def, gap),
+                FileEntity::Const(def) => self.const_def(def, gap),
+                FileEntity::Enum(def) => self.enum_def(def, gap),
+            }
+            prev = Some(group(entity));
+        }
+        self.comments_before(usize::MAX, 0, Gap::Preserve);
+    }
+
+    fn import(&mut self, import: &Import, gap: Gap) {
+        let span = import.span();
+        self.comments_inside(span, 0, gap);
+        let names: Vec<String> = import
+            .1
+            .iter()
+            .flat_map(|name| match name {
+                ImportName::Id(id) => Some(id.clone()),
+                ImportName::Alias(id, alias) => Some(format!("{id} => {alias}")),
+                ImportName::WholeFile | ImportName::Namespace(_) => None,
+            })
+            .collect();
+        let namespace = import.1.iter().find_map(|name| match name {
+            ImportName::Namespace(ns) => Some(ns),
+            _ => None,
+        });
+        let file = format!("import \"{}\"", import.0);
+        if let Some(ns) = namespace {
+            self.line(0, format!("{file} as {ns}").as_str());
+        } else if names.is_empty() {
+            self.line(0, file.as_str());
+        } else {
+            self.line(0, format!("{file} {{").as_str());
+            let last = names.len() - 1;
+            for (idx, name) in names.iter().enumerate() {
+                let sep = if idx == last { "" } else { "," };
+                self.line(1, format!("{name}{sep}").as_str());
+            }
+            self.line(0, "}");
+        }
+        self.passed(span);
+    }
+
+    fn struct_def(&mut self, def: &StructDef, gap: Gap) {
+        self.comments_inside(def.span, 0, gap);
+        if def.fields.is_empty() {
+            self.line(0, format!("struct {} {{}}", def.name).as_str());
+        } else {
+            self.line(0, format!("struct {} {{", def.name).as_str());
+            let last = def.fields.len() - 1;
+            for (idx, field) in def.fields.iter().enumerate() {
+                let sep = if idx == last { "" } else { "," };
+                self.line(1, format!("{field}{sep}").as_str());
+            }
+            self.line(0, "}");
+        }
+        self.passed(def.span);
+    }
+
+    fn const_def(&mut self, def: &ConstDef, gap: Gap) {
+        self.comments_inside(def.span, 0, gap);
+        let line = format!("const {} = {};", def.name, message(&def.value));
+        self.line(0, line.as_str());
+        self.passed(def.span);
+    }
+
+    fn enum_def(&mut self, def: &EnumDef, gap: Gap) {
+        self.comments_inside(def.span, 0, gap);
+        if def.variants.is_empty() {
+            self.line(0, format!("enum {} {{}}", def.name).as_str());
+        } else {
+            self.line(0, format!("enum {} {{", def.name).as_str());
+            let last = def.variants.len() - 1;
+            for (idx, variant) in def.variants.iter().enumerate() {
+                let sep = if idx == last { "" } else { "," };
+                self.line(1, format!("{variant}{sep}").as_str());
+            }
+            self.line(0, "}");
+        }
+        self.passed(def.span);
+    }
+
+    fn tree(&mut self, tree: &Tree, gap: Gap) {
+        self.comments_before(tree.span.start, 0, gap);
+        let header = format!("{} {}{}", tree.tpe, tree.name, params(&tree.params));
+        let elems = &tree.calls.elems;
+        match elems.as_slice() {
+            [] if tree.tpe.is_action() => self.line(0, format!("{header};").as_str()),
+            [] => self.line(0, format!("{header} {{}}").as_str()),
+            [call] if call.is_lambda() || call.is_decorator() => {
+                self.call(call, 0, format!("{header} ").as_str(), "", Gap::None)
+            }
+            _ => {
+                self.line(0, format!("{header} {{").as_str());
+                self.block(&tree.calls, 1, tree.span);
+                self.line(0, "}");
+            }
+        }
+        self.passed(tree.span);
+    }
+
+    /// prints the calls inside the braces along with the comments
+    /// that are placed before the closing brace of the `parent`.
+    fn block(&mut self, calls: &Calls, level: usize, parent: Span) {
+        for (idx, call) in calls.elems.iter().enumerate() {
+            let gap = if idx == 0 { Gap::None } else { Gap::Preserve };
+            self.call(call, level, "", "", gap);
+        }
+        if !parent.is_empty() {
+            self.comments_before(parent.end, level, Gap::None);
+        }
+    }
+
+    /// prints the call starting with the `prefix` on the first line and ending with the `suffix` on the last line
+    fn call(&mut self, call: &Call, level: usize, prefix: &str, suffix: &str, gap: Gap) {
+        let span = call.span();
+        match call {
+            Call::Invocation(name, args, _) => {
+                let inline = format!("{prefix}{name}({}){suffix}", self.args_inline(args));
+                if self.fits(level, &inline, args, span) {
+                    self.comments_inside(span, level, gap);
+                    self.line(level, inline.as_str());
+                } else {
+                    self.comments_before(span.start, level, gap);
+                    self.line(level, format!("{prefix}{name}(").as_str());
+                    self.args(args, level + 1);
+                    if !span.is_empty() {
+                        self.comments_before(span.end, level + 1, Gap::None);
+                    }
+                    self.line(level, format!("){suffix}").as_str());
+                }
+            }
+            Call::HoInvocation(name, _) => {
+                self.comments_inside(span, level, gap);
+                self.line(level, format!("{prefix}{name}(..){suffix}").as_str());
+            }
+            Call::Lambda(tpe, args, calls, _) => {
+                self.comments_before(span.start, level, gap);
+                let tpe = if args.args.is_empty() {
+                    tpe.to_string()
+                } else {
+                    format!("{tpe}({})", self.args_inline(args))
+                };
+                if calls.elems.is_empty() {
+                    self.line(level, format!("{prefix}{tpe} {{}}{suffix}").as_str());
+                } else {
+                    self.line(level, format!("{prefix}{tpe} {{").as_str());
+                    self.block(calls, level + 1, span);
+                    self.line(level, format!("}}{suffix}").as_str());
+                }
+            }
+            Call::Decorator(tpe, args, child, _) => {
+                self.comments_before(span.start, level, gap);
+                let decor = if args.args.is_empty() {
+                    format!("{prefix}{tpe} ")
+                } else {
+                    format!("{prefix}{tpe}({}) ", self.args_inline(args))
+                };
+                self.call(child, level, decor.as_str(), suffix, Gap::None);
+            }
+        }
+        self.passed(span);
+    }
+
+    /// the arguments can be placed on the same line
+    /// if they fit the width, there are no lambdas and no comments inside
+    fn fits(&self, level: usize, line: &str, args: &Arguments, span: Span) -> bool {
+        let has_lambdas = args.args.iter().any(
+            |a| matches!(a.value(), ArgumentRhs::Call(c) if c.is_lambda() || c.is_decorator()),
+        );
+        let has_comments = !span.is_empty()
+            && self
+                .comments
+                .iter()
+                .any(|c| c.span.start > span.start && c.span.start < span.end);
+        !has_lambdas && !has_comments && level * self.cfg.indent + line.len() <= self.cfg.width
+    }
+
+    fn args(&mut self, args: &Arguments, level: usize) {
+        let last = args.args.len().saturating_sub(1);
+        for (idx, arg) in args.args.iter().enumerate() {
+            let suffix = if idx == last { "" } else { "," };
+            let gap = if idx == 0 { Gap::None } else { Gap::Preserve };
+            let name = arg.name().map(|n| format!("{n} = ")).unwrap_or_default();
+            match arg.value() {
+                ArgumentRhs::Call(call) => self.call(call, level, name.as_str(), suffix, gap),
+                rhs => {
+                    let span = arg.span();
+                    self.comments_before(span.start, level, gap);
+                    self.line(level, format!("{name}{}{suffix}", rhs_inline(rhs)).as_str());
+                    self.passed(span);
+                }
+            }
+        }
+    }
+
+    fn args_inline(&self, args: &Arguments) -> String {
+        args.args.iter().map(arg_inline).join(", ")
+    }
+}
+
+fn params(params: &Params) -> String {
+    if params.params.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "({})",
+            params.params.iter().map(|p| p.to_string()).join(", ")
+        )
+    }
+}
+
+fn arg_inline(arg: &Argument) -> String {
+    match arg {
+        Argument::Assigned(name, rhs, _) => format!("{name} = {}", rhs_inline(rhs)),
+        Argument::Unassigned(rhs, _) => rhs_inline(rhs),
+    }
+}
+
+fn rhs_inline(rhs: &ArgumentRhs) -> String {
+    match rhs {
+        ArgumentRhs::Id(id) => id.clone(),
+        ArgumentRhs::Mes(m) => message(m),
+        ArgumentRhs::Expr(e) => e.to_string(),
+        ArgumentRhs::Call(call) => call_inline(call),
+    }
+}
+
+/// the call on one line. It is used only when the call does not contain lambdas.
+fn call_inline(call: &Call) -> String {
+    match call {
+        Call::Invocation(name, args, _) => {
+            format!("{name}({})", args.args.iter().map(arg_inline).join(", "))
+        }
+        Call::HoInvocation(name, _) => format!("{name}(..)"),
+        Call::Lambda(tpe, args, calls, _) if args.args.is_empty() => {
+            format!(
+                "{tpe} {{ {} }}",
+                calls.elems.iter().map(call_inline).join(" ")
+            )
+        }
+        Call::Lambda(tpe, args, calls, _) => format!(
+            "{tpe}({}) {{ {} }}",
+            args.args.iter().map(arg_inline).join(", "),
+            calls.elems.iter().map(call_inline).join(" ")
+        ),
+        Call::Decorator(tpe, args, child, _) if args.args.is_empty() => {
+            format!("{tpe} {}", call_inline(child))
+        }
+        Call::Decorator(tpe, args, child, _) => format!(
+            "{tpe}({}) {}",
+            args.args.iter().map(arg_inline).join(", "),
+            call_inline(child)
+        ),
+    }
+}
+
+/// prints the message in the form the parser accepts
+pub fn message(m: &Message) -> String {
+    match m {
+        Message::Num(n) => match n {
+            Number::Int(v) => v.to_string(),
+            Number::Float(v) => {
+                // the float always needs the point to be distinguished from the integer
+                let v = format!("{v:?}");
+                if v.contains('.') {
+                    v
+                } else if let Some(idx) = v.find('e') {
+                    format!("{}.0{}", &v[..idx], &v[idx..])
+                } else {
+                    format!("{v}.0")
+                }
+            }
+            Number::Hex(v) if *v >= 0 => format!("{v:#x}"),
+            Number::Binary(v) if *v >= 0 => format!("{v:#b}"),
+            Number::Hex(v) => v.to_string(),
+            Number::Binary(v) => v.to_string(),
+        },
+        Message::String(s) => format!("\"{}\"", s.0),
+        Message::Bool(Bool::True) => "true".to_string(),
+        Message::Bool(Bool::False) => "false".to_string(),
+        Message::Array(elems) => format!("[{}]", elems.iter().map(message).join(", ")),
+        Message::Object(obj) => format!(
+            "{{{}}}",
+            obj.iter()
+                .sorted_by(|(l, _), (r, _)| l.cmp(r))
+                .map(|(k, v)| format!("\"{k}\": {}", message(v)))
+                .join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::parser::ast::message::{Message, Number};
+    use crate::tree::printer::{message, Printer};
+
+    #[test]
+    fn messages() {
+        assert_eq!(message(&Message::float(1.0)), "1.0");
+        assert_eq!(message(&Message::float(1e-7)), "1.0e-7");
+        assert_eq!(message(&Message::Num(Number::Hex(31))), "0x1f");
+        assert_eq!(message(&Message::Num(Number::Binary(5))), "0b101");
+        assert_eq!(
+            message(&Message::object(vec![
+                ("b".to_string(), Message::int(1)),
+                ("a".to_string(), Message::array(vec![Message::bool(true)])),
+            ])),
+            r#"{"a": [true], "b": 1}"#
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let src = r#"
+// the std actions
+import "std::actions"
+import "nav.tree" { move => go , /* stop it */ stop }
+/* the root */
+root main sequence { // always
+    go(1)
+
+    // then
+    retry(3) fallback { stop() }
+    inverter check(a=1,
+    // the second one
+    b= sequence {success()})
+} // end
+"#;
+        let expected = r#"// the std actions
+import "std::actions"
+/* stop it */
+import "nav.tree" {
+    move => go,
+    stop
+}
+
+/* the root */
+root main sequence { // always
+    go(1)
+
+    // then
+    retry(3) fallback {
+        stop()
+    }
+    inverter check(
+        a = 1,
+        // the second one
+        b = sequence {
+            success()
+        }
+    )
+} // end
+"#;
+        let printer = Printer::default();
+        let formatted = printer.format(src).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(printer.format(formatted.as_str()).unwrap(), expected);
+    }
+
+    #[test]
+    fn namespaces() {
+        let src = r#"import   "nav/common.tree"   as nav
+root main nav::approach(nav::Mode::auto)
+"#;
+        let expected = r#"import "nav/common.tree" as nav
+
+root main {
+    nav::approach(nav::Mode::auto)
+}
+"#;
+        let printer = Printer::default();
+        let formatted = printer.format(src).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(printer.format(formatted.as_str()).unwrap(), expected);
+    }
+
+    #[test]
+    fn structs() {
+        let src = r#"struct Empty{}
+impl go(p:array< Point >);
+struct Point{x:num,label ? : string}
+"#;
+        let expected = r#"struct Empty {}
+
+impl go(p:array<Point>);
+
+struct Point {
+    x:num,
+    label?:string
+}
+"#;
+        let printer = Printer::default();
+        assert_eq!(printer.format(src).unwrap(), expected);
+    }
+
+    #[test]
+    fn consts_and_enums() {
+        let src = r#"import "defs.tree"
+const timeout=1000;
+const modes = ["a","b"];
+enum Mode{auto,manual}
+root main go(Mode::auto,timeout)
+"#;
+        let expected = r#"import "defs.tree"
+
+const timeout = 1000;
+const modes = ["a", "b"];
+
+enum Mode {
+    auto,
+    manual
+}
+
+root main {
+    go(Mode::auto, timeout)
+}
+"#;
+        let printer = Printer::default();
+        assert_eq!(printer.format(src).unwrap(), expected);
+        assert_eq!(printer.format(expected).unwrap(), expected);
+    }
+}
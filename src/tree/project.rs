@@ -1,13 +1,23 @@
+pub mod diff;
 pub mod file;
+pub mod graph;
 pub mod imports;
+pub mod libs;
+pub mod lint;
+pub mod typecheck;
 
 use crate::read_file;
 use crate::runtime::action::ActionName;
-use crate::runtime::builder::{builtin, ros_core, ros_nav};
-use crate::tree::parser::ast::{FileEntity, Tree};
+use crate::tree::diagnostic::Diagnostic;
+use crate::tree::parser::ast::arg::{Field, MesType};
+use crate::tree::parser::ast::message::Message;
+use crate::tree::parser::ast::{FileEntity, Import, Key, Tree};
 use crate::tree::parser::Parser;
 use crate::tree::project::file::File;
+use crate::tree::project::imports::ImportMap;
+use crate::tree::project::libs::Libraries;
 use crate::tree::{cerr, TreeError};
+use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
@@ -21,12 +31,14 @@ pub type AliasName = String;
 ///   - `main` is a pointer to the file and definition when the tree is started.
 ///   - `files` is a map of the files
 ///   - `std` is a set of the standard actions
+///   - `libs` is a set of the libraries the files can be imported from like `nav::approach.tree`
 #[derive(Debug, Default, Clone)]
 pub struct Project {
     pub root: PathBuf,
     pub main: (FileName, TreeName),
     pub files: HashMap<FileName, File>,
     pub std: HashSet<ActionName>,
+    pub libs: Libraries,
 }
 
 impl<'a> Project {
@@ -56,48 +68,56 @@ impl<'a> Project {
     ///     - other.tree
     ///
     /// Setting up the root as root_folder allows pulling in the other.tree file.
+    /// The libraries are taken from the manifest `forester.toml` in the root if it is presented.
     pub fn build_with_root(
         main_file: FileName,
         main_call: TreeName,
         root: PathBuf,
     ) -> Result<Project, TreeError> {
-        debug!(
-            target:"ast",
-            "built project with root: {:?}, main file: {} and root definition: {} ",
-            &root, main_file, main_call
-        );
-        let mut project = Project {
-            root: root.clone(),
-            main: ("".to_string(), "".to_string()),
-            files: Default::default(),
-            std: Default::default(),
-        };
-        project.main = (main_file.clone(), main_call);
-        project.parse_file(root, main_file)?;
-        Ok(project)
+        let libs = Libraries::load(&root)?;
+        Project::build_with_libs(main_file, Some(main_call), root, libs)
     }
     /// build the project with the given main file and root.
     /// The root will be found in the main file.
     /// If there are more than one root in the main file, the first one will be used.
+    /// The libraries are taken from the manifest `forester.toml` in the root if it is presented.
     pub fn build(main_file: FileName, root: PathBuf) -> Result<Project, TreeError> {
+        let libs = Libraries::load(&root)?;
+        Project::build_with_libs(main_file, None, root, libs)
+    }
+
+    /// build the project with the given main file, root and libraries.
+    /// If the main call is not set, the first root definition in the main file will be used.
+    pub fn build_with_libs(
+        main_file: FileName,
+        main_call: Option<TreeName>,
+        root: PathBuf,
+        libs: Libraries,
+    ) -> Result<Project, TreeError> {
+        libs.check()?;
         let mut project = Project {
             root: root.clone(),
             main: ("".to_string(), "".to_string()),
             files: Default::default(),
             std: Default::default(),
+            libs,
         };
 
         project.parse_file(root.clone(), main_file.clone())?;
+        project.resolve()?;
 
-        let main_call = project
-            .files
-            .get(main_file.as_str())
-            .and_then(|file| file.definitions.iter().find(|(_name, t)| t.is_root()))
-            .map(|(name, _)| name.to_string())
-            .ok_or(TreeError::IOError(format!(
-                "no root operation in the file {}",
-                main_file.clone()
-            )))?;
+        let main_call = match main_call {
+            Some(main_call) => main_call,
+            None => project
+                .files
+                .get(main_file.as_str())
+                .and_then(|file| file.definitions.iter().find(|(_name, t)| t.is_root()))
+                .map(|(name, _)| name.to_string())
+                .ok_or(TreeError::IOError(format!(
+                    "no root operation in the file {}",
+                    main_file.clone()
+                )))?,
+        };
         debug!(
            target:"ast",
             "built project with root: {:?}, main file: {} and root definition: {} ",
@@ -113,14 +133,23 @@ impl<'a> Project {
     /// If there are some imports to the other files they will not work
     /// unless the imports are absolute,
     pub fn build_from_text(text: String) -> Result<Project, TreeError> {
+        Project::build_from_text_with_libs(text, Libraries::default())
+    }
+
+    /// build the project with the given text and libraries.
+    /// The root will be empty.
+    pub fn build_from_text_with_libs(text: String, libs: Libraries) -> Result<Project, TreeError> {
+        libs.check()?;
         let mut project = Project {
             root: PathBuf::new(),
             main: ("".to_string(), "".to_string()),
             files: Default::default(),
             std: Default::default(),
+            libs,
         };
 
         project.parse_text(text)?;
+        project.resolve()?;
 
         let main_call = project
             .files
@@ -135,15 +164,177 @@ impl<'a> Project {
         Ok(project)
     }
 
+    /// replaces the names of the structs and the enums in the types of the parameters and the fields
+    /// with the declared structs including their fields and the enums including their variants.
+    /// Returns the errors for the names that can not be found and the structs referring to themselves.
+    pub fn resolve_types(&mut self) -> Vec<TreeError> {
+        let mut errors = vec![];
+        let mut params = vec![];
+        let mut fields = vec![];
+        for file in self.files.values().sorted_by_key(|f| f.name.clone()) {
+            let imports = ImportMap::build(file).unwrap_or_default();
+            for tree in file.definitions.values() {
+                let mut resolved = tree.params.clone();
+                for p in resolved.params.iter_mut() {
+                    match self.resolve_type(file, &imports, &p.tpe, &mut vec![], 0) {
+                        Ok(tpe) => p.tpe = tpe,
+                        Err(Some(e)) => errors.push(file.locate(e, tree.span)),
+                        Err(None) => {}
+                    }
+                }
+                params.push((file.name.clone(), tree.name.clone(), resolved));
+            }
+            for def in file.structs.values() {
+                let mut resolved = def.fields.clone();
+                for f in resolved.iter_mut() {
+                    let mut visiting = vec![def.name.clone()];
+                    match self.resolve_type(file, &imports, &f.tpe, &mut visiting, 1) {
+                        Ok(tpe) => f.tpe = tpe,
+                        Err(Some(e)) => errors.push(file.locate(e, def.span)),
+                        Err(None) => {}
+                    }
+                }
+                fields.push((file.name.clone(), def.name.clone(), resolved));
+            }
+        }
+        for (file, tree, resolved) in params {
+            if let Some(tree) = self
+                .files
+                .get_mut(&file)
+                .and_then(|f| f.definitions.get_mut(&tree))
+            {
+                tree.params = resolved;
+            }
+        }
+        for (file, def, resolved) in fields {
+            if let Some(def) = self
+                .files
+                .get_mut(&file)
+                .and_then(|f| f.structs.get_mut(&def))
+            {
+                def.fields = resolved;
+            }
+        }
+        errors
+    }
+
+    /// finds the value of the constant or the variant of the enum like `Mode::auto`
+    /// that is declared in the file or imported into it.
+    /// The qualified names that can not be found are the errors since they can not be the pointers to bb.
+    pub fn find_const(
+        &self,
+        file: &File,
+        imports: &ImportMap,
+        key: &Key,
+    ) -> Result<Option<Message>, TreeError> {
+        if let Some(def) = file.consts.get(key) {
+            return Ok(Some(def.value.clone()));
+        }
+        // the constant can be imported through a namespace like `nav::timeout`
+        if let Some((def, _)) = imports.find_const(key, self)? {
+            return Ok(Some(def.value.clone()));
+        }
+        match key.rsplit_once("::") {
+            Some((name, variant)) => {
+                let def = match file.enums.get(name) {
+                    Some(def) => Some(def),
+                    None => imports
+                        .find_enum(&name.to_string(), self)?
+                        .map(|(def, _)| def),
+                };
+                match def {
+                    Some(def) if def.variants.iter().any(|v| v == variant) => {
+                        Ok(Some(Message::str(variant)))
+                    }
+                    Some(def) => Err(cerr(format!(
+                        "the enum {} does not have the variant {variant}",
+                        def.name
+                    ))),
+                    None => Err(cerr(format!("the enum {name} can not be found"))),
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn resolve(&mut self) -> Result<(), TreeError> {
+        match TreeError::from_errors(self.resolve_types()) {
+            None => Ok(()),
+            Some(e) => Err(e),
+        }
+    }
+
+    /// replaces the names of the structs and the enums with their definitions.
+    /// Only the errors of the given level (`base` is the depth of the nesting) are reported,
+    /// the errors of the nested structs are reported at their definitions
+    /// and the type stays unresolved (`Err(None)`).
+    fn resolve_type(
+        &self,
+        file: &File,
+        imports: &ImportMap,
+        tpe: &MesType,
+        visiting: &mut Vec<Key>,
+        base: usize,
+    ) -> Result<MesType, Option<TreeError>> {
+        match tpe {
+            MesType::Named(name) => {
+                let found = match file.structs.get(name) {
+                    Some(def) => Some((def, &file.name)),
+                    None => imports.find_struct(name, self).map_err(Some)?,
+                };
+                let Some((def, def_file)) = found else {
+                    let enum_def = match file.enums.get(name) {
+                        Some(def) => Some(def),
+                        None => imports
+                            .find_enum(name, self)
+                            .map_err(Some)?
+                            .map(|(def, _)| def),
+                    };
+                    return match enum_def {
+                        Some(def) => Ok(MesType::Enum(def.name.clone(), def.variants.clone())),
+                        None => Err((visiting.len() == base)
+                            .then(|| cerr(format!("the type {name} can not be found")))),
+                    };
+                };
+                if visiting.contains(&def.name) {
+                    return Err((base > 0 && visiting[0] == def.name)
+                        .then(|| cerr(format!("the struct {} refers to itself", def.name))));
+                }
+                let def_file = self.find_file(def_file).map_err(Some)?;
+                let def_imports = ImportMap::build(def_file).unwrap_or_default();
+                visiting.push(def.name.clone());
+                let fields = def
+                    .fields
+                    .iter()
+                    .map(|f| {
+                        self.resolve_type(def_file, &def_imports, &f.tpe, visiting, base)
+                            .map(|tpe| Field { tpe, ..f.clone() })
+                    })
+                    .collect::<Result<Vec<_>, _>>();
+                visiting.pop();
+                Ok(MesType::Struct(def.name.clone(), fields?))
+            }
+            MesType::ArrayOf(elem) => Ok(MesType::array_of(
+                self.resolve_type(file, imports, elem, visiting, base)?,
+            )),
+            tpe => Ok(tpe.clone()),
+        }
+    }
+
     fn parse_text(&mut self, text: String) -> Result<(), TreeError> {
-        let ast_file = Parser::new(text.as_str())?.parse()?;
+        let ast_file = Parser::new(text.as_str())
+            .and_then(|p| p.parse())
+            .map_err(|e| e.in_file("_"))?;
 
-        let mut file = File::new("_".to_string());
+        let mut file = File::new_with_text("_".to_string(), text.clone());
         for ent in ast_file.0.into_iter() {
             match ent {
                 FileEntity::Tree(t) => file.add_def(t)?,
+                FileEntity::Struct(s) => file.add_struct(s)?,
+                FileEntity::Enum(e) => file.add_enum(e)?,
+                FileEntity::Const(c) => file.add_const(c)?,
                 FileEntity::Import(i) => {
-                    self.parse_file(PathBuf::new(), i.f_name().to_string())?;
+                    self.parse_import(PathBuf::new(), &file, &i)?;
                     file.add_import(i)?
                 }
             };
@@ -154,17 +345,23 @@ impl<'a> Project {
     }
 
     fn parse_file(&mut self, root: PathBuf, file: FileName) -> Result<(), TreeError> {
-        let text = file_to_str(root.clone(), file.clone())?;
-        let ast_file = Parser::new(text.as_str())?.parse()?;
-
         if !self.files.contains_key(file.as_str()) {
-            let mut file = File::new(file);
+            let text = file_to_str(root.clone(), &self.libs, file.clone())?;
+            let ast_file = Parser::new(text.as_str())
+                .and_then(|p| p.parse())
+                .map_err(|e| e.in_file(file.as_str()))?;
+            let mut file = File::new_with_text(file, text);
+            // the files importing each other get parsed once
+            self.files.insert(file.name.clone(), file.clone());
 
             for ent in ast_file.0.into_iter() {
                 match ent {
                     FileEntity::Tree(t) => file.add_def(t)?,
+                    FileEntity::Struct(s) => file.add_struct(s)?,
+                    FileEntity::Enum(e) => file.add_enum(e)?,
+                    FileEntity::Const(c) => file.add_const(c)?,
                     FileEntity::Import(i) => {
-                        self.parse_file(root.clone(), i.f_name().to_string())?;
+                        self.parse_import(root.clone(), &file, &i)?;
                         file.add_import(i)?
                     }
                 };
@@ -174,20 +371,38 @@ impl<'a> Project {
         }
         Ok(())
     }
+
+    /// parses the imported file and points at the import statement if the file can not be processed
+    fn parse_import(
+        &mut self,
+        root: PathBuf,
+        file: &File,
+        import: &Import,
+    ) -> Result<(), TreeError> {
+        self.parse_file(root, import.f_name().to_string())
+            .map_err(|e| match e {
+                TreeError::IOError(m) => TreeError::CompileError(
+                    Diagnostic::new(format!(
+                        "the import {} can not be processed: {m}",
+                        import.f_name()
+                    ))
+                    .into(),
+                ),
+                e => e,
+            })
+            .map_err(|e| file.locate(e, import.span()))
+    }
 }
-fn file_to_str(root: PathBuf, file: FileName) -> Result<String, TreeError> {
-    if file.contains("::") {
-        let parts: Vec<_> = file.split("::").collect();
-        if parts.len() != 2 {
-            return Err(TreeError::IOError(format!("invalid file name: {}", file)));
-        } else {
-            match parts.as_slice() {
-                ["std", "actions"] => Ok(builtin::builtin_actions_file()),
-                ["ros", "nav2"] => Ok(ros_nav::ros_actions_file()),
-                ["ros", "core"] => Ok(ros_core::ros_actions_file()),
-                _ => Err(TreeError::IOError(format!("invalid file name: {}", file))),
-            }
-        }
+/// reads the text of the file that is either a module, from a library or relative to the root
+pub(crate) fn file_to_str(
+    root: PathBuf,
+    libs: &Libraries,
+    file: FileName,
+) -> Result<String, TreeError> {
+    if let Some(text) = libs.modules.text(file.as_str()) {
+        Ok(text)
+    } else if let Some(path) = libs.resolve(file.as_str())? {
+        Ok(read_file(&path)?)
     } else {
         let mut path = root;
         path.push(file);
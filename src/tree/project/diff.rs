@@ -0,0 +1,131 @@
+//! The difference between the definitions of two projects.
+//!
+//! The definitions are matched by the file and the name.
+//! The definition that disappears from one file and turns up unchanged in another one is moved.
+//! The expanded trees are compared with [`crate::runtime::rtree::diff`].
+use crate::tree::parser::ast::arg::Params;
+use crate::tree::project::graph::Def;
+use crate::tree::project::{FileName, Project, TreeName};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// The change of a definition
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefChange {
+    Added(Def),
+    Removed(Def),
+    /// The definition is the same but defined in another file
+    Moved {
+        name: TreeName,
+        from: FileName,
+        to: FileName,
+    },
+    /// The parameters of the definition are changed
+    Reparameterised {
+        def: Def,
+        from: Params,
+        to: Params,
+    },
+    /// The type or the body of the definition are changed
+    Modified(Def),
+}
+
+/// The difference between the definitions of the old and the new projects
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProjectDiff {
+    pub changes: Vec<DefChange>,
+}
+
+impl ProjectDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for ProjectDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let params = |p: &Params| p.params.iter().join(", ");
+        let mut lines = self.changes.iter().map(|c| match c {
+            DefChange::Added((file, name)) => format!("+ {name} ({file})"),
+            DefChange::Removed((file, name)) => format!("- {name} ({file})"),
+            DefChange::Moved { name, from, to } => format!("> {name}: {from} -> {to}"),
+            DefChange::Reparameterised {
+                def: (file, name),
+                from,
+                to,
+            } => format!("~ {name} ({file}): ({}) -> ({})", params(from), params(to)),
+            DefChange::Modified((file, name)) => format!("* {name} ({file})"),
+        });
+        f.write_str(lines.join("\n").as_str())
+    }
+}
+
+/// finds the difference between the definitions of the old and the new projects
+pub fn diff(old: &Project, new: &Project) -> ProjectDiff {
+    let defs = |p: &Project| {
+        p.files
+            .values()
+            .flat_map(|f| {
+                f.definitions
+                    .values()
+                    .map(|t| ((f.name.clone(), t.name.clone()), t.clone()))
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+    let old_defs = defs(old);
+    let new_defs = defs(new);
+
+    let mut changes = vec![];
+    let mut removed = vec![];
+    for (def, tree) in old_defs.iter() {
+        match new_defs.get(def) {
+            None => removed.push((def, tree)),
+            Some(new_tree) => {
+                if tree.params != new_tree.params {
+                    changes.push(DefChange::Reparameterised {
+                        def: def.clone(),
+                        from: tree.params.clone(),
+                        to: new_tree.params.clone(),
+                    });
+                }
+                if tree.tpe != new_tree.tpe || tree.calls != new_tree.calls {
+                    changes.push(DefChange::Modified(def.clone()));
+                }
+            }
+        }
+    }
+
+    let mut added = new_defs
+        .iter()
+        .filter(|(def, _)| !old_defs.contains_key(*def))
+        .collect_vec();
+    for (def, tree) in removed {
+        let (file, name) = def;
+        let moved = added.iter().position(|((_, n), t)| n == name && *t == tree);
+        match moved {
+            Some(idx) => {
+                let ((to, _), _) = added.remove(idx);
+                changes.push(DefChange::Moved {
+                    name: name.clone(),
+                    from: file.clone(),
+                    to: to.clone(),
+                });
+            }
+            None => changes.push(DefChange::Removed(def.clone())),
+        }
+    }
+    changes.extend(
+        added
+            .into_iter()
+            .map(|(def, _)| DefChange::Added(def.clone())),
+    );
+    ProjectDiff { changes }
+}
+
+impl Project {
+    /// finds the difference between the definitions of this project and the new one, see [`diff`]
+    pub fn diff(&self, new: &Project) -> ProjectDiff {
+        diff(self, new)
+    }
+}
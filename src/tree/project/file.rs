@@ -1,14 +1,29 @@
-use crate::tree::parser::ast::{Import, ImportName, Tree};
+use crate::tree::diagnostic::{Diagnostic, Location, Span};
+use crate::tree::parser::ast::{ConstDef, EnumDef, Import, ImportName, Key, StructDef, Tree};
 use crate::tree::project::{FileName, TreeName};
-use crate::tree::TreeError;
+use crate::tree::{cerr, TreeError};
+use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 
 /// The entity represents a file on the disk
+/// ## Structure
+///   - `imports` is a map of the imported files and the names
+///   - `definitions` is a map of the trees defined in the file
+///   - `structs` is a map of the struct types declared in the file
+///   - `enums` is a map of the enum types declared in the file
+///   - `consts` is a map of the named constants declared in the file
+///   - `import_spans` keeps the place of the first import statement for every imported file
+///   - `text` is the source text that is used to point out the errors
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct File {
     pub name: String,
     pub imports: HashMap<FileName, HashSet<ImportName>>,
     pub definitions: HashMap<TreeName, Tree>,
+    pub structs: HashMap<Key, StructDef>,
+    pub enums: HashMap<Key, EnumDef>,
+    pub consts: HashMap<Key, ConstDef>,
+    pub import_spans: HashMap<FileName, Span>,
+    pub text: String,
 }
 
 impl File {
@@ -17,10 +32,34 @@ impl File {
             name,
             imports: Default::default(),
             definitions: Default::default(),
+            structs: Default::default(),
+            enums: Default::default(),
+            consts: Default::default(),
+            import_spans: Default::default(),
+            text: Default::default(),
         }
     }
+    pub fn new_with_text(name: FileName, text: String) -> Self {
+        File {
+            text,
+            ..File::new(name)
+        }
+    }
+
+    /// the location of the given span in this file
+    pub fn location(&self, span: Span) -> Location {
+        Location::new(Some(self.name.clone()), Some(span)).resolve(self.text.as_str())
+    }
+
+    /// attaches this file and the given span to the error if the error does not point anywhere yet
+    pub fn locate(&self, err: TreeError, span: Span) -> TreeError {
+        err.locate(self.name.as_str(), span, self.text.as_str())
+    }
 
     pub fn add_import(&mut self, import: Import) -> Result<(), TreeError> {
+        self.import_spans
+            .entry(import.0.clone())
+            .or_insert(import.span());
         self.imports
             .entry(import.0.clone())
             .and_modify(|names| names.extend(import.1.clone()))
@@ -34,10 +73,79 @@ impl File {
                 Ok(())
             }
 
-            Some(_) => Err(TreeError::ParseError(format!(
-                "the tree '{}' is already presented",
-                tree.name
-            ))),
+            Some(prev) => Err(TreeError::ParseError(
+                Diagnostic::new(format!("the tree '{}' is already presented", tree.name))
+                    .in_file(self.name.as_str())
+                    .with_span(tree.span)
+                    .resolve(self.text.as_str())
+                    .with_note(
+                        format!("the first definition of '{}'", prev.name),
+                        self.location(prev.span),
+                    )
+                    .into(),
+            )),
+        }
+    }
+
+    pub fn add_struct(&mut self, def: StructDef) -> Result<(), TreeError> {
+        let prev = self
+            .structs
+            .get(&def.name)
+            .map(|prev| prev.span)
+            .or(self.enums.get(&def.name).map(|prev| prev.span));
+        match prev {
+            None => {
+                self.structs.insert(def.name.clone(), def);
+                Ok(())
+            }
+            Some(prev) => Err(self.duplicate("type", &def.name, def.span, prev)),
         }
     }
+
+    /// the enums share the names with the structs since both of them are types
+    pub fn add_enum(&mut self, def: EnumDef) -> Result<(), TreeError> {
+        let prev = self
+            .enums
+            .get(&def.name)
+            .map(|prev| prev.span)
+            .or(self.structs.get(&def.name).map(|prev| prev.span));
+        if let Some(prev) = prev {
+            return Err(self.duplicate("type", &def.name, def.span, prev));
+        }
+        if let Some(v) = def.variants.iter().duplicates().next() {
+            return Err(self.locate(
+                cerr(format!(
+                    "the variant '{v}' of the enum '{}' is presented twice",
+                    def.name
+                )),
+                def.span,
+            ));
+        }
+        self.enums.insert(def.name.clone(), def);
+        Ok(())
+    }
+
+    pub fn add_const(&mut self, def: ConstDef) -> Result<(), TreeError> {
+        match self.consts.get(&def.name) {
+            None => {
+                self.consts.insert(def.name.clone(), def);
+                Ok(())
+            }
+            Some(prev) => Err(self.duplicate("constant", &def.name, def.span, prev.span)),
+        }
+    }
+
+    fn duplicate(&self, kind: &str, name: &Key, span: Span, prev: Span) -> TreeError {
+        TreeError::ParseError(
+            Diagnostic::new(format!("the {kind} '{name}' is already presented"))
+                .in_file(self.name.as_str())
+                .with_span(span)
+                .resolve(self.text.as_str())
+                .with_note(
+                    format!("the first declaration of '{name}'"),
+                    self.location(prev),
+                )
+                .into(),
+        )
+    }
 }
@@ -0,0 +1,148 @@
+//! The graph of the invocations between the definitions of the project.
+//!
+//! The runtime tree expands every invocation eagerly,
+//! thus the definitions invoking themselves directly or through the other ones
+//! are found in the graph before the expansion to be reported along with the whole chain.
+use crate::tree::diagnostic::Span;
+use crate::tree::parser::ast::call::Call;
+use crate::tree::project::file::File;
+use crate::tree::project::imports::ImportMap;
+use crate::tree::project::{FileName, Project, TreeName};
+use crate::tree::{cerr, TreeError};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+/// The definition of the tree along with the file it is defined in
+pub type Def = (FileName, TreeName);
+
+/// The invocations between the definitions of the project.
+/// The calls passed as the arguments are considered invoked by the tree they are written down in
+/// since they get expanded along with it.
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    edges: HashMap<Def, Vec<(Def, Span)>>,
+}
+
+impl CallGraph {
+    /// builds the graph resolving the calls the same way the runtime tree does.
+    /// The calls that can not be resolved are skipped since they are reported by the type checking.
+    pub fn build(project: &Project) -> Self {
+        let mut edges = HashMap::new();
+        for file in project.files.values() {
+            let imports = ImportMap::build(file).unwrap_or_default();
+            for tree in file.definitions.values() {
+                let callees = tree
+                    .calls
+                    .flatten()
+                    .into_iter()
+                    .filter_map(|call| match call {
+                        Call::Invocation(key, _, span) => {
+                            resolve(project, file, &imports, key).map(|def| (def, *span))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                edges.insert((file.name.clone(), tree.name.clone()), callees);
+            }
+        }
+        CallGraph { edges }
+    }
+
+    /// the definitions invoked by the given one along with the places of the calls
+    pub fn callees(&self, def: &Def) -> &[(Def, Span)] {
+        self.edges.get(def).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// the definitions that can be invoked starting from the given one including itself
+    pub fn reachable(&self, from: &Def) -> HashSet<Def> {
+        let mut reached = HashSet::new();
+        let mut queue = vec![from.clone()];
+        while let Some(def) = queue.pop() {
+            if reached.insert(def.clone()) {
+                queue.extend(self.callees(&def).iter().map(|(d, _)| d.clone()));
+            }
+        }
+        reached
+    }
+
+    /// finds the chain of the invocations starting from the given definition
+    /// that comes back to one of the definitions of the chain.
+    /// Returns the cycle that starts and ends with the same definition
+    /// where every definition goes along with the place of the call invoking it.
+    pub fn find_cycle(&self, from: &Def) -> Option<Vec<(Def, Span)>> {
+        self.visit(from, Span::default(), &mut vec![], &mut HashSet::new())
+    }
+
+    fn visit(
+        &self,
+        def: &Def,
+        span: Span,
+        path: &mut Vec<(Def, Span)>,
+        done: &mut HashSet<Def>,
+    ) -> Option<Vec<(Def, Span)>> {
+        if done.contains(def) {
+            return None;
+        }
+        if let Some(pos) = path.iter().position(|(d, _)| d == def) {
+            let mut cycle = path[pos..].to_vec();
+            cycle.push((def.clone(), span));
+            return Some(cycle);
+        }
+        path.push((def.clone(), span));
+        for (callee, span) in self.callees(def) {
+            if let Some(cycle) = self.visit(callee, *span, path, done) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.insert(def.clone());
+        None
+    }
+}
+
+/// finds the definition the call refers to:
+/// the definitions of the file go first and then the imports.
+pub(crate) fn resolve(
+    project: &Project,
+    file: &File,
+    imports: &ImportMap,
+    key: &TreeName,
+) -> Option<Def> {
+    if file.definitions.contains_key(key) {
+        return Some((file.name.clone(), key.clone()));
+    }
+    imports
+        .find(key, project)
+        .ok()
+        .map(|(tree, f)| (f.clone(), tree.name.clone()))
+}
+
+impl Project {
+    /// checks that the trees reachable from the main root do not invoke themselves
+    /// directly or through the other trees since they can not be expanded.
+    ///
+    /// # Errors
+    /// The error names the whole chain of the invocations and points at the call closing it.
+    pub fn check_recursion(&self) -> Result<(), TreeError> {
+        let graph = CallGraph::build(self);
+        let Some(cycle) = graph.find_cycle(&self.main) else {
+            return Ok(());
+        };
+        let chain = cycle
+            .iter()
+            .map(|((file, tree), _)| format!("{tree} ({file})"))
+            .join(" -> ");
+        let ((_, name), _) = &cycle[0];
+        let err = cerr(format!(
+            "the tree {name} invokes itself recursively: {chain}. \
+             Set the recursion depth to expand it a limited number of times"
+        ));
+        // the last call closing the cycle is written down in the previous definition
+        let ((caller, _), _) = &cycle[cycle.len() - 2];
+        let (_, span) = &cycle[cycle.len() - 1];
+        match self.files.get(caller) {
+            Some(file) => Err(file.locate(err, *span)),
+            None => Err(err),
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use crate::tree::parser::ast::{ImportName, Tree};
+use crate::tree::parser::ast::{ConstDef, EnumDef, ImportName, Key, StructDef, Tree};
 use crate::tree::project::{AliasName, File, FileName, Project, TreeName};
 use crate::tree::{cerr, TreeError};
+use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 
 /// reordering by tree definition
@@ -9,54 +10,80 @@ pub struct ImportMap {
     pub aliases: HashMap<AliasName, TreeName>,
     pub trees: HashMap<TreeName, FileName>,
     pub files: HashSet<FileName>,
+    pub namespaces: HashMap<AliasName, FileName>,
 }
 
 impl ImportMap {
-    /// processes the imports checking there is no crossing between aliases and definitions
+    /// processes the imports checking there is no crossing between aliases, namespaces and definitions
     /// ## Note
-    /// For now, when the import of the whole file there is no validations on crossings and other things.
-    /// Thus, better off to perform imports only for the used definitions.
+    /// The crossings of the definitions from the whole file imports are found only when the name is used.
+    /// Thus, better off to perform imports only for the used definitions or through a namespace.
     pub fn build(file: &File) -> Result<Self, TreeError> {
         let mut map = ImportMap::default();
-        for (file, items) in &file.imports {
-            for item in items {
-                match item {
-                    ImportName::Id(v) => {
-                        if map.trees.get(v).filter(|f| f != &file).is_some() {
-                            return Err(cerr(format!("the import call {v} is presented twice from several different files")));
-                        }
-                        if map.aliases.get(v).is_some() {
-                            return Err(cerr(format!("the import call {v} is presented as alias")));
-                        }
-                        map.trees.insert(v.to_string(), file.to_string());
+        for (imported, items) in &file.imports {
+            let span = file.import_spans.get(imported).copied().unwrap_or_default();
+            map.add(imported, items).map_err(|e| file.locate(e, span))?;
+        }
+
+        Ok(map)
+    }
+
+    /// adds the names imported from the file
+    fn add(&mut self, file: &FileName, items: &HashSet<ImportName>) -> Result<(), TreeError> {
+        for item in items {
+            match item {
+                ImportName::Id(v) => {
+                    if self.trees.get(v).filter(|f| f != &file).is_some() {
+                        return Err(cerr(format!(
+                            "the import call {v} is presented twice from several different files"
+                        )));
+                    }
+                    if self.aliases.get(v).is_some() {
+                        return Err(cerr(format!("the import call {v} is presented as alias")));
                     }
-                    ImportName::Alias(id, alias) => {
-                        if map.aliases.get(alias).filter(|idt| *idt != id).is_some() {
-                            return Err(cerr(format!(
-                                "the import alias {alias} is already defined for another call "
-                            )));
-                        }
-                        map.aliases.insert(alias.to_string(), id.to_string());
-                        map.trees.insert(id.to_string(), file.to_string());
+                    self.trees.insert(v.to_string(), file.to_string());
+                }
+                ImportName::Alias(id, alias) => {
+                    if self.aliases.get(alias).filter(|idt| *idt != id).is_some() {
+                        return Err(cerr(format!(
+                            "the import alias {alias} is already defined for another call "
+                        )));
                     }
-                    ImportName::WholeFile => {
-                        map.files.insert(file.to_string());
+                    self.aliases.insert(alias.to_string(), id.to_string());
+                    self.trees.insert(id.to_string(), file.to_string());
+                }
+                ImportName::WholeFile => {
+                    self.files.insert(file.to_string());
+                }
+                ImportName::Namespace(ns) => {
+                    if self.namespaces.get(ns).filter(|f| *f != file).is_some() {
+                        return Err(cerr(format!(
+                            "the namespace {ns} is already defined for another file"
+                        )));
                     }
+                    self.namespaces.insert(ns.to_string(), file.to_string());
                 }
             }
         }
-
-        Ok(map)
+        Ok(())
     }
 
-    /// find the tree in the project considering the aliases and the definitions
+    /// find the tree in the project considering the aliases, the namespaces and the definitions
     pub fn find<'a>(
         &'a self,
         key: &TreeName,
         project: &'a Project,
     ) -> Result<(&'a Tree, &'a FileName), TreeError> {
+        // try to find by namespace
+        if let Some((file, id)) = self.qualified(key) {
+            project
+                .find_tree(file, &id.to_string())
+                .map(|t| (t, file))
+                .ok_or(cerr(format!(
+                    "the call {key} can not be found in the file {file} "
+                )))
         // try to find by name
-        if let Some(file) = self.trees.get(key) {
+        } else if let Some(file) = self.trees.get(key) {
             project
                 .find_tree(file, key)
                 .map(|t| (t, file))
@@ -77,14 +104,110 @@ impl ImportMap {
                 )))
         } else {
             // try to find bluntly everywhere. Probably this is not the best idea
-            self.files
-                .iter()
-                .flat_map(|f| project.files.get(f))
-                .find(|f| f.definitions.contains_key(key))
-                .and_then(|f| f.definitions.get(key).map(|t| (t, &f.name)))
+            self.in_files(key, project, |f| &f.definitions)?
                 .ok_or(cerr(format!(
                     "the call {key} can not be found among the file in the project"
                 )))
         }
     }
+
+    /// find the tree like `find` but the absence of the tree is not an error.
+    /// Only the ambiguous names among the imported files are reported.
+    pub fn lookup<'a>(
+        &'a self,
+        key: &TreeName,
+        project: &'a Project,
+    ) -> Result<Option<(&'a Tree, &'a FileName)>, TreeError> {
+        self.find_decl(key, project, |f| &f.definitions)
+    }
+
+    /// find the struct in the project considering the aliases and the imported files
+    pub fn find_struct<'a>(
+        &'a self,
+        key: &Key,
+        project: &'a Project,
+    ) -> Result<Option<(&'a StructDef, &'a FileName)>, TreeError> {
+        self.find_decl(key, project, |f| &f.structs)
+    }
+
+    /// find the enum in the project considering the aliases and the imported files
+    pub fn find_enum<'a>(
+        &'a self,
+        key: &Key,
+        project: &'a Project,
+    ) -> Result<Option<(&'a EnumDef, &'a FileName)>, TreeError> {
+        self.find_decl(key, project, |f| &f.enums)
+    }
+
+    /// find the constant in the project considering the aliases and the imported files
+    pub fn find_const<'a>(
+        &'a self,
+        key: &Key,
+        project: &'a Project,
+    ) -> Result<Option<(&'a ConstDef, &'a FileName)>, TreeError> {
+        self.find_decl(key, project, |f| &f.consts)
+    }
+
+    fn find_decl<'a, T, F>(
+        &'a self,
+        key: &Key,
+        project: &'a Project,
+        decls: F,
+    ) -> Result<Option<(&'a T, &'a FileName)>, TreeError>
+    where
+        F: Fn(&'a File) -> &'a HashMap<Key, T>,
+    {
+        let named = match self.qualified(key) {
+            Some((file, id)) => Some((id, file)),
+            None => self.trees.get(key).map(|file| (key.as_str(), file)).or(self
+                .aliases
+                .get(key)
+                .and_then(|id| self.trees.get(id).map(|file| (id.as_str(), file)))),
+        };
+        match named {
+            Some((id, file)) => Ok(project
+                .files
+                .get(file)
+                .and_then(|f| decls(f).get(id))
+                .map(|def| (def, file))),
+            None => self.in_files(key, project, decls),
+        }
+    }
+
+    /// splits the name like `nav::approach` into the file imported as the namespace `nav` and the rest.
+    /// Returns None if the name is not qualified or the prefix is not a namespace
+    /// since it can be a variant of the enum like `Mode::auto`.
+    fn qualified<'k>(&self, key: &'k str) -> Option<(&FileName, &'k str)> {
+        key.split_once("::")
+            .and_then(|(ns, rest)| self.namespaces.get(ns).map(|file| (file, rest)))
+    }
+
+    /// searches the definition among the files imported entirely.
+    /// The definition presented in several files is an error since it is not clear what to pick.
+    fn in_files<'a, T, F>(
+        &'a self,
+        key: &Key,
+        project: &'a Project,
+        decls: F,
+    ) -> Result<Option<(&'a T, &'a FileName)>, TreeError>
+    where
+        F: Fn(&'a File) -> &'a HashMap<Key, T>,
+    {
+        let found: Vec<(&T, &FileName)> = self
+            .files
+            .iter()
+            .sorted()
+            .flat_map(|f| project.files.get(f))
+            .filter_map(|f| decls(f).get(key).map(|def| (def, &f.name)))
+            .collect();
+        match found.as_slice() {
+            [] => Ok(None),
+            [def] => Ok(Some(*def)),
+            _ => Err(cerr(format!(
+                "the name {key} is ambiguous since it is defined in the imported files {}. \
+                 Import it by name or through a namespace",
+                found.iter().map(|(_, f)| f).join(", ")
+            ))),
+        }
+    }
 }
@@ -0,0 +1,193 @@
+use crate::runtime::builder::modules::StdModules;
+use crate::tree::{cerr, TreeError};
+use itertools::Itertools;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// the manifest that is looked up in the root of the project
+pub const MANIFEST: &str = "forester.toml";
+
+/// The libraries of the trees that are shared between several projects.
+/// The files of the library are imported with the name of the library like `import "nav::approach.tree"`.
+///
+/// The library is found either by the explicit mapping of the name to the folder
+/// or as the folder with the same name in one of the search paths.
+/// The explicit mapping takes precedence over the search paths.
+/// The modules registered from the code like `std::actions` take precedence over both.
+///
+/// # Manifest
+/// The libraries can be set in the file `forester.toml` in the root of the project.
+/// The relative paths relate to the folder of the manifest.
+/// ```toml
+/// search_paths = ["../shared", "/opt/forester/libs"]
+///
+/// [libraries]
+/// nav = "../robots/common/nav"
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Libraries {
+    /// the folders where the libraries are looked up by name
+    pub search_paths: Vec<PathBuf>,
+    /// the names of the libraries along with their folders
+    pub libraries: HashMap<String, PathBuf>,
+    /// the virtual files along with the implementations of their actions
+    #[serde(skip)]
+    pub modules: StdModules,
+}
+
+impl Libraries {
+    /// loads the manifest from the root of the project if it is presented
+    pub fn load(root: &Path) -> Result<Self, TreeError> {
+        let manifest = root.join(MANIFEST);
+        if manifest.is_file() {
+            Libraries::from_manifest(&manifest)
+        } else {
+            Ok(Libraries::default())
+        }
+    }
+
+    /// reads the manifest and resolves the relative paths against the folder of the manifest
+    pub fn from_manifest(manifest: &Path) -> Result<Self, TreeError> {
+        let text = fs::read_to_string(manifest)
+            .map_err(|e| TreeError::IOError(format!("error:{e}, file:{}", manifest.display())))?;
+        let libs: Libraries = toml::from_str(text.as_str()).map_err(|e| {
+            cerr(format!(
+                "the manifest {} can not be parsed: {}",
+                manifest.display(),
+                e.message()
+            ))
+        })?;
+        let libs = libs.relative_to(manifest.parent().unwrap_or(Path::new("")));
+        libs.check()?;
+        Ok(libs)
+    }
+
+    /// adds the folder to look up the libraries in
+    pub fn search_path(&mut self, path: PathBuf) {
+        self.search_paths.push(path);
+    }
+
+    /// maps the name of the library to the folder
+    pub fn library(&mut self, name: &str, dir: PathBuf) {
+        self.libraries.insert(name.to_string(), dir);
+    }
+
+    /// merges the libraries set explicitly over the ones from the manifest.
+    /// The modules are taken from the explicit ones since the manifest can not set them.
+    pub fn merge(mut self, other: Libraries) -> Self {
+        self.search_paths.extend(other.search_paths);
+        self.libraries.extend(other.libraries);
+        self.modules = other.modules;
+        self
+    }
+
+    /// checks that the names of the libraries do not clash with the modules
+    pub fn check(&self) -> Result<(), TreeError> {
+        for name in self.libraries.keys().sorted() {
+            let prefix = format!("{name}::");
+            if let Some(m) = self
+                .modules
+                .names()
+                .into_iter()
+                .find(|m| m.starts_with(&prefix))
+            {
+                return Err(cerr(format!(
+                    "the library name {name} is reserved for the module {m}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn relative_to(self, base: &Path) -> Self {
+        Libraries {
+            search_paths: self
+                .search_paths
+                .into_iter()
+                .map(|p| base.join(p))
+                .collect(),
+            libraries: self
+                .libraries
+                .into_iter()
+                .map(|(name, dir)| (name, base.join(dir)))
+                .collect(),
+            modules: self.modules,
+        }
+    }
+
+    /// finds the path of the file imported from the library like `nav::approach.tree`.
+    /// Returns None if the file is not from a library or it is a module.
+    ///
+    /// # Errors
+    /// The library that can not be found or is found in several search paths.
+    pub fn resolve(&self, file: &str) -> Result<Option<PathBuf>, TreeError> {
+        let Some((lib, rest)) = file
+            .split_once("::")
+            .filter(|_| !self.modules.contains(file))
+        else {
+            return Ok(None);
+        };
+        if let Some(dir) = self.libraries.get(lib) {
+            return Ok(Some(dir.join(rest)));
+        }
+        let found: Vec<PathBuf> = self
+            .search_paths
+            .iter()
+            .map(|p| p.join(lib).join(rest))
+            .filter(|p| p.is_file())
+            .collect();
+        match found.as_slice() {
+            [] => Err(cerr(format!(
+                "the library {lib} can not be found in the search paths [{}]",
+                display(&self.search_paths)
+            ))),
+            [path] => Ok(Some(path.clone())),
+            _ => Err(cerr(format!(
+                "the import {file} is resolved ambiguously to [{}]. \
+                 Map the library {lib} to the folder explicitly",
+                display(&found)
+            ))),
+        }
+    }
+}
+
+fn display(paths: &[PathBuf]) -> String {
+    paths.iter().map(|p| p.display().to_string()).join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::project::libs::Libraries;
+    use std::path::PathBuf;
+
+    #[test]
+    fn manifest() {
+        let libs: Libraries = toml::from_str(
+            r#"
+            search_paths = ["shared"]
+            [libraries]
+            nav = "../nav"
+            "#,
+        )
+        .unwrap();
+        let libs = libs.relative_to(&PathBuf::from("/robot"));
+        assert_eq!(libs.search_paths, vec![PathBuf::from("/robot/shared")]);
+        assert_eq!(
+            libs.resolve("nav::approach.tree").unwrap(),
+            Some(PathBuf::from("/robot/../nav/approach.tree"))
+        );
+        assert_eq!(libs.resolve("approach.tree").unwrap(), None);
+        assert!(libs.resolve("arm::grasp.tree").is_err());
+    }
+
+    #[test]
+    fn reserved() {
+        let mut libs = Libraries::default();
+        libs.library("ros", PathBuf::from("ros"));
+        assert!(libs.check().is_err());
+        assert_eq!(libs.resolve("ros::nav2").unwrap(), None);
+    }
+}
@@ -0,0 +1,528 @@
+//! The lint pass of the project.
+//!
+//! Unlike the type checking, the lints do not prevent the tree from running
+//! but point out the places that are likely to be mistakes:
+//!  - the definitions that are never invoked from the main root
+//!  - the imported names, aliases, namespaces and files that are never used
+//!  - the parameters that are never referenced in the body of the tree
+//!  - the aliases that are shadowed by the definitions of the file or shadow the definitions of the files imported entirely
+//!  - the children of a fallback following the unconditional `success()` that are never ticked
+//!  - the actions of the runtime tree that do not have a registered implementation
+//!
+//! Every lint has a level that turns it off, keeps it as a warning or denies it.
+//! The files of the libraries and the modules like `nav::approach.tree` are not linted
+//! apart from the implementations of their actions.
+use crate::runtime::action::ActionName;
+use crate::runtime::rtree::rnode::{RNode, RNodeName};
+use crate::runtime::rtree::RuntimeTreeStarter;
+use crate::tree::diagnostic::{LineCol, Span};
+use crate::tree::parser::ast::arg::{ArgumentRhs, MesType};
+use crate::tree::parser::ast::call::{Call, Calls};
+use crate::tree::parser::ast::{ImportName, Key, Tree, TreeType};
+use crate::tree::project::file::File;
+use crate::tree::project::graph::{resolve, CallGraph, Def};
+use crate::tree::project::imports::ImportMap;
+use crate::tree::project::{FileName, Project, TreeName};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use strum_macros::{Display, EnumString};
+
+/// The kinds of the problems the lint pass looks for.
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Deserialize, Serialize)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Lint {
+    /// the definition is never invoked from the main root
+    UnusedDefinition,
+    /// the imported name, alias, namespace or file is never used
+    UnusedImport,
+    /// the parameter is never referenced in the body of the tree
+    UnusedParam,
+    /// the alias is shadowed by a definition of the file
+    /// or shadows a definition of a file imported entirely
+    ShadowedAlias,
+    /// the child of a fallback is never ticked since one of the previous children is `success()`
+    UnreachableChild,
+    /// the action does not have a registered implementation
+    UnimplementedAction,
+}
+
+/// The level of the lint
+#[derive(
+    Display, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, EnumString, Deserialize, Serialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    /// the lint is turned off
+    Allow,
+    #[default]
+    Warn,
+    /// the lint is reported as an error, see [`LintReport::is_denied`]
+    Deny,
+}
+
+/// The levels of the lints. The lints that are not set explicitly get the default level.
+///
+/// # Example
+/// ```
+/// use forester_rs::tree::project::lint::{Level, Lint, LintConfig};
+///
+/// let config = LintConfig::default()
+///     .with(Lint::UnusedParam, Level::Allow)
+///     .with(Lint::UnimplementedAction, Level::Deny);
+/// assert_eq!(config.level(&Lint::UnusedImport), Level::Warn);
+/// assert_eq!(config.level(&Lint::UnusedParam), Level::Allow);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LintConfig {
+    pub default: Level,
+    pub levels: HashMap<Lint, Level>,
+}
+
+impl LintConfig {
+    pub fn with(mut self, lint: Lint, level: Level) -> Self {
+        self.levels.insert(lint, level);
+        self
+    }
+    pub fn level(&self, lint: &Lint) -> Level {
+        self.levels.get(lint).copied().unwrap_or(self.default)
+    }
+}
+
+/// The found problem along with the place in the file.
+/// Both the line and the column start from 1.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintWarning {
+    pub lint: Lint,
+    pub level: Level,
+    pub message: String,
+    pub file: FileName,
+    pub span: Span,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}:{}:{}: {}",
+            self.level, self.lint, self.file, self.line, self.col, self.message
+        )
+    }
+}
+
+/// The warnings of the lint pass ordered by the place in the files.
+///
+/// # Example
+/// The report turns into json for the tools like CI:
+/// ```json
+/// {
+///   "warnings": [
+///     {
+///       "lint": "unused_param",
+///       "level": "warn",
+///       "message": "the parameter timeout of the tree approach is never used",
+///       "file": "main.tree",
+///       "span": { "start": 42, "end": 97 },
+///       "line": 4,
+///       "col": 1
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LintReport {
+    pub warnings: Vec<LintWarning>,
+}
+
+impl LintReport {
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+    /// at least one of the found problems is denied in the config
+    pub fn is_denied(&self) -> bool {
+        self.warnings.iter().any(|w| w.level == Level::Deny)
+    }
+    /// the warnings of the given lint
+    pub fn of(&self, lint: Lint) -> Vec<&LintWarning> {
+        self.warnings.iter().filter(|w| w.lint == lint).collect()
+    }
+    pub fn extend(&mut self, other: LintReport) {
+        self.warnings.extend(other.warnings);
+        self.sort();
+    }
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+    fn sort(&mut self) {
+        self.warnings
+            .sort_by(|l, r| (&l.file, l.span.start).cmp(&(&r.file, r.span.start)));
+    }
+}
+
+impl Display for LintReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for w in self.warnings.iter() {
+            writeln!(f, "{w}")?;
+        }
+        Ok(())
+    }
+}
+
+/// runs the lints over the definitions and the imports of the project.
+pub fn check(project: &Project, config: &LintConfig) -> LintReport {
+    let mut linter = Linter::new(project, config);
+    linter.unused_definitions();
+    for file in linter.own_files() {
+        linter.unused_imports(file);
+        linter.shadowed_aliases(file);
+        for tree in file.definitions.values().sorted_by_key(|t| t.span.start) {
+            linter.unused_params(file, tree);
+            linter.unreachable_children(file, tree);
+        }
+    }
+    linter.finish()
+}
+
+/// runs the lint over the actions of the runtime tree
+/// looking for the ones that are neither registered nor provided by the modules.
+pub fn check_actions(
+    project: &Project,
+    starter: &RuntimeTreeStarter,
+    registered: &HashSet<ActionName>,
+    config: &LintConfig,
+) -> LintReport {
+    let mut linter = Linter::new(project, config);
+    let actions = starter
+        .tree
+        .nodes
+        .values()
+        .filter_map(|node| match node {
+            RNode::Leaf(RNodeName::Name(name, file) | RNodeName::Alias(name, _, file), _) => {
+                Some((name.clone(), file.clone()))
+            }
+            _ => None,
+        })
+        .unique()
+        .sorted();
+    for (name, file_name) in actions {
+        if registered.contains(&name)
+            || starter
+                .std_actions
+                .contains(&(name.clone(), file_name.clone()))
+        {
+            continue;
+        }
+        if let Some(file) = project.files.get(&file_name) {
+            let span = file
+                .definitions
+                .get(&name)
+                .map(|t| t.span)
+                .unwrap_or_default();
+            linter.warn(
+                Lint::UnimplementedAction,
+                file,
+                span,
+                format!("the action {name} does not have a registered implementation"),
+            );
+        }
+    }
+    linter.finish()
+}
+
+impl Project {
+    /// runs the lint pass over the project. See [`check`]
+    pub fn lint(&self, config: &LintConfig) -> LintReport {
+        check(self, config)
+    }
+}
+
+struct Linter<'a> {
+    project: &'a Project,
+    config: &'a LintConfig,
+    imports: HashMap<&'a FileName, ImportMap>,
+    warnings: Vec<LintWarning>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(project: &'a Project, config: &'a LintConfig) -> Self {
+        // the broken imports are reported by the type checking
+        let imports = project
+            .files
+            .iter()
+            .map(|(name, file)| (name, ImportMap::build(file).unwrap_or_default()))
+            .collect();
+        Linter {
+            project,
+            config,
+            imports,
+            warnings: vec![],
+        }
+    }
+
+    fn finish(self) -> LintReport {
+        let mut report = LintReport {
+            warnings: self.warnings,
+        };
+        report.sort();
+        report
+    }
+
+    fn warn(&mut self, lint: Lint, file: &File, span: Span, message: String) {
+        let level = self.config.level(&lint);
+        if level == Level::Allow {
+            return;
+        }
+        let LineCol { line, col } = LineCol::from_offset(file.text.as_str(), span.start);
+        self.warnings.push(LintWarning {
+            lint,
+            level,
+            message,
+            file: file.name.clone(),
+            span,
+            line,
+            col,
+        });
+    }
+
+    /// the files of the project apart from the libraries and the modules
+    fn own_files(&self) -> Vec<&'a File> {
+        self.project
+            .files
+            .values()
+            .filter(|f| !f.name.contains("::"))
+            .sorted_by_key(|f| f.name.clone())
+            .collect()
+    }
+
+    /// finds the definition the call refers to in the same way the runtime tree does
+    fn resolve(&self, file: &File, key: &TreeName) -> Option<Def> {
+        resolve(self.project, file, self.imports.get(&file.name)?, key)
+    }
+
+    fn unused_definitions(&mut self) {
+        let reached = CallGraph::build(self.project).reachable(&self.project.main);
+        let (_, main) = &self.project.main;
+        for file in self.own_files() {
+            for tree in file.definitions.values().sorted_by_key(|t| t.span.start) {
+                if !tree.is_root() && !reached.contains(&(file.name.clone(), tree.name.clone())) {
+                    self.warn(
+                        Lint::UnusedDefinition,
+                        file,
+                        tree.span,
+                        format!(
+                            "the tree {} is never invoked from the root {main}",
+                            tree.name
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn unused_imports(&mut self, file: &File) {
+        let mut refs: HashSet<Key> = HashSet::new();
+        for tree in file.definitions.values() {
+            refs.extend(references(&tree.calls));
+            refs.extend(tree.params.params.iter().filter_map(|p| type_name(&p.tpe)));
+        }
+        for def in file.structs.values() {
+            refs.extend(def.fields.iter().filter_map(|f| type_name(&f.tpe)));
+        }
+        let local = |name: &str| declares(file, name);
+        let uses = |name: &str| {
+            let prefix = format!("{name}::");
+            refs.iter().any(|r| r == name || r.starts_with(&prefix))
+        };
+
+        for (imported, items) in file.imports.iter().sorted_by_key(|(f, _)| *f) {
+            let span = file.import_spans.get(imported).copied().unwrap_or_default();
+            let defines = |name: &str| {
+                self.project
+                    .files
+                    .get(imported)
+                    .is_some_and(|f| declares(f, name))
+            };
+            // the qualified names of the types are replaced with the declarations when the project is built
+            let uses_file = || {
+                refs.iter().any(|r| {
+                    let head = r.split("::").next().unwrap_or(r);
+                    !local(r) && (defines(r) || defines(head))
+                })
+            };
+            for item in items.iter().sorted_by_key(|i| format!("{i:?}")) {
+                let unused = match item {
+                    ImportName::Id(v) if !uses(v) => Some(format!(
+                        "the name {v} imported from {imported} is never used"
+                    )),
+                    ImportName::Alias(id, alias) if !uses(alias) => Some(format!(
+                        "the alias {alias} for {id} imported from {imported} is never used"
+                    )),
+                    ImportName::Namespace(ns) if !uses(ns) && !uses_file() => Some(format!(
+                        "the namespace {ns} for the file {imported} is never used"
+                    )),
+                    ImportName::WholeFile if !uses_file() => Some(format!(
+                        "the file {imported} is imported but none of its definitions is used"
+                    )),
+                    _ => None,
+                };
+                if let Some(message) = unused {
+                    self.warn(Lint::UnusedImport, file, span, message);
+                }
+            }
+        }
+    }
+
+    fn shadowed_aliases(&mut self, file: &File) {
+        let whole_files: Vec<&FileName> = file
+            .imports
+            .iter()
+            .filter(|(_, items)| items.contains(&ImportName::WholeFile))
+            .map(|(f, _)| f)
+            .sorted()
+            .collect();
+        for (imported, items) in file.imports.iter().sorted_by_key(|(f, _)| *f) {
+            let span = file.import_spans.get(imported).copied().unwrap_or_default();
+            for item in items.iter().sorted_by_key(|i| format!("{i:?}")) {
+                let ImportName::Alias(id, alias) = item else {
+                    continue;
+                };
+                let shadowed = if file.definitions.contains_key(alias) {
+                    Some(format!(
+                        "the alias {alias} for {id} imported from {imported} is shadowed by the definition {alias} of the file"
+                    ))
+                } else {
+                    whole_files
+                        .iter()
+                        .filter(|f| **f != imported)
+                        .find(|f| {
+                            self.project
+                                .files
+                                .get(**f)
+                                .is_some_and(|f| f.definitions.contains_key(alias))
+                        })
+                        .map(|f| {
+                            format!(
+                                "the alias {alias} for {id} imported from {imported} shadows the definition {alias} of the file {f} imported entirely"
+                            )
+                        })
+                };
+                if let Some(message) = shadowed {
+                    self.warn(Lint::ShadowedAlias, file, span, message);
+                }
+            }
+        }
+    }
+
+    fn unused_params(&mut self, file: &File, tree: &Tree) {
+        if tree.tpe.is_action() {
+            return;
+        }
+        let refs = references(&tree.calls);
+        for p in tree.params.params.iter() {
+            if !refs.contains(&p.name) {
+                self.warn(
+                    Lint::UnusedParam,
+                    file,
+                    tree.span,
+                    format!(
+                        "the parameter {} of the tree {} is never used",
+                        p.name, tree.name
+                    ),
+                );
+            }
+        }
+    }
+
+    fn unreachable_children(&mut self, file: &File, tree: &Tree) {
+        let mut fallbacks = vec![];
+        if is_fallback(&tree.tpe) {
+            fallbacks.push(&tree.calls.elems);
+        }
+        for call in tree.calls.flatten() {
+            if let Call::Lambda(tpe, _, calls, _) = call {
+                if is_fallback(tpe) {
+                    fallbacks.push(&calls.elems);
+                }
+            }
+        }
+        for children in fallbacks {
+            let success = children
+                .iter()
+                .enumerate()
+                .find_map(|(idx, call)| match call {
+                    Call::Invocation(key, ..)
+                        if self
+                            .resolve(file, key)
+                            .is_some_and(|(f, name)| f == "std::actions" && name == "success") =>
+                    {
+                        Some((idx, key))
+                    }
+                    _ => None,
+                });
+            if let Some((next, key)) =
+                success.and_then(|(idx, key)| Some((children.get(idx + 1)?, key)))
+            {
+                self.warn(
+                    Lint::UnreachableChild,
+                    file,
+                    next.span(),
+                    format!("the children of the fallback after {key}() are never ticked since it always succeeds"),
+                );
+            }
+        }
+    }
+}
+
+fn is_fallback(tpe: &TreeType) -> bool {
+    matches!(tpe, TreeType::Fallback | TreeType::RFallback)
+}
+
+/// the file declares the tree, the type or the constant with the given name
+fn declares(file: &File, name: &str) -> bool {
+    file.definitions.contains_key(name)
+        || file.structs.contains_key(name)
+        || file.enums.contains_key(name)
+        || file.consts.contains_key(name)
+}
+
+/// the name of the struct or the enum the type refers to
+fn type_name(tpe: &MesType) -> Option<Key> {
+    match tpe {
+        MesType::Named(name) | MesType::Struct(name, _) | MesType::Enum(name, _) => {
+            Some(name.clone())
+        }
+        MesType::ArrayOf(tpe) => type_name(tpe),
+        _ => None,
+    }
+}
+
+/// the names the calls refer to: the invoked trees, the pointers, the parameters and the constants
+fn references(calls: &Calls) -> HashSet<Key> {
+    let mut refs = HashSet::new();
+    for call in calls.flatten() {
+        if let Some(key) = call.key() {
+            refs.insert(key);
+        }
+        if let Call::Invocation(_, args, _)
+        | Call::Decorator(_, args, ..)
+        | Call::Lambda(_, args, ..) = call
+        {
+            for arg in args.args.iter() {
+                match arg.value() {
+                    ArgumentRhs::Id(id) => {
+                        refs.insert(id.clone());
+                    }
+                    ArgumentRhs::Expr(e) => refs.extend(e.ids()),
+                    ArgumentRhs::Mes(_) | ArgumentRhs::Call(_) => {}
+                }
+            }
+        }
+    }
+    refs
+}
@@ -0,0 +1,605 @@
+//! The static type checking of the project.
+//!
+//! The pass goes over every definition of every file and checks:
+//!  - the calls can be resolved and the arguments correspond to the parameters
+//!  - the required parameters get the arguments and the default values have the types of the parameters
+//!  - the types of the literals, expressions and parameters passed down
+//!  - the pointers to bb against the types they are written with by the `out` parameters
+//!  - the `tree` parameters get the calls and the higher order invocations refer to them
+//!  - the arguments of the decorators
+//!  - the constants and the variants of the enums the arguments refer to
+//!
+//! All the errors are collected rather than the first one
+//! and every error points at the place in the file.
+use crate::tree::diagnostic::{Location, Span};
+use crate::tree::parser::ast::arg::{
+    Argument, ArgumentRhs, Arguments, ArgumentsType, MesType, Param, Params,
+};
+use crate::tree::parser::ast::call::Call;
+use crate::tree::parser::ast::expr::Expr;
+use crate::tree::parser::ast::message::Message;
+use crate::tree::parser::ast::{Key, Tree, TreeType};
+use crate::tree::printer;
+use crate::tree::project::file::File;
+use crate::tree::project::imports::ImportMap;
+use crate::tree::project::{FileName, Project, TreeName};
+use crate::tree::{cerr, TreeError};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+/// checks the whole project and returns all found errors ordered by the place in the files.
+pub fn check(project: &Project) -> Vec<TreeError> {
+    let mut checker = Checker::new(project);
+    let files: Vec<(&File, ImportMap)> = project
+        .files
+        .values()
+        .sorted_by_key(|f| f.name.clone())
+        .map(|file| match ImportMap::build(file) {
+            Ok(imports) => (file, imports),
+            Err(e) => {
+                checker.errors.push(e);
+                (file, ImportMap::default())
+            }
+        })
+        .collect();
+    let scopes = || {
+        files.iter().flat_map(|(file, imports)| {
+            file.definitions
+                .values()
+                .sorted_by_key(|t| t.span.start)
+                .map(move |tree| Scope {
+                    file,
+                    imports,
+                    tree,
+                })
+        })
+    };
+
+    // the parameters passed further to the output parameters become the output parameters as well
+    // thus the chains of them get unfolded until nothing changes.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for scope in scopes() {
+            for call in scope.tree.calls.elems.iter() {
+                changed |= checker.infer_outs(&scope, call);
+            }
+        }
+    }
+    for scope in scopes() {
+        checker.check_defaults(&scope);
+        for call in scope.tree.calls.elems.iter() {
+            checker.check_call(&scope, call);
+        }
+    }
+    checker.finish()
+}
+
+impl Project {
+    /// runs the static type checking over the project.
+    /// Several errors are combined into [`TreeError::Multiple`]
+    pub fn typecheck(&self) -> Result<(), TreeError> {
+        match TreeError::from_errors(check(self)) {
+            None => Ok(()),
+            Some(e) => Err(e),
+        }
+    }
+}
+
+/// the definition the calls are written in
+struct Scope<'s, 'a> {
+    file: &'a File,
+    imports: &'s ImportMap,
+    tree: &'a Tree,
+}
+
+impl<'s, 'a: 's> Scope<'s, 'a> {
+    fn param(&self, key: &Key) -> Option<&'a Param> {
+        self.tree.params.params.iter().find(|p| &p.name == key)
+    }
+
+    /// the value of the constant or the variant of the enum the id refers to
+    fn constant(&self, project: &Project, key: &Key) -> Result<Option<Message>, TreeError> {
+        project.find_const(self.file, self.imports, key)
+    }
+
+    /// finds the definition in the file or in the imports along with the file it is defined in
+    fn resolve(
+        &self,
+        project: &'a Project,
+        name: &TreeName,
+    ) -> Result<Option<(&'s FileName, &'s Tree)>, TreeError> {
+        match self.file.definitions.get(name) {
+            Some(tree) => Ok(Some((&self.file.name, tree))),
+            None => Ok(self
+                .imports
+                .lookup(name, project)?
+                .map(|(tree, file)| (file, tree))),
+        }
+    }
+}
+
+/// the pointer to bb written by an output parameter
+struct Write {
+    tpe: MesType,
+    tree: TreeName,
+    location: Location,
+}
+
+/// the value that can be checked only when all the pointers are known
+struct Read<'a> {
+    file: &'a File,
+    span: Span,
+    params: &'a Params,
+    expr: Expr,
+    expected: MesType,
+    target: String,
+}
+
+struct Checker<'a> {
+    project: &'a Project,
+    /// the parameters that are written to including the ones passed further to the output parameters
+    outs: HashMap<(FileName, TreeName), HashMap<Key, MesType>>,
+    writes: HashMap<Key, Write>,
+    reads: Vec<Read<'a>>,
+    errors: Vec<TreeError>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(project: &'a Project) -> Self {
+        Checker {
+            project,
+            outs: HashMap::new(),
+            writes: HashMap::new(),
+            reads: vec![],
+            errors: vec![],
+        }
+    }
+
+    fn error(&mut self, file: &File, err: TreeError, span: Span) {
+        self.errors.push(file.locate(err, span));
+    }
+
+    /// the type the parameter of the definition writes with if the parameter is an output one
+    fn out_type(&self, file: &FileName, tree: &Tree, param: &Param) -> Option<MesType> {
+        if param.dir.is_out() {
+            Some(param.tpe.clone())
+        } else {
+            self.outs
+                .get(&(file.clone(), tree.name.clone()))
+                .and_then(|ps| ps.get(&param.name))
+                .cloned()
+        }
+    }
+
+    /// marks the parameters of the enclosing tree that are passed to the output parameters.
+    /// Returns true if something new is found.
+    fn infer_outs(&mut self, scope: &Scope<'_, 'a>, call: &'a Call) -> bool {
+        match call {
+            Call::Invocation(name, args, _) => {
+                let Ok(Some((file, tree))) = scope.resolve(self.project, name) else {
+                    return false;
+                };
+                let mut changed = false;
+                for (a, p) in pairs(args, &tree.params) {
+                    match a.value() {
+                        ArgumentRhs::Id(id) => {
+                            let (Some(own), Some(tpe)) =
+                                (scope.param(id), self.out_type(file, tree, p))
+                            else {
+                                continue;
+                            };
+                            let tpe = if own.tpe == MesType::Any {
+                                tpe
+                            } else {
+                                own.tpe.clone()
+                            };
+                            changed |= self
+                                .outs
+                                .entry((scope.file.name.clone(), scope.tree.name.clone()))
+                                .or_default()
+                                .insert(id.clone(), tpe)
+                                .is_none();
+                        }
+                        ArgumentRhs::Call(call) => changed |= self.infer_outs(scope, call),
+                        _ => {}
+                    }
+                }
+                changed
+            }
+            Call::HoInvocation(..) => false,
+            Call::Lambda(_, _, calls, _) => {
+                let mut changed = false;
+                for call in calls.elems.iter() {
+                    changed |= self.infer_outs(scope, call);
+                }
+                changed
+            }
+            Call::Decorator(_, _, child, _) => self.infer_outs(scope, child),
+        }
+    }
+
+    fn check_call(&mut self, scope: &Scope<'_, 'a>, call: &'a Call) {
+        match call {
+            Call::Invocation(name, args, span) => match scope.resolve(self.project, name) {
+                Ok(Some(def)) => self.check_args(scope, name, def, args, *span),
+                Ok(None) => self.error(
+                    scope.file,
+                    cerr(format!("the call {name} can not be found")),
+                    *span,
+                ),
+                Err(e) => self.error(scope.file, e, *span),
+            },
+            Call::HoInvocation(key, span) => {
+                if !scope.param(key).is_some_and(|p| p.tpe == MesType::Tree) {
+                    self.error(
+                        scope.file,
+                        cerr(format!(
+                            "the tree '{}' does not have the parameter '{key}' of the type tree to invoke",
+                            scope.tree.name
+                        )),
+                        *span,
+                    )
+                }
+            }
+            Call::Lambda(_, _, calls, _) => {
+                for call in calls.elems.iter() {
+                    self.check_call(scope, call);
+                }
+            }
+            Call::Decorator(tpe, args, child, span) => {
+                self.check_decorator(scope, tpe, args, *span);
+                self.check_call(scope, child);
+            }
+        }
+    }
+
+    /// the default values are the literals of the types of the input parameters
+    fn check_defaults(&mut self, scope: &Scope<'_, 'a>) {
+        let tree = scope.tree;
+        for p in tree.params.params.iter() {
+            let Some(default) = &p.default else { continue };
+            let error = if p.tpe == MesType::Tree || p.dir.is_out() {
+                cerr(format!(
+                    "the parameter '{}' of '{}' can not have a default value",
+                    p.name, tree.name
+                ))
+            } else if let Err(reason) = default.conform(&p.tpe) {
+                cerr(format!(
+                    "the parameter '{}' of '{}' has the wrong default value: {reason}",
+                    p.name, tree.name
+                ))
+            } else {
+                continue;
+            };
+            self.error(scope.file, error, tree.span)
+        }
+    }
+
+    fn check_args(
+        &mut self,
+        scope: &Scope<'_, 'a>,
+        name: &TreeName,
+        (file, tree): (&FileName, &Tree),
+        args: &'a Arguments,
+        span: Span,
+    ) {
+        let params = &tree.params;
+        let tpe = match args.get_type() {
+            Ok(tpe) => tpe,
+            Err(e) => return self.error(scope.file, e, span),
+        };
+        match tpe {
+            ArgumentsType::Unnamed if args.args.len() > params.params.len() => {
+                return self.error(
+                    scope.file,
+                    cerr(format!(
+                        "the call {name} expects {} arguments but got {}",
+                        params.params.len(),
+                        args.args.len()
+                    )),
+                    span,
+                )
+            }
+            ArgumentsType::Named => {
+                let mut seen = HashSet::new();
+                for a in args.args.iter() {
+                    let key = a.name().cloned().unwrap_or_default();
+                    if !seen.insert(key.clone()) {
+                        self.error(
+                            scope.file,
+                            cerr(format!("the argument {key} is passed to {name} twice")),
+                            a.span(),
+                        )
+                    } else if !params.params.iter().any(|p| p.name == key) {
+                        self.error(
+                            scope.file,
+                            cerr(format!(
+                                "the argument {a} does not correspond to the definition {name}"
+                            )),
+                            a.span(),
+                        )
+                    }
+                }
+            }
+            _ => {}
+        }
+        let given = pairs(args, params);
+        for p in params.params.iter() {
+            if !p.is_optional() && !given.iter().any(|(_, g)| g.name == p.name) {
+                self.error(
+                    scope.file,
+                    cerr(format!(
+                        "the call {name} misses the argument for the required parameter '{p}'"
+                    )),
+                    span,
+                )
+            }
+        }
+        for (a, p) in given {
+            let out = self.out_type(file, tree, p);
+            self.check_arg(scope, name, a, p, out);
+        }
+    }
+
+    /// checks the argument against the parameter
+    /// that is considered as an output one if the type it writes with is given.
+    fn check_arg(
+        &mut self,
+        scope: &Scope<'_, 'a>,
+        name: &TreeName,
+        arg: &'a Argument,
+        param: &Param,
+        out: Option<MesType>,
+    ) {
+        let target = format!("the parameter '{param}' of '{name}'");
+        let tpe = &param.tpe;
+        let span = arg.span();
+        let error = |m: String| cerr(format!("{target} expects {m} but got '{}'", arg.value()));
+        // the constants are checked as the literals they are inlined with
+        if let ArgumentRhs::Id(id) = arg.value() {
+            if scope.param(id).is_none() && *tpe != MesType::Tree {
+                match scope.constant(self.project, id) {
+                    Ok(Some(_)) if out.is_some() => {
+                        return self.error(
+                            scope.file,
+                            error("a pointer to bb to write to".to_string()),
+                            span,
+                        )
+                    }
+                    Ok(Some(m)) => return self.check_message(scope, &m, tpe, &target, span),
+                    Ok(None) => {}
+                    Err(e) => return self.error(scope.file, e, span),
+                }
+            }
+        }
+        match (arg.value(), out) {
+            (ArgumentRhs::Call(call), _) if *tpe == MesType::Tree => self.check_call(scope, call),
+            (ArgumentRhs::Id(id), _) if scope.param(id).is_some_and(|p| p.tpe == MesType::Tree) => {
+                if *tpe != MesType::Tree {
+                    self.error(scope.file, error(format!("{tpe}")), span)
+                }
+            }
+            _ if *tpe == MesType::Tree => self.error(scope.file, error("a tree".to_string()), span),
+            (ArgumentRhs::Id(id), Some(out)) => {
+                if scope.param(id).is_none() {
+                    self.write(scope.file, id, &out, name, span)
+                }
+            }
+            (_, Some(_)) => self.error(
+                scope.file,
+                error("a pointer to bb to write to".to_string()),
+                span,
+            ),
+            (ArgumentRhs::Call(_), _) => self.error(scope.file, error(format!("{tpe}")), span),
+            (ArgumentRhs::Mes(m), _) => self.check_message(scope, m, tpe, &target, span),
+            (ArgumentRhs::Id(id), _) => self.read(scope, Expr::Id(id.clone()), tpe, target, span),
+            (ArgumentRhs::Expr(e), _) => self.read(scope, e.clone(), tpe, target, span),
+        }
+    }
+
+    fn check_message(
+        &mut self,
+        scope: &Scope<'_, 'a>,
+        m: &Message,
+        tpe: &MesType,
+        target: &str,
+        span: Span,
+    ) {
+        match (m.conform(tpe), tpe) {
+            (Ok(()), _) => {}
+            (Err(reason), MesType::ArrayOf(_) | MesType::Struct(..) | MesType::Enum(..)) => self
+                .error(
+                    scope.file,
+                    cerr(format!("{target} expects {tpe} but {reason}")),
+                    span,
+                ),
+            (Err(_), _) => self.error(
+                scope.file,
+                cerr(format!(
+                    "{target} expects {tpe} but got '{}'",
+                    printer::message(m)
+                )),
+                span,
+            ),
+        }
+    }
+
+    /// the decorators have the fixed contract:
+    /// either no arguments or one number that can be passed with a pointer or a parameter.
+    fn check_decorator(
+        &mut self,
+        scope: &Scope<'_, 'a>,
+        tpe: &TreeType,
+        args: &'a Arguments,
+        span: Span,
+    ) {
+        match (tpe, args.args.as_slice()) {
+            (TreeType::Inverter | TreeType::ForceSuccess | TreeType::ForceFail, []) => {}
+            (TreeType::Inverter | TreeType::ForceSuccess | TreeType::ForceFail, _) => self.error(
+                scope.file,
+                cerr(format!("the decorator {tpe} does not have arguments")),
+                span,
+            ),
+            (_, [a]) => {
+                let target = format!("the decorator {tpe}");
+                match a.value() {
+                    ArgumentRhs::Mes(Message::Num(_)) => {}
+                    ArgumentRhs::Id(id)
+                        if !scope.param(id).is_some_and(|p| p.tpe == MesType::Tree) =>
+                    {
+                        self.read(scope, Expr::Id(id.clone()), &MesType::Num, target, a.span())
+                    }
+                    ArgumentRhs::Expr(e) => {
+                        self.read(scope, e.clone(), &MesType::Num, target, a.span())
+                    }
+                    v => self.error(
+                        scope.file,
+                        cerr(format!(
+                            "{target} expects a number or a pointer but got '{v}'"
+                        )),
+                        a.span(),
+                    ),
+                }
+            }
+            _ => self.error(
+                scope.file,
+                cerr(format!("the decorator {tpe} has only one argument")),
+                span,
+            ),
+        }
+    }
+
+    /// the constants of the expression are inlined at once
+    /// since the types of them are known.
+    fn read(
+        &mut self,
+        scope: &Scope<'_, 'a>,
+        expr: Expr,
+        tpe: &MesType,
+        target: String,
+        span: Span,
+    ) {
+        let inlined = expr.try_map_ids(&|id| {
+            let value = match scope.param(&id) {
+                Some(_) => None,
+                None => scope.constant(self.project, &id)?,
+            };
+            Ok(value.map(Expr::Mes).unwrap_or(Expr::Id(id)))
+        });
+        let expr = match inlined {
+            Ok(expr) => expr,
+            Err(e) => return self.error(scope.file, e, span),
+        };
+        self.reads.push(Read {
+            file: scope.file,
+            span,
+            params: &scope.tree.params,
+            expr,
+            expected: tpe.clone(),
+            target,
+        })
+    }
+
+    /// remembers the type of the pointer the output parameter writes to.
+    /// The pointers written with different types are reported.
+    fn write(&mut self, file: &File, key: &Key, tpe: &MesType, tree: &TreeName, span: Span) {
+        if *tpe == MesType::Any {
+            return;
+        }
+        match self.writes.get(key) {
+            Some(prev) if !prev.tpe.compatible(tpe) => {
+                let err = cerr(format!(
+                    "the pointer '{key}' is written as {tpe} by '{tree}' but it is written as {} by '{}'",
+                    prev.tpe, prev.tree
+                ))
+                .with_note(
+                    format!("'{key}' is written by '{}'", prev.tree),
+                    prev.location.clone(),
+                );
+                self.error(file, err, span)
+            }
+            Some(_) => {}
+            None => {
+                self.writes.insert(
+                    key.clone(),
+                    Write {
+                        tpe: tpe.clone(),
+                        tree: tree.clone(),
+                        location: file.location(span),
+                    },
+                );
+            }
+        }
+    }
+
+    /// the type of the parameter of the enclosing tree or the pointer written by an output parameter
+    fn id_type(&self, params: &Params, key: &Key) -> Option<MesType> {
+        match params.params.iter().find(|p| &p.name == key) {
+            Some(p) if p.tpe == MesType::Any || p.tpe == MesType::Tree => None,
+            Some(p) => Some(p.tpe.clone()),
+            None => self.writes.get(key).map(|w| w.tpe.clone()),
+        }
+    }
+
+    fn check_read(&self, read: &Read) -> Result<(), TreeError> {
+        let Read {
+            params,
+            expr,
+            expected,
+            target,
+            ..
+        } = read;
+        match expr.tpe_with(&|id| self.id_type(params, id))? {
+            Some(t) if !expected.compatible(&t) => {
+                let err = cerr(format!(
+                    "{target} expects {expected} but got '{expr}' of the type {t}"
+                ));
+                match (expr, self.writes.get(&expr.to_string())) {
+                    (Expr::Id(id), Some(w)) if !params.params.iter().any(|p| &p.name == id) => {
+                        Err(err.with_note(
+                            format!("'{id}' is written as {} by '{}'", w.tpe, w.tree),
+                            w.location.clone(),
+                        ))
+                    }
+                    _ => Err(err),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn finish(mut self) -> Vec<TreeError> {
+        let errors: Vec<TreeError> = self
+            .reads
+            .iter()
+            .flat_map(|r| self.check_read(r).err().map(|e| r.file.locate(e, r.span)))
+            .collect();
+        self.errors.extend(errors);
+        self.errors.sort_by_key(|e| {
+            e.diagnostic()
+                .map(|d| (d.location.file.clone(), d.location.span.map(|s| s.start)))
+        });
+        self.errors
+    }
+}
+
+/// the pairs of the arguments and the parameters they are passed to.
+/// The arguments that do not correspond to any parameter are skipped.
+fn pairs<'r, 'p>(args: &'r Arguments, params: &'p Params) -> Vec<(&'r Argument, &'p Param)> {
+    match args.get_type() {
+        Ok(ArgumentsType::Unnamed) => args.args.iter().zip(params.params.iter()).collect(),
+        Ok(ArgumentsType::Named) => args
+            .args
+            .iter()
+            .flat_map(|a| {
+                params
+                    .params
+                    .iter()
+                    .find(|p| a.name() == Some(&p.name))
+                    .map(|p| (a, p))
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
@@ -1,18 +1,20 @@
 mod statements;
 
 use crate::{runtime_tree_default};
+use crate::runtime::rtree::diff::Change;
 use crate::runtime::rtree::rnode::{RNode, RNodeId};
 use crate::runtime::rtree::RuntimeTree;
 
 
 use crate::tree::TreeError;
 use crate::visualizer::statements::ToStmt;
+use graphviz_rust::attributes::{color_name, NodeAttributes};
 use graphviz_rust::cmd::{CommandArg, Format};
 use graphviz_rust::dot_generator::*;
 use graphviz_rust::dot_structures::*;
 use graphviz_rust::printer::PrinterContext;
 use graphviz_rust::{exec, print};
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::path::PathBuf;
 
 /// The struct to visualize the given runtime tree to graphviz format.
@@ -50,6 +52,67 @@ impl Visualizer {
         Ok(graph)
     }
 
+    /// Draws the new tree highlighting the changes against the old one:
+    /// the added nodes are green, the re-parameterised ones are gold, the moved ones are blue
+    /// and the removed nodes of the old tree are pink and dashed.
+    fn build_diff_graph(old: &RuntimeTree, new: &RuntimeTree) -> Result<Graph, TreeError> {
+        let diff = old.diff(new);
+        let added = diff.added(new);
+        let removed = diff.removed(old);
+        let mut reparameterised = HashSet::new();
+        let mut moved = HashSet::new();
+        for change in diff.changes.iter() {
+            match change {
+                Change::Reparameterised { new, .. } => {
+                    reparameterised.insert(*new);
+                }
+                Change::Moved { new, .. } => {
+                    moved.insert(*new);
+                }
+                _ => {}
+            }
+        }
+
+        let mut graph = graph!(strict di id!(""));
+        for (id, node) in new.iter() {
+            let fill = if added.contains(&id) {
+                Some(color_name::palegreen)
+            } else if reparameterised.contains(&id) {
+                Some(color_name::gold)
+            } else if moved.contains(&id) {
+                Some(color_name::lightblue)
+            } else {
+                None
+            };
+            graph.add_stmt(highlight(node.to_stmt(id.to_string()), fill, "filled"));
+            for c in node.children() {
+                if new.nodes.contains_key(&c) {
+                    graph.add_stmt(stmt!(edge!(node_id!(id) => node_id!(c))));
+                }
+            }
+        }
+
+        let old_an = old.analyze();
+        for (id, node) in old.iter().filter(|(id, _)| removed.contains(id)) {
+            let removed_id = format!("r{id}");
+            graph.add_stmt(highlight(
+                node.to_stmt(removed_id.clone()),
+                Some(color_name::lightpink),
+                "\"filled,dashed\"",
+            ));
+            if let Some(parent) = old_an.parent(&id) {
+                let parent_id = match diff.matched.get(parent) {
+                    Some(p) => p.to_string(),
+                    None => format!("r{parent}"),
+                };
+                graph.add_stmt(stmt!(
+                    edge!(node_id!(parent_id) => node_id!(removed_id); attr!("style", "dashed"))
+                ));
+            }
+        }
+        Ok(graph)
+    }
+
     pub fn dot(runtime_tree: &RuntimeTree) -> Result<String, TreeError> {
         debug!(target:"visualizer","visualize a given tree ");
 
@@ -58,6 +121,15 @@ impl Visualizer {
             &mut PrinterContext::default(),
         ))
     }
+    /// The dot representation of the new tree with the changes against the old one highlighted.
+    pub fn diff_dot(old: &RuntimeTree, new: &RuntimeTree) -> Result<String, TreeError> {
+        debug!(target:"visualizer","visualize the difference between the given trees");
+
+        Ok(print(
+            Visualizer::build_diff_graph(old, new)?,
+            &mut PrinterContext::default(),
+        ))
+    }
     pub fn project_svg_to_file(
         root: PathBuf,
         file: Option<&String>,
@@ -88,6 +160,18 @@ impl Visualizer {
     }
 }
 
+fn highlight(stmt: Stmt, fill: Option<color_name>, style: &str) -> Stmt {
+    match (stmt, fill) {
+        (Stmt::Node(mut node), Some(color)) => {
+            node.attributes
+                .push(NodeAttributes::style(style.to_string()));
+            node.attributes.push(NodeAttributes::fillcolor(color));
+            Stmt::Node(node)
+        }
+        (stmt, _) => stmt,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::runtime::rtree::RuntimeTree;